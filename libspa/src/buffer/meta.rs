@@ -0,0 +1,292 @@
+// Copyright The pipewire-rs Contributors.
+// SPDX-License-Identifier: MIT
+
+//! Metadata attached to buffers.
+
+use std::{convert::TryFrom, fmt::Debug, mem};
+
+use crate::utils::{Point, Rectangle};
+
+#[derive(Copy, Clone, PartialEq, Eq)]
+pub struct MetaType(spa_sys::spa_meta_type);
+
+#[allow(non_upper_case_globals)]
+impl MetaType {
+    pub const Invalid: Self = Self(spa_sys::SPA_META_Invalid);
+    /// Header of the buffer, see [`MetaHeader`]
+    pub const Header: Self = Self(spa_sys::SPA_META_Header);
+    /// Cropping region of a video frame, see [`MetaRegion`]
+    pub const VideoCrop: Self = Self(spa_sys::SPA_META_VideoCrop);
+    /// Array of damaged regions of a video frame, see [`MetaRegion`]
+    pub const VideoDamage: Self = Self(spa_sys::SPA_META_VideoDamage);
+    /// A bitmap
+    pub const Bitmap: Self = Self(spa_sys::SPA_META_Bitmap);
+    /// A cursor, see [`MetaCursor`]
+    pub const Cursor: Self = Self(spa_sys::SPA_META_Cursor);
+    /// Metadata contains a control pod
+    pub const Control: Self = Self(spa_sys::SPA_META_Control);
+    /// Don't write to the buffer when the count is > 0
+    pub const Busy: Self = Self(spa_sys::SPA_META_Busy);
+
+    pub fn from_raw(raw: spa_sys::spa_meta_type) -> Self {
+        Self(raw)
+    }
+
+    pub fn as_raw(&self) -> spa_sys::spa_meta_type {
+        self.0
+    }
+}
+
+impl Debug for MetaType {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let name = format!(
+            "MetaType::{}",
+            match *self {
+                Self::Invalid => "Invalid",
+                Self::Header => "Header",
+                Self::VideoCrop => "VideoCrop",
+                Self::VideoDamage => "VideoDamage",
+                Self::Bitmap => "Bitmap",
+                Self::Cursor => "Cursor",
+                Self::Control => "Control",
+                Self::Busy => "Busy",
+                _ => "Unknown",
+            }
+        );
+        f.write_str(&name)
+    }
+}
+
+/// A metadata item attached to a buffer.
+///
+/// Use the typed accessors such as [`header`](Self::header) to interpret the metadata,
+/// or [`data`](Self::data) to access the raw bytes of metadata types which are not modeled.
+#[repr(transparent)]
+pub struct Meta(spa_sys::spa_meta);
+
+impl Meta {
+    pub fn as_raw(&self) -> &spa_sys::spa_meta {
+        &self.0
+    }
+
+    pub fn type_(&self) -> MetaType {
+        MetaType::from_raw(self.0.type_)
+    }
+
+    /// The size of the metadata, in bytes.
+    pub fn size(&self) -> u32 {
+        self.0.size
+    }
+
+    /// The raw bytes of the metadata.
+    pub fn data(&self) -> &[u8] {
+        if self.0.data.is_null() {
+            &[]
+        } else {
+            unsafe {
+                std::slice::from_raw_parts(
+                    self.0.data as *const u8,
+                    usize::try_from(self.0.size).unwrap(),
+                )
+            }
+        }
+    }
+
+    /// Interpret the metadata as `T`, if it is of type `type_` and large enough.
+    fn cast<T>(&self, type_: MetaType) -> Option<&T> {
+        if self.type_() != type_
+            || self.0.data.is_null()
+            || usize::try_from(self.0.size).unwrap() < mem::size_of::<T>()
+        {
+            None
+        } else {
+            unsafe { Some(&*(self.0.data as *const T)) }
+        }
+    }
+
+    /// Interpret the metadata as a [`MetaHeader`], if it is of type [`MetaType::Header`].
+    pub fn header(&self) -> Option<&MetaHeader> {
+        self.cast(MetaType::Header)
+    }
+
+    /// Interpret the metadata as a [`MetaRegion`], if it is of type [`MetaType::VideoCrop`].
+    pub fn video_crop(&self) -> Option<&MetaRegion> {
+        self.cast(MetaType::VideoCrop)
+    }
+
+    /// Interpret the metadata as an array of [`MetaRegion`], if it is of type [`MetaType::VideoDamage`].
+    ///
+    /// The array is terminated by the first region with an empty size, which is not included.
+    pub fn video_damage(&self) -> Option<impl Iterator<Item = &MetaRegion>> {
+        if self.type_() != MetaType::VideoDamage || self.0.data.is_null() {
+            return None;
+        }
+
+        let regions = unsafe {
+            std::slice::from_raw_parts(
+                self.0.data as *const MetaRegion,
+                usize::try_from(self.0.size).unwrap() / mem::size_of::<MetaRegion>(),
+            )
+        };
+
+        Some(regions.iter().take_while(|region| region.is_valid()))
+    }
+
+    /// Interpret the metadata as a [`MetaCursor`], if it is of type [`MetaType::Cursor`].
+    pub fn cursor(&self) -> Option<&MetaCursor> {
+        self.cast(MetaType::Cursor)
+    }
+}
+
+impl Debug for Meta {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("Meta")
+            .field("type", &self.type_())
+            .field("size", &self.size())
+            .finish()
+    }
+}
+
+bitflags::bitflags! {
+    #[derive(Debug, PartialEq, Eq, Clone, Copy)]
+    pub struct MetaHeaderFlags: u32 {
+        /// Data is not continuous with previous buffer
+        const DISCONT = spa_sys::SPA_META_HEADER_FLAG_DISCONT;
+        /// Data might be corrupted
+        const CORRUPTED = spa_sys::SPA_META_HEADER_FLAG_CORRUPTED;
+        /// Media specific marker
+        const MARKER = spa_sys::SPA_META_HEADER_FLAG_MARKER;
+        /// Data contains a codec specific header
+        const HEADER = spa_sys::SPA_META_HEADER_FLAG_HEADER;
+        /// Data contains media neutral data
+        const GAP = spa_sys::SPA_META_HEADER_FLAG_GAP;
+        /// Cannot be decoded independently
+        const DELTA_UNIT = spa_sys::SPA_META_HEADER_FLAG_DELTA_UNIT;
+    }
+}
+
+/// Describes essential buffer header metadata such as flags and timestamps.
+#[repr(transparent)]
+pub struct MetaHeader(spa_sys::spa_meta_header);
+
+impl MetaHeader {
+    pub fn as_raw(&self) -> &spa_sys::spa_meta_header {
+        &self.0
+    }
+
+    pub fn flags(&self) -> MetaHeaderFlags {
+        MetaHeaderFlags::from_bits_retain(self.0.flags)
+    }
+
+    /// Offset in current substream
+    pub fn offset(&self) -> u32 {
+        self.0.offset
+    }
+
+    /// Presentation timestamp in nanoseconds
+    pub fn pts(&self) -> i64 {
+        self.0.pts
+    }
+
+    /// Decoding timestamp as a difference with pts
+    pub fn dts_offset(&self) -> i64 {
+        self.0.dts_offset
+    }
+
+    /// Sequence number, increments with a media specific frequency
+    pub fn seq(&self) -> u64 {
+        self.0.seq
+    }
+}
+
+impl Debug for MetaHeader {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("MetaHeader")
+            .field("flags", &self.flags())
+            .field("offset", &self.offset())
+            .field("pts", &self.pts())
+            .field("dts-offset", &self.dts_offset())
+            .field("seq", &self.seq())
+            .finish()
+    }
+}
+
+/// Metadata describing a region of a video frame, such as the cropping or damaged region.
+#[repr(transparent)]
+pub struct MetaRegion(spa_sys::spa_meta_region);
+
+impl MetaRegion {
+    pub fn as_raw(&self) -> &spa_sys::spa_meta_region {
+        &self.0
+    }
+
+    pub fn position(&self) -> Point {
+        Point::from_raw(self.0.region.position)
+    }
+
+    pub fn size(&self) -> Rectangle {
+        Rectangle::from_raw(self.0.region.size)
+    }
+
+    /// Returns `true` if the region has a non-empty size.
+    pub fn is_valid(&self) -> bool {
+        self.0.region.size.width != 0 && self.0.region.size.height != 0
+    }
+}
+
+impl Debug for MetaRegion {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("MetaRegion")
+            .field("position", &self.position())
+            .field("size", &self.size())
+            .finish()
+    }
+}
+
+/// Cursor information.
+#[repr(transparent)]
+pub struct MetaCursor(spa_sys::spa_meta_cursor);
+
+impl MetaCursor {
+    pub fn as_raw(&self) -> &spa_sys::spa_meta_cursor {
+        &self.0
+    }
+
+    /// Cursor id. An id of 0 is an invalid id and means that there is no new cursor data.
+    pub fn id(&self) -> u32 {
+        self.0.id
+    }
+
+    /// Extra flags
+    pub fn flags(&self) -> u32 {
+        self.0.flags
+    }
+
+    /// Position on screen
+    pub fn position(&self) -> Point {
+        Point::from_raw(self.0.position)
+    }
+
+    /// Offsets for hotspot in bitmap, this field has no meaning when there is no valid bitmap
+    pub fn hotspot(&self) -> Point {
+        Point::from_raw(self.0.hotspot)
+    }
+
+    /// Offset of the bitmap meta in this struct.
+    /// When the offset is 0, there is no new bitmap information.
+    pub fn bitmap_offset(&self) -> u32 {
+        self.0.bitmap_offset
+    }
+}
+
+impl Debug for MetaCursor {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("MetaCursor")
+            .field("id", &self.id())
+            .field("flags", &self.flags())
+            .field("position", &self.position())
+            .field("hotspot", &self.hotspot())
+            .field("bitmap-offset", &self.bitmap_offset())
+            .finish()
+    }
+}
@@ -1,7 +1,9 @@
 // Copyright The pipewire-rs Contributors.
 // SPDX-License-Identifier: MIT
 
-use std::{convert::TryFrom, fmt::Debug};
+pub mod meta;
+
+use std::{convert::TryFrom, fmt::Debug, os::fd::RawFd};
 
 #[derive(Copy, Clone, PartialEq, Eq)]
 pub struct DataType(spa_sys::spa_data_type);
@@ -73,8 +75,39 @@ impl Data {
         DataFlags::from_bits_retain(self.0.flags)
     }
 
-    // FIXME: Add bindings for the fd field, but how to detect when it is not set / invalid?
+    /// The file descriptor of the data, for fd based data types such as
+    /// [`DataType::MemFd`] and [`DataType::DmaBuf`].
+    ///
+    /// Returns `None` for other data types, or if the fd is not set.
+    ///
+    /// For [`DataType::DmaBuf`], each [`Data`] of a buffer is one plane of the frame, and the
+    /// layout of the plane is given by [`map_offset`](Self::map_offset) and the
+    /// [`offset`](Chunk::offset) and [`stride`](Chunk::stride) of the [`chunk`](Self::chunk).
+    /// A DMA-BUF fd is meant to be imported in a graphics stack and must not be `mmap`ed
+    /// and accessed as if it was regular memory, as this requires explicit synchronization with the device.
+    pub fn fd(&self) -> Option<RawFd> {
+        match self.type_() {
+            DataType::MemFd | DataType::DmaBuf if self.0.fd >= 0 => {
+                Some(RawFd::try_from(self.0.fd).unwrap())
+            }
+            _ => None,
+        }
+    }
+
+    /// The offset to map the fd at.
+    pub fn map_offset(&self) -> u32 {
+        self.0.mapoffset
+    }
+
+    /// The maximum size of the data, in bytes.
+    pub fn max_size(&self) -> u32 {
+        self.0.maxsize
+    }
 
+    /// Get the data as a CPU accessible slice.
+    ///
+    /// This is only available if the data is memory mapped, which is never the case for
+    /// [`DataType::DmaBuf`]: use [`fd`](Self::fd) to access it instead.
     pub fn data(&mut self) -> Option<&mut [u8]> {
         // FIXME: For safety, perhaps only return a non-mut slice when DataFlags::WRITABLE is not set?
         if self.0.data.is_null() {
@@ -111,7 +144,7 @@ impl Debug for Data {
         f.debug_struct("Data")
             .field("type", &self.type_())
             .field("flags", &self.flags())
-            // FIXME: Add fd
+            .field("fd", &self.fd())
             .field("data", &self.0.data) // Only print the pointer here, as we don't want to print a (potentially very big) slice.
             .field("chunk", &self.chunk())
             .finish()
@@ -0,0 +1,306 @@
+// Copyright The pipewire-rs Contributors.
+// SPDX-License-Identifier: MIT
+
+use std::{fmt, str::FromStr};
+
+use nix::errno::Errno;
+
+use crate::{
+    pod::{deserialize::PodDeserializer, Value, ValueArray},
+    utils::SpaTypes,
+};
+
+/// The position of an audio channel, as used in the `audio.channel` property of ports,
+/// the `audio.position` property of nodes and the channel maps of params.
+///
+/// Positions are displayed and parsed using their short SPA name, such as `FL` or `AUX3`.
+#[repr(transparent)]
+#[derive(PartialEq, Eq, PartialOrd, Ord, Hash, Clone, Copy)]
+pub struct ChannelPosition(pub spa_sys::spa_audio_channel);
+
+#[allow(non_upper_case_globals)]
+impl ChannelPosition {
+    /// unspecified
+    pub const Unknown: Self = Self(spa_sys::SPA_AUDIO_CHANNEL_UNKNOWN);
+    /// N/A, silent
+    pub const NA: Self = Self(spa_sys::SPA_AUDIO_CHANNEL_NA);
+    /// mono stream
+    pub const MONO: Self = Self(spa_sys::SPA_AUDIO_CHANNEL_MONO);
+    /// front left
+    pub const FL: Self = Self(spa_sys::SPA_AUDIO_CHANNEL_FL);
+    /// front right
+    pub const FR: Self = Self(spa_sys::SPA_AUDIO_CHANNEL_FR);
+    /// front center
+    pub const FC: Self = Self(spa_sys::SPA_AUDIO_CHANNEL_FC);
+    /// low frequency effects
+    pub const LFE: Self = Self(spa_sys::SPA_AUDIO_CHANNEL_LFE);
+    /// side left
+    pub const SL: Self = Self(spa_sys::SPA_AUDIO_CHANNEL_SL);
+    /// side right
+    pub const SR: Self = Self(spa_sys::SPA_AUDIO_CHANNEL_SR);
+    /// front left center
+    pub const FLC: Self = Self(spa_sys::SPA_AUDIO_CHANNEL_FLC);
+    /// front right center
+    pub const FRC: Self = Self(spa_sys::SPA_AUDIO_CHANNEL_FRC);
+    /// rear center
+    pub const RC: Self = Self(spa_sys::SPA_AUDIO_CHANNEL_RC);
+    /// rear left
+    pub const RL: Self = Self(spa_sys::SPA_AUDIO_CHANNEL_RL);
+    /// rear right
+    pub const RR: Self = Self(spa_sys::SPA_AUDIO_CHANNEL_RR);
+    /// top center
+    pub const TC: Self = Self(spa_sys::SPA_AUDIO_CHANNEL_TC);
+    /// top front left
+    pub const TFL: Self = Self(spa_sys::SPA_AUDIO_CHANNEL_TFL);
+    /// top front center
+    pub const TFC: Self = Self(spa_sys::SPA_AUDIO_CHANNEL_TFC);
+    /// top front right
+    pub const TFR: Self = Self(spa_sys::SPA_AUDIO_CHANNEL_TFR);
+    /// top rear left
+    pub const TRL: Self = Self(spa_sys::SPA_AUDIO_CHANNEL_TRL);
+    /// top rear center
+    pub const TRC: Self = Self(spa_sys::SPA_AUDIO_CHANNEL_TRC);
+    /// top rear right
+    pub const TRR: Self = Self(spa_sys::SPA_AUDIO_CHANNEL_TRR);
+    /// rear left center
+    pub const RLC: Self = Self(spa_sys::SPA_AUDIO_CHANNEL_RLC);
+    /// rear right center
+    pub const RRC: Self = Self(spa_sys::SPA_AUDIO_CHANNEL_RRC);
+    /// front left wide
+    pub const FLW: Self = Self(spa_sys::SPA_AUDIO_CHANNEL_FLW);
+    /// front right wide
+    pub const FRW: Self = Self(spa_sys::SPA_AUDIO_CHANNEL_FRW);
+    /// low frequency effects 2
+    pub const LFE2: Self = Self(spa_sys::SPA_AUDIO_CHANNEL_LFE2);
+    /// front left high
+    pub const FLH: Self = Self(spa_sys::SPA_AUDIO_CHANNEL_FLH);
+    /// front center high
+    pub const FCH: Self = Self(spa_sys::SPA_AUDIO_CHANNEL_FCH);
+    /// front right high
+    pub const FRH: Self = Self(spa_sys::SPA_AUDIO_CHANNEL_FRH);
+    /// top front left center
+    pub const TFLC: Self = Self(spa_sys::SPA_AUDIO_CHANNEL_TFLC);
+    /// top front right center
+    pub const TFRC: Self = Self(spa_sys::SPA_AUDIO_CHANNEL_TFRC);
+    /// top side left
+    pub const TSL: Self = Self(spa_sys::SPA_AUDIO_CHANNEL_TSL);
+    /// top side right
+    pub const TSR: Self = Self(spa_sys::SPA_AUDIO_CHANNEL_TSR);
+    /// left low frequency effects
+    pub const LLFE: Self = Self(spa_sys::SPA_AUDIO_CHANNEL_LLFE);
+    /// right low frequency effects
+    pub const RLFE: Self = Self(spa_sys::SPA_AUDIO_CHANNEL_RLFE);
+    /// bottom center
+    pub const BC: Self = Self(spa_sys::SPA_AUDIO_CHANNEL_BC);
+    /// bottom left center
+    pub const BLC: Self = Self(spa_sys::SPA_AUDIO_CHANNEL_BLC);
+    /// bottom right center
+    pub const BRC: Self = Self(spa_sys::SPA_AUDIO_CHANNEL_BRC);
+
+    const NAMED: [(Self, &'static str); 38] = [
+        (Self::Unknown, "UNK"),
+        (Self::NA, "NA"),
+        (Self::MONO, "MONO"),
+        (Self::FL, "FL"),
+        (Self::FR, "FR"),
+        (Self::FC, "FC"),
+        (Self::LFE, "LFE"),
+        (Self::SL, "SL"),
+        (Self::SR, "SR"),
+        (Self::FLC, "FLC"),
+        (Self::FRC, "FRC"),
+        (Self::RC, "RC"),
+        (Self::RL, "RL"),
+        (Self::RR, "RR"),
+        (Self::TC, "TC"),
+        (Self::TFL, "TFL"),
+        (Self::TFC, "TFC"),
+        (Self::TFR, "TFR"),
+        (Self::TRL, "TRL"),
+        (Self::TRC, "TRC"),
+        (Self::TRR, "TRR"),
+        (Self::RLC, "RLC"),
+        (Self::RRC, "RRC"),
+        (Self::FLW, "FLW"),
+        (Self::FRW, "FRW"),
+        (Self::LFE2, "LFE2"),
+        (Self::FLH, "FLH"),
+        (Self::FCH, "FCH"),
+        (Self::FRH, "FRH"),
+        (Self::TFLC, "TFLC"),
+        (Self::TFRC, "TFRC"),
+        (Self::TSL, "TSL"),
+        (Self::TSR, "TSR"),
+        (Self::LLFE, "LLFE"),
+        (Self::RLFE, "RLFE"),
+        (Self::BC, "BC"),
+        (Self::BLC, "BLC"),
+        (Self::BRC, "BRC"),
+    ];
+
+    /// Get the position of the auxiliary channel `index`, displayed as `AUX<index>`.
+    ///
+    /// Returns `None` if `index` is out of the range of auxiliary channels.
+    pub fn aux(index: u32) -> Option<Self> {
+        let raw = spa_sys::SPA_AUDIO_CHANNEL_START_Aux.checked_add(index)?;
+        (raw <= spa_sys::SPA_AUDIO_CHANNEL_LAST_Aux).then_some(Self(raw))
+    }
+
+    /// The index of the channel if it is an auxiliary channel, see [`aux`](Self::aux).
+    pub fn aux_index(&self) -> Option<u32> {
+        (spa_sys::SPA_AUDIO_CHANNEL_START_Aux..=spa_sys::SPA_AUDIO_CHANNEL_LAST_Aux)
+            .contains(&self.0)
+            .then(|| self.0 - spa_sys::SPA_AUDIO_CHANNEL_START_Aux)
+    }
+
+    /// Get the short SPA name of the position, or `None` for auxiliary and custom positions.
+    pub fn name(&self) -> Option<&'static str> {
+        Self::NAMED
+            .iter()
+            .find(|(position, _)| position == self)
+            .map(|(_, name)| *name)
+    }
+
+    /// Parse a list of positions, as used in the `audio.position` property of nodes,
+    /// such as `FL,FR` or `[ FL FR ]`.
+    ///
+    /// Returns [`Errno::EINVAL`] if any of the positions is unknown.
+    pub fn parse_list(list: &str) -> Result<Vec<Self>, Errno> {
+        list.trim()
+            .trim_start_matches('[')
+            .trim_end_matches(']')
+            .split(|c: char| c == ',' || c.is_whitespace())
+            .filter(|position| !position.is_empty())
+            .map(str::parse)
+            .collect()
+    }
+
+    /// Obtain a [`ChannelPosition`] from a raw `spa_audio_channel` variant.
+    pub fn from_raw(raw: spa_sys::spa_audio_channel) -> Self {
+        Self(raw)
+    }
+
+    /// Get the raw [`spa_sys::spa_audio_channel`] representing this `ChannelPosition`.
+    pub fn as_raw(&self) -> spa_sys::spa_audio_channel {
+        self.0
+    }
+}
+
+impl fmt::Display for ChannelPosition {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match (self.name(), self.aux_index()) {
+            (Some(name), _) => f.write_str(name),
+            (None, Some(index)) => write!(f, "AUX{}", index),
+            (None, None) => f.write_str("UNK"),
+        }
+    }
+}
+
+impl fmt::Debug for ChannelPosition {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "ChannelPosition::{}", self)
+    }
+}
+
+impl FromStr for ChannelPosition {
+    type Err = Errno;
+
+    /// Parse a position from its short SPA name, such as `FL` or `AUX3`.
+    ///
+    /// Returns [`Errno::EINVAL`] if the name is unknown.
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        if let Some((position, _)) = Self::NAMED.iter().find(|(_, name)| *name == s) {
+            return Ok(*position);
+        }
+
+        s.strip_prefix("AUX")
+            .and_then(|index| index.parse().ok())
+            .and_then(Self::aux)
+            .ok_or(Errno::EINVAL)
+    }
+}
+
+/// Parse the channel map of a param, either the `channelMap` of a `Props` object,
+/// or the `position` of a `Format` object.
+///
+/// Returns [`Errno::EINVAL`] if the pod is neither a `Props` nor a `Format` object,
+/// and [`Errno::ENOENT`] if it does not have a channel map.
+pub fn parse_channel_map(pod: &crate::pod::Pod) -> Result<Vec<ChannelPosition>, Errno> {
+    let object = match PodDeserializer::deserialize_any_from(pod.as_bytes()) {
+        Ok((_, Value::Object(object))) => object,
+        _ => return Err(Errno::EINVAL),
+    };
+
+    let key = if object.type_ == SpaTypes::ObjectParamProps.as_raw() {
+        spa_sys::SPA_PROP_channelMap
+    } else if object.type_ == SpaTypes::ObjectParamFormat.as_raw() {
+        spa_sys::SPA_FORMAT_AUDIO_position
+    } else {
+        return Err(Errno::EINVAL);
+    };
+
+    object
+        .properties
+        .iter()
+        .find(|prop| prop.key == key)
+        .and_then(|prop| match &prop.value {
+            Value::ValueArray(ValueArray::Id(positions)) => Some(
+                positions
+                    .iter()
+                    .map(|id| ChannelPosition::from_raw(id.0))
+                    .collect(),
+            ),
+            _ => None,
+        })
+        .ok_or(Errno::ENOENT)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::param::audio::PropsBuilder;
+
+    #[test]
+    fn display_and_parse() {
+        assert_eq!(ChannelPosition::FL.to_string(), "FL");
+        assert_eq!("MONO".parse(), Ok(ChannelPosition::MONO));
+        assert_eq!(ChannelPosition::aux(3).unwrap().to_string(), "AUX3");
+        assert_eq!("AUX3".parse(), Ok(ChannelPosition::aux(3).unwrap()));
+        assert_eq!("XX".parse::<ChannelPosition>(), Err(Errno::EINVAL));
+        assert_eq!(ChannelPosition::aux(0x10000), None);
+        assert_eq!(
+            format!("{:?}", ChannelPosition::RLFE),
+            "ChannelPosition::RLFE"
+        );
+
+        for (position, name) in ChannelPosition::NAMED {
+            assert_eq!(name.parse(), Ok(position));
+        }
+    }
+
+    #[test]
+    fn parse_list() {
+        let front = vec![ChannelPosition::FL, ChannelPosition::FR];
+        assert_eq!(ChannelPosition::parse_list("FL,FR"), Ok(front.clone()));
+        assert_eq!(ChannelPosition::parse_list("[ FL FR ]"), Ok(front));
+        assert_eq!(ChannelPosition::parse_list(""), Ok(vec![]));
+        assert_eq!(ChannelPosition::parse_list("FL,XX"), Err(Errno::EINVAL));
+    }
+
+    #[test]
+    fn channel_map() {
+        let bytes = PropsBuilder::new()
+            .channel_map(&[spa_sys::SPA_AUDIO_CHANNEL_FL, spa_sys::SPA_AUDIO_CHANNEL_FR])
+            .to_pod_bytes()
+            .unwrap();
+        let pod = crate::pod::Pod::from_bytes(&bytes).unwrap();
+        assert_eq!(
+            parse_channel_map(pod),
+            Ok(vec![ChannelPosition::FL, ChannelPosition::FR])
+        );
+
+        let bytes = PropsBuilder::new().mute(true).to_pod_bytes().unwrap();
+        let pod = crate::pod::Pod::from_bytes(&bytes).unwrap();
+        assert_eq!(parse_channel_map(pod), Err(Errno::ENOENT));
+    }
+}
@@ -1,7 +1,11 @@
 // Copyright The pipewire-rs Contributors.
 // SPDX-License-Identifier: MIT
 
+mod channel;
+mod props;
 mod raw;
+pub use channel::*;
+pub use props::*;
 pub use raw::*;
 
 use std::ffi::CStr;
@@ -0,0 +1,274 @@
+// Copyright The pipewire-rs Contributors.
+// SPDX-License-Identifier: MIT
+
+use nix::errno::Errno;
+
+use crate::{
+    param::{ParamObject, ParamType},
+    pod::{Object, PodBuf, Property, Value, ValueArray},
+    utils::{Id, SpaTypes},
+};
+
+use super::MAX_CHANNELS;
+
+/// Convert a linear volume, as used by PipeWire, to the cubic scale usually shown to users.
+///
+/// PipeWire volumes are linear amplitude factors, where `1.0` is the nominal volume.
+/// As loudness is not perceived linearly, volume sliders show the cube root of the volume
+/// instead, so that a slider at half of its course sets a volume of `0.125`.
+pub fn linear_to_cubic(volume: f32) -> f32 {
+    volume.cbrt()
+}
+
+/// Convert a volume on the cubic scale usually shown to users to the linear volume used by PipeWire,
+/// see [`linear_to_cubic`].
+pub fn cubic_to_linear(volume: f32) -> f32 {
+    volume * volume * volume
+}
+
+/// The audio properties of a `Props` param, such as the volume of a node or of a device route.
+///
+/// Properties set to [`None`] are left out of the param, so when setting it, the node keeps
+/// its current value for them.
+///
+/// Volumes are linear, see [`linear_to_cubic`].
+#[derive(Debug, Default, Clone, PartialEq)]
+pub struct Props {
+    /// Whether the audio is muted.
+    pub mute: Option<bool>,
+    /// The volume, applying to all channels.
+    pub volume: Option<f32>,
+    /// The volume of each channel.
+    pub channel_volumes: Option<Vec<f32>>,
+    /// The position of each channel, such as [`SPA_AUDIO_CHANNEL_FL`](crate::sys::SPA_AUDIO_CHANNEL_FL).
+    pub channel_map: Option<Vec<u32>>,
+}
+
+impl ParamObject for Props {
+    const OBJECT_TYPE: SpaTypes = SpaTypes::ObjectParamProps;
+
+    /// Properties which are not audio related are ignored.
+    fn from_object(object: Object) -> Result<Self, Errno> {
+        let mut props = Self::default();
+
+        for prop in object.properties {
+            match (prop.key, prop.value) {
+                (spa_sys::SPA_PROP_mute, Value::Bool(v)) => props.mute = Some(v),
+                (spa_sys::SPA_PROP_volume, Value::Float(v)) => props.volume = Some(v),
+                (spa_sys::SPA_PROP_channelVolumes, Value::ValueArray(ValueArray::Float(v))) => {
+                    props.channel_volumes = Some(v)
+                }
+                (spa_sys::SPA_PROP_channelMap, Value::ValueArray(ValueArray::Id(v))) => {
+                    props.channel_map = Some(v.into_iter().map(|Id(v)| v).collect())
+                }
+                _ => {}
+            }
+        }
+
+        Ok(props)
+    }
+}
+
+impl From<Props> for Object {
+    fn from(value: Props) -> Self {
+        let mut properties = Vec::with_capacity(4);
+
+        if let Some(mute) = value.mute {
+            properties.push(Property::new(spa_sys::SPA_PROP_mute, Value::Bool(mute)));
+        }
+        if let Some(volume) = value.volume {
+            properties.push(Property::new(
+                spa_sys::SPA_PROP_volume,
+                Value::Float(volume),
+            ));
+        }
+        if let Some(volumes) = value.channel_volumes {
+            properties.push(Property::new(
+                spa_sys::SPA_PROP_channelVolumes,
+                Value::ValueArray(ValueArray::Float(volumes)),
+            ));
+        }
+        if let Some(positions) = value.channel_map {
+            properties.push(Property::new(
+                spa_sys::SPA_PROP_channelMap,
+                Value::ValueArray(ValueArray::Id(positions.into_iter().map(Id).collect())),
+            ));
+        }
+
+        Object {
+            type_: SpaTypes::ObjectParamProps.as_raw(),
+            id: ParamType::Props.as_raw(),
+            properties,
+        }
+    }
+}
+
+/// A builder for the audio properties of a `Props` param, such as the volume of a node.
+///
+/// Only the properties that are set are included in the resulting pod, so the node keeps
+/// its current value for the others.
+///
+/// Volumes are linear, see [`linear_to_cubic`].
+///
+/// ```rust
+/// use libspa::param::audio::PropsBuilder;
+///
+/// let bytes = PropsBuilder::new()
+///     .mute(false)
+///     .channel_volumes(&[0.5, 0.5])
+///     .channel_map(&[libspa::sys::SPA_AUDIO_CHANNEL_FL, libspa::sys::SPA_AUDIO_CHANNEL_FR])
+///     .to_pod_bytes()
+///     .unwrap();
+/// ```
+#[derive(Debug, Default, Clone, PartialEq)]
+pub struct PropsBuilder {
+    props: Props,
+}
+
+impl PropsBuilder {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Set the `mute` property.
+    #[must_use]
+    pub fn mute(mut self, mute: bool) -> Self {
+        self.props.mute = Some(mute);
+        self
+    }
+
+    /// Set the `volume` property, applying to all channels.
+    #[must_use]
+    pub fn volume(mut self, volume: f32) -> Self {
+        self.props.volume = Some(volume);
+        self
+    }
+
+    /// Set the `channelVolumes` property, with the volume of each channel.
+    #[must_use]
+    pub fn channel_volumes(mut self, volumes: &[f32]) -> Self {
+        self.props.channel_volumes = Some(volumes.to_vec());
+        self
+    }
+
+    /// Set the `channelMap` property, with the position of each channel,
+    /// such as [`SPA_AUDIO_CHANNEL_FL`](crate::sys::SPA_AUDIO_CHANNEL_FL).
+    #[must_use]
+    pub fn channel_map(mut self, positions: &[u32]) -> Self {
+        self.props.channel_map = Some(positions.to_vec());
+        self
+    }
+
+    /// Check that the properties set are consistent.
+    ///
+    /// Returns [`Errno::EINVAL`] if a volume is negative or not finite, if there are more than
+    /// [`MAX_CHANNELS`] channels, or if both `channelVolumes` and `channelMap` are set
+    /// with a different number of channels.
+    pub fn validate(&self) -> Result<(), Errno> {
+        let props = &self.props;
+        let valid_volume = |volume: &f32| volume.is_finite() && *volume >= 0.0;
+
+        if !props.volume.iter().all(valid_volume) {
+            return Err(Errno::EINVAL);
+        }
+
+        if let Some(volumes) = &props.channel_volumes {
+            if volumes.len() > MAX_CHANNELS || !volumes.iter().all(valid_volume) {
+                return Err(Errno::EINVAL);
+            }
+        }
+
+        if let Some(positions) = &props.channel_map {
+            if positions.len() > MAX_CHANNELS {
+                return Err(Errno::EINVAL);
+            }
+        }
+
+        if let (Some(volumes), Some(positions)) = (&props.channel_volumes, &props.channel_map) {
+            if volumes.len() != positions.len() {
+                return Err(Errno::EINVAL);
+            }
+        }
+
+        Ok(())
+    }
+
+    /// Build the properties, after [validating](Self::validate) them.
+    pub fn build(&self) -> Result<Props, Errno> {
+        self.validate()?;
+
+        Ok(self.props.clone())
+    }
+
+    /// Build the raw bytes of the `Props` param pod, after [validating](Self::validate) the properties.
+    pub fn to_pod_bytes(&self) -> Result<PodBuf, Errno> {
+        self.build().map(|props| props.to_pod_bytes())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{param::assert_roundtrip, pod::Pod};
+
+    #[test]
+    fn build() {
+        let bytes = PropsBuilder::new()
+            .mute(true)
+            .channel_volumes(&[0.5, 1.0])
+            .channel_map(&[spa_sys::SPA_AUDIO_CHANNEL_FL, spa_sys::SPA_AUDIO_CHANNEL_FR])
+            .to_pod_bytes()
+            .unwrap();
+        let pod = Pod::from_bytes(&bytes).unwrap();
+
+        assert_eq!(
+            Props::parse(pod),
+            Ok(Props {
+                mute: Some(true),
+                volume: None,
+                channel_volumes: Some(vec![0.5, 1.0]),
+                channel_map: Some(vec![
+                    spa_sys::SPA_AUDIO_CHANNEL_FL,
+                    spa_sys::SPA_AUDIO_CHANNEL_FR
+                ]),
+            })
+        );
+    }
+
+    #[test]
+    fn roundtrip() {
+        assert_roundtrip(&Props {
+            mute: Some(false),
+            volume: Some(cubic_to_linear(0.5)),
+            channel_volumes: None,
+            channel_map: None,
+        });
+        assert!((linear_to_cubic(cubic_to_linear(0.5)) - 0.5).abs() < f32::EPSILON);
+    }
+
+    #[test]
+    fn validate() {
+        assert_eq!(PropsBuilder::new().validate(), Ok(()));
+        assert_eq!(
+            PropsBuilder::new().volume(f32::NAN).validate(),
+            Err(Errno::EINVAL)
+        );
+        assert_eq!(
+            PropsBuilder::new().volume(-1.0).validate(),
+            Err(Errno::EINVAL)
+        );
+        assert_eq!(
+            PropsBuilder::new()
+                .channel_volumes(&[1.0, 1.0])
+                .channel_map(&[spa_sys::SPA_AUDIO_CHANNEL_MONO])
+                .build(),
+            Err(Errno::EINVAL)
+        );
+        assert_eq!(
+            PropsBuilder::new()
+                .channel_volumes(&[1.0; MAX_CHANNELS + 1])
+                .validate(),
+            Err(Errno::EINVAL)
+        );
+    }
+}
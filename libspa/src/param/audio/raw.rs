@@ -5,7 +5,7 @@ use crate::param::audio::AudioFormat;
 use crate::pod::{Property, Value, ValueArray};
 use crate::utils::{
     self,
-    result::{Error, SpaResult, SpaSuccess},
+    result::{Error, SpaSuccess},
 };
 use std::fmt::Debug;
 
@@ -81,7 +81,7 @@ impl AudioInfoRaw {
     /// helper function to parse format properties type
     pub fn parse(&mut self, format: &crate::pod::Pod) -> Result<SpaSuccess, Error> {
         let res = unsafe { spa_sys::spa_format_audio_raw_parse(format.as_raw_ptr(), &mut self.0) };
-        SpaResult::from_c(res).into_result()
+        crate::spa_result!(res)
     }
 
     /// Obtain an [`AudioInfoRaw`] from a raw `spa_audio_info_raw` variant.
@@ -0,0 +1,236 @@
+// Copyright The pipewire-rs Contributors.
+// SPDX-License-Identifier: MIT
+
+//! Types for dealing with the `Buffers` param of a port or a stream.
+//!
+//! Once the format is negotiated, the `Buffers` param tells how many buffers are allocated
+//! for the port, and the geometry of their data. A stream typically sets it from its
+//! `param_changed` callback, after receiving the format, to size the buffers for that format.
+
+use nix::errno::Errno;
+
+use crate::{
+    param::{ParamObject, ParamType},
+    pod::{ChoiceValue, Object, Property, Value},
+    utils::{Choice, ChoiceEnum, SpaTypes},
+};
+
+/// A typed representation of a `Buffers` param.
+///
+/// Use [`Buffers::builder`] to create one with validated values.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Buffers {
+    /// The number of buffers.
+    pub buffers: u32,
+    /// The number of data blocks per buffer, for example one per plane of a planar video format.
+    pub blocks: u32,
+    /// The size of a data block, in bytes.
+    pub size: u32,
+    /// The stride of a data block, in bytes, or 0 if not applicable.
+    pub stride: u32,
+    /// The alignment of the data of a block, in bytes.
+    pub align: u32,
+}
+
+impl Buffers {
+    /// Create a builder for a `Buffers` param.
+    pub fn builder() -> BuffersBuilder {
+        BuffersBuilder::default()
+    }
+}
+
+impl ParamObject for Buffers {
+    const OBJECT_TYPE: SpaTypes = SpaTypes::ObjectParamBuffers;
+
+    /// Values given as a choice, as in the params offered by a port before negotiation,
+    /// are read as the default of the choice. Missing values are left at zero.
+    fn from_object(object: Object) -> Result<Self, Errno> {
+        let mut buffers = Self {
+            buffers: 0,
+            blocks: 0,
+            size: 0,
+            stride: 0,
+            align: 0,
+        };
+
+        for prop in object.properties {
+            let value = match prop.value {
+                Value::Int(v) => v,
+                Value::Choice(ChoiceValue::Int(Choice(_, choice))) => match choice {
+                    ChoiceEnum::None(v)
+                    | ChoiceEnum::Range { default: v, .. }
+                    | ChoiceEnum::Step { default: v, .. }
+                    | ChoiceEnum::Enum { default: v, .. }
+                    | ChoiceEnum::Flags { default: v, .. } => v,
+                },
+                _ => continue,
+            };
+            let value = u32::try_from(value).map_err(|_| Errno::EINVAL)?;
+
+            match prop.key {
+                spa_sys::SPA_PARAM_BUFFERS_buffers => buffers.buffers = value,
+                spa_sys::SPA_PARAM_BUFFERS_blocks => buffers.blocks = value,
+                spa_sys::SPA_PARAM_BUFFERS_size => buffers.size = value,
+                spa_sys::SPA_PARAM_BUFFERS_stride => buffers.stride = value,
+                spa_sys::SPA_PARAM_BUFFERS_align => buffers.align = value,
+                _ => {}
+            }
+        }
+
+        Ok(buffers)
+    }
+}
+
+impl From<Buffers> for Object {
+    fn from(value: Buffers) -> Self {
+        let int = |key, value: u32| Property::new(key, Value::Int(value as i32));
+
+        Object {
+            type_: SpaTypes::ObjectParamBuffers.as_raw(),
+            id: ParamType::Buffers.as_raw(),
+            properties: vec![
+                int(spa_sys::SPA_PARAM_BUFFERS_buffers, value.buffers),
+                int(spa_sys::SPA_PARAM_BUFFERS_blocks, value.blocks),
+                int(spa_sys::SPA_PARAM_BUFFERS_size, value.size),
+                int(spa_sys::SPA_PARAM_BUFFERS_stride, value.stride),
+                int(spa_sys::SPA_PARAM_BUFFERS_align, value.align),
+            ],
+        }
+    }
+}
+
+/// A builder for a [`Buffers`] param, see [`Buffers::builder`].
+///
+/// By default, 8 buffers of a single block aligned on 16 bytes are requested.
+/// The size of the blocks has to be set.
+#[derive(Debug, Clone, Copy)]
+pub struct BuffersBuilder {
+    buffers: u32,
+    blocks: u32,
+    size: u32,
+    stride: u32,
+    align: u32,
+}
+
+impl Default for BuffersBuilder {
+    fn default() -> Self {
+        Self {
+            buffers: 8,
+            blocks: 1,
+            size: 0,
+            stride: 0,
+            align: 16,
+        }
+    }
+}
+
+impl BuffersBuilder {
+    /// Set the number of buffers.
+    #[must_use]
+    pub fn buffers(mut self, buffers: u32) -> Self {
+        self.buffers = buffers;
+        self
+    }
+
+    /// Set the number of data blocks per buffer.
+    #[must_use]
+    pub fn blocks(mut self, blocks: u32) -> Self {
+        self.blocks = blocks;
+        self
+    }
+
+    /// Set the size of a data block, in bytes.
+    #[must_use]
+    pub fn size(mut self, size: u32) -> Self {
+        self.size = size;
+        self
+    }
+
+    /// Set the stride of a data block, in bytes.
+    #[must_use]
+    pub fn stride(mut self, stride: u32) -> Self {
+        self.stride = stride;
+        self
+    }
+
+    /// Set the alignment of the data of a block, in bytes.
+    #[must_use]
+    pub fn align(mut self, align: u32) -> Self {
+        self.align = align;
+        self
+    }
+
+    /// Build the param.
+    ///
+    /// Returns [`Errno::EINVAL`] if the number of buffers, the number of blocks or the size is 0,
+    /// if the alignment is not a power of two, if the stride is larger than the size,
+    /// or if a value does not fit in the `Int` pods of the param.
+    pub fn build(self) -> Result<Buffers, Errno> {
+        let values = [
+            self.buffers,
+            self.blocks,
+            self.size,
+            self.stride,
+            self.align,
+        ];
+        if self.buffers == 0
+            || self.blocks == 0
+            || self.size == 0
+            || !self.align.is_power_of_two()
+            || self.stride > self.size
+            || values.iter().any(|&v| i32::try_from(v).is_err())
+        {
+            return Err(Errno::EINVAL);
+        }
+
+        Ok(Buffers {
+            buffers: self.buffers,
+            blocks: self.blocks,
+            size: self.size,
+            stride: self.stride,
+            align: self.align,
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::param::assert_roundtrip;
+
+    #[test]
+    fn roundtrip() {
+        let buffers = Buffers::builder()
+            .buffers(4)
+            .size(4096)
+            .stride(8)
+            .build()
+            .unwrap();
+        assert_eq!(buffers.blocks, 1);
+        assert_eq!(buffers.align, 16);
+
+        assert_roundtrip(&buffers);
+    }
+
+    #[test]
+    fn validation() {
+        assert_eq!(Buffers::builder().build(), Err(Errno::EINVAL));
+        assert_eq!(
+            Buffers::builder().size(1024).buffers(0).build(),
+            Err(Errno::EINVAL)
+        );
+        assert_eq!(
+            Buffers::builder().size(1024).align(3).build(),
+            Err(Errno::EINVAL)
+        );
+        assert_eq!(
+            Buffers::builder().size(1024).stride(2048).build(),
+            Err(Errno::EINVAL)
+        );
+        assert_eq!(
+            Buffers::builder().size(u32::MAX).build(),
+            Err(Errno::EINVAL)
+        );
+        assert!(Buffers::builder().size(1024).build().is_ok());
+    }
+}
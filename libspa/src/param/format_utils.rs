@@ -4,9 +4,13 @@
 use std::mem::MaybeUninit;
 
 use crate::{
-    param::format::{MediaSubtype, MediaType},
+    param::{
+        audio::AudioInfoRaw,
+        format::{MediaSubtype, MediaType},
+        video::VideoInfoRaw,
+    },
     pod::Pod,
-    utils::result::{Error, SpaResult},
+    utils::result::Error,
 };
 
 /// helper function to parse format properties type
@@ -22,7 +26,7 @@ pub fn parse_format(format: &Pod) -> Result<(MediaType, MediaSubtype), Error> {
         )
     };
 
-    match SpaResult::from_c(res).into_sync_result() {
+    match crate::spa_result!(sync res) {
         Err(e) => Err(e),
         Ok(_) => Ok(unsafe {
             (
@@ -32,3 +36,138 @@ pub fn parse_format(format: &Pod) -> Result<(MediaType, MediaSubtype), Error> {
         }),
     }
 }
+
+/// A parsed `Format` param, of any media type.
+///
+/// This allows code handling both audio and video, such as a recorder, to match on a single type.
+/// Formats without a typed representation keep their media type and subtype only.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Format {
+    /// A raw audio format.
+    AudioRaw(AudioInfoRaw),
+    /// A raw video format.
+    VideoRaw(VideoInfoRaw),
+    /// Any other format, such as a DSP or an encoded format.
+    Other {
+        media_type: MediaType,
+        media_subtype: MediaSubtype,
+    },
+}
+
+impl Format {
+    /// Parse a `Format` or `EnumFormat` param pod.
+    ///
+    /// The pod is parsed using [`AudioInfoRaw::parse`] or [`VideoInfoRaw::parse`] depending on its media type.
+    pub fn parse(format: &Pod) -> Result<Self, Error> {
+        let (media_type, media_subtype) = parse_format(format)?;
+
+        match (media_type, media_subtype) {
+            (MediaType::Audio, MediaSubtype::Raw) => {
+                let mut info = AudioInfoRaw::new();
+                info.parse(format)?;
+                Ok(Self::AudioRaw(info))
+            }
+            (MediaType::Video, MediaSubtype::Raw) => {
+                let mut info = VideoInfoRaw::new();
+                info.parse(format)?;
+                Ok(Self::VideoRaw(info))
+            }
+            _ => Ok(Self::Other {
+                media_type,
+                media_subtype,
+            }),
+        }
+    }
+
+    /// The media type of the format.
+    pub fn media_type(&self) -> MediaType {
+        match self {
+            Self::AudioRaw(_) => MediaType::Audio,
+            Self::VideoRaw(_) => MediaType::Video,
+            Self::Other { media_type, .. } => *media_type,
+        }
+    }
+
+    /// The media subtype of the format.
+    pub fn media_subtype(&self) -> MediaSubtype {
+        match self {
+            Self::AudioRaw(_) | Self::VideoRaw(_) => MediaSubtype::Raw,
+            Self::Other { media_subtype, .. } => *media_subtype,
+        }
+    }
+
+    /// Get the raw audio format, if this is one.
+    pub fn as_audio_raw(&self) -> Option<&AudioInfoRaw> {
+        match self {
+            Self::AudioRaw(info) => Some(info),
+            _ => None,
+        }
+    }
+
+    /// Get the raw video format, if this is one.
+    pub fn as_video_raw(&self) -> Option<&VideoInfoRaw> {
+        match self {
+            Self::VideoRaw(info) => Some(info),
+            _ => None,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        param::{audio::AudioFormat, video::VideoFormat, ParamType},
+        pod::{serialize::PodSerializer, Object, PodBuf, Property, Value},
+        utils::SpaTypes,
+    };
+
+    fn serialize(properties: Vec<Property>) -> PodBuf {
+        let object = Object {
+            type_: SpaTypes::ObjectParamFormat.as_raw(),
+            id: ParamType::Format.as_raw(),
+            properties,
+        };
+        PodSerializer::serialize_to_buf(&Value::Object(object)).unwrap()
+    }
+
+    #[test]
+    #[cfg_attr(miri, ignore)]
+    fn parse_audio_and_video() {
+        let mut audio = AudioInfoRaw::new();
+        audio.set_format(AudioFormat::F32LE);
+        audio.set_rate(48000);
+        let bytes = serialize(audio.into());
+        let format = Format::parse(Pod::from_bytes(&bytes).unwrap()).unwrap();
+        assert_eq!(format.media_type(), MediaType::Audio);
+        assert_eq!(format.media_subtype(), MediaSubtype::Raw);
+        assert_eq!(format.as_audio_raw().unwrap().rate(), 48000);
+        assert!(format.as_video_raw().is_none());
+
+        let mut video = VideoInfoRaw::new();
+        video.set_format(VideoFormat::RGBA);
+        let bytes = serialize(video.into());
+        let format = Format::parse(Pod::from_bytes(&bytes).unwrap()).unwrap();
+        assert_eq!(format.media_type(), MediaType::Video);
+        assert_eq!(format.as_video_raw().unwrap().format(), VideoFormat::RGBA);
+
+        let bytes = serialize(vec![
+            Property::new(
+                spa_sys::SPA_FORMAT_mediaType,
+                Value::Id(crate::utils::Id(MediaType::Audio.as_raw())),
+            ),
+            Property::new(
+                spa_sys::SPA_FORMAT_mediaSubtype,
+                Value::Id(crate::utils::Id(MediaSubtype::Dsp.as_raw())),
+            ),
+        ]);
+        let format = Format::parse(Pod::from_bytes(&bytes).unwrap()).unwrap();
+        assert_eq!(
+            format,
+            Format::Other {
+                media_type: MediaType::Audio,
+                media_subtype: MediaSubtype::Dsp
+            }
+        );
+    }
+}
@@ -0,0 +1,135 @@
+// Copyright The pipewire-rs Contributors.
+// SPDX-License-Identifier: MIT
+
+//! Types for dealing with the `Latency` param of a node or a port.
+//!
+//! The latency of each direction is propagated through the graph: a node reports the latency
+//! from its ports to the source (for [`Direction::Input`]) or to the sink (for [`Direction::Output`]),
+//! so that the graph can compensate for it.
+//!
+//! The latency is split in a part relative to the quantum, a part relative to the rate,
+//! in samples, and a fixed part in nanoseconds. The total latency is the sum of the three.
+
+use nix::errno::Errno;
+
+use crate::{
+    param::{ParamObject, ParamType},
+    pod::{Object, Property, Value},
+    utils::{Direction, Id, SpaTypes},
+};
+
+/// A typed representation of a `Latency` param.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Latency {
+    /// The direction the latency is reported for.
+    pub direction: Direction,
+    /// The minimum latency, as a multiple of the quantum.
+    pub min_quantum: f32,
+    /// The maximum latency, as a multiple of the quantum.
+    pub max_quantum: f32,
+    /// The minimum latency, in samples.
+    pub min_rate: i32,
+    /// The maximum latency, in samples.
+    pub max_rate: i32,
+    /// The minimum latency, in nanoseconds.
+    pub min_ns: i64,
+    /// The maximum latency, in nanoseconds.
+    pub max_ns: i64,
+}
+
+impl Latency {
+    /// Create a new, zero, latency for `direction`.
+    pub fn new(direction: Direction) -> Self {
+        Self {
+            direction,
+            min_quantum: 0.0,
+            max_quantum: 0.0,
+            min_rate: 0,
+            max_rate: 0,
+            min_ns: 0,
+            max_ns: 0,
+        }
+    }
+}
+
+impl ParamObject for Latency {
+    const OBJECT_TYPE: SpaTypes = SpaTypes::ObjectParamLatency;
+
+    /// Missing values are left at zero.
+    /// Returns [`Errno::EINVAL`] if the object lacks a direction.
+    fn from_object(object: Object) -> Result<Self, Errno> {
+        let mut direction = None;
+        let mut latency = Self::new(Direction::Input);
+
+        for prop in object.properties {
+            match (prop.key, prop.value) {
+                (spa_sys::SPA_PARAM_LATENCY_direction, Value::Id(Id(v))) => {
+                    direction = Some(Direction::from_raw(v))
+                }
+                (spa_sys::SPA_PARAM_LATENCY_minQuantum, Value::Float(v)) => latency.min_quantum = v,
+                (spa_sys::SPA_PARAM_LATENCY_maxQuantum, Value::Float(v)) => latency.max_quantum = v,
+                (spa_sys::SPA_PARAM_LATENCY_minRate, Value::Int(v)) => latency.min_rate = v,
+                (spa_sys::SPA_PARAM_LATENCY_maxRate, Value::Int(v)) => latency.max_rate = v,
+                (spa_sys::SPA_PARAM_LATENCY_minNs, Value::Long(v)) => latency.min_ns = v,
+                (spa_sys::SPA_PARAM_LATENCY_maxNs, Value::Long(v)) => latency.max_ns = v,
+                _ => {}
+            }
+        }
+
+        latency.direction = direction.ok_or(Errno::EINVAL)?;
+
+        Ok(latency)
+    }
+}
+
+impl From<Latency> for Object {
+    fn from(value: Latency) -> Self {
+        Object {
+            type_: SpaTypes::ObjectParamLatency.as_raw(),
+            id: ParamType::Latency.as_raw(),
+            properties: vec![
+                Property::new(
+                    spa_sys::SPA_PARAM_LATENCY_direction,
+                    Value::Id(Id(value.direction.as_raw())),
+                ),
+                Property::new(
+                    spa_sys::SPA_PARAM_LATENCY_minQuantum,
+                    Value::Float(value.min_quantum),
+                ),
+                Property::new(
+                    spa_sys::SPA_PARAM_LATENCY_maxQuantum,
+                    Value::Float(value.max_quantum),
+                ),
+                Property::new(
+                    spa_sys::SPA_PARAM_LATENCY_minRate,
+                    Value::Int(value.min_rate),
+                ),
+                Property::new(
+                    spa_sys::SPA_PARAM_LATENCY_maxRate,
+                    Value::Int(value.max_rate),
+                ),
+                Property::new(spa_sys::SPA_PARAM_LATENCY_minNs, Value::Long(value.min_ns)),
+                Property::new(spa_sys::SPA_PARAM_LATENCY_maxNs, Value::Long(value.max_ns)),
+            ],
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::param::assert_roundtrip;
+
+    #[test]
+    fn roundtrip() {
+        let mut latency = Latency::new(Direction::Output);
+        latency.min_quantum = 1.0;
+        latency.max_quantum = 1.0;
+        latency.min_rate = 256;
+        latency.max_rate = 512;
+        latency.min_ns = 1_000_000;
+        latency.max_ns = 2_000_000;
+
+        assert_roundtrip(&latency);
+    }
+}
@@ -4,13 +4,28 @@
 //! Types for dealing with SPA parameters.
 
 pub mod audio;
+pub mod buffers;
 pub mod format;
 pub mod format_utils;
+pub mod latency;
+pub mod port_config;
+pub mod profile;
+pub mod profiler;
+pub mod route;
 pub mod video;
 
+pub use format_utils::Format;
+
 use std::ffi::CStr;
 use std::fmt::Debug;
 
+use nix::errno::Errno;
+
+use crate::{
+    pod::{deserialize::PodDeserializer, serialize::PodSerializer, Object, Pod, PodBuf, Value},
+    utils::SpaTypes,
+};
+
 /// Different parameter types that can be queried
 #[derive(Copy, Clone, PartialEq, Eq)]
 pub struct ParamType(pub spa_sys::spa_param_type);
@@ -61,6 +76,42 @@ impl ParamType {
     pub fn as_raw(&self) -> spa_sys::spa_param_type {
         self.0
     }
+
+    /// Get the type of the object pods used for this param, such as
+    /// [`SpaTypes::ObjectParamFormat`] for [`ParamType::EnumFormat`] and [`ParamType::Format`].
+    ///
+    /// Returns `None` for [`ParamType::Invalid`], [`ParamType::Control`], which is a sequence
+    /// instead of an object, and unknown params.
+    pub fn object_type(&self) -> Option<SpaTypes> {
+        let type_ = match *self {
+            Self::PropInfo => SpaTypes::ObjectParamPropInfo,
+            Self::Props => SpaTypes::ObjectParamProps,
+            Self::EnumFormat | Self::Format => SpaTypes::ObjectParamFormat,
+            Self::Buffers => SpaTypes::ObjectParamBuffers,
+            Self::Meta => SpaTypes::ObjectParamMeta,
+            Self::IO => SpaTypes::ObjectParamIO,
+            Self::EnumProfile | Self::Profile => SpaTypes::ObjectParamProfile,
+            Self::EnumPortConfig | Self::PortConfig => SpaTypes::ObjectParamPortConfig,
+            Self::EnumRoute | Self::Route => SpaTypes::ObjectParamRoute,
+            Self::Latency => SpaTypes::ObjectParamLatency,
+            Self::ProcessLatency => SpaTypes::ObjectParamProcessLatency,
+            _ => return None,
+        };
+
+        Some(type_)
+    }
+}
+
+impl From<spa_sys::spa_param_type> for ParamType {
+    fn from(raw: spa_sys::spa_param_type) -> Self {
+        Self::from_raw(raw)
+    }
+}
+
+impl From<ParamType> for spa_sys::spa_param_type {
+    fn from(param: ParamType) -> Self {
+        param.as_raw()
+    }
 }
 
 impl Debug for ParamType {
@@ -81,8 +132,11 @@ impl Debug for ParamType {
 bitflags::bitflags! {
     #[derive(Debug, PartialEq, Eq, Clone, Copy)]
     pub struct ParamInfoFlags: u32 {
+        /// Bit toggled each time the param changes
         const SERIAL = 1<<0;
+        /// The param is readable
         const READ   = 1<<1;
+        /// The param is writable
         const WRITE  = 1<<2;
         const READWRITE = Self::READ.bits() | Self::WRITE.bits();
     }
@@ -100,6 +154,16 @@ impl ParamInfo {
     pub fn flags(&self) -> ParamInfoFlags {
         ParamInfoFlags::from_bits_truncate(self.0.flags)
     }
+
+    /// Returns `true` if the param can be enumerated, see [`ParamInfoFlags::READ`].
+    pub fn is_readable(&self) -> bool {
+        self.flags().contains(ParamInfoFlags::READ)
+    }
+
+    /// Returns `true` if the param can be set, see [`ParamInfoFlags::WRITE`].
+    pub fn is_writable(&self) -> bool {
+        self.flags().contains(ParamInfoFlags::WRITE)
+    }
 }
 
 impl Debug for ParamInfo {
@@ -110,3 +174,68 @@ impl Debug for ParamInfo {
             .finish()
     }
 }
+
+/// A param with a typed representation, such as a [`Route`](route::Route) or a [`Latency`](latency::Latency).
+///
+/// Implementors only convert between the typed param and its [`Object`],
+/// parsing and serializing pods is provided on top of that.
+pub trait ParamObject: Clone + Into<Object> {
+    /// The type of the object pods of the param.
+    const OBJECT_TYPE: SpaTypes;
+
+    /// Build the param from an object of type [`OBJECT_TYPE`](Self::OBJECT_TYPE).
+    ///
+    /// Returns [`Errno::EINVAL`] if the object lacks required properties.
+    fn from_object(object: Object) -> Result<Self, Errno>;
+
+    /// Parse the param from a pod.
+    ///
+    /// Returns [`Errno::EINVAL`] if the pod is not an object of type [`OBJECT_TYPE`](Self::OBJECT_TYPE),
+    /// or if [`from_object`](Self::from_object) fails.
+    fn parse(pod: &Pod) -> Result<Self, Errno> {
+        match PodDeserializer::deserialize_any_from(pod.as_bytes()) {
+            Ok((_, Value::Object(object))) if object.type_ == Self::OBJECT_TYPE.as_raw() => {
+                Self::from_object(object)
+            }
+            _ => Err(Errno::EINVAL),
+        }
+    }
+
+    /// Serialize the param into a pod.
+    fn to_pod_bytes(&self) -> PodBuf {
+        PodSerializer::serialize_to_buf(&Value::Object(self.clone().into()))
+            .expect("Failed to serialize param")
+    }
+}
+
+/// Check that `param` is parsed back unchanged once serialized.
+#[cfg(test)]
+pub(crate) fn assert_roundtrip<T: ParamObject + PartialEq + Debug>(param: &T) {
+    let bytes = param.to_pod_bytes();
+    let pod = bytes.as_pod().expect("Serialized param is a valid pod");
+
+    assert_eq!(T::parse(pod).as_ref(), Ok(param));
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn object_type() {
+        assert_eq!(
+            ParamType::EnumFormat.object_type(),
+            Some(SpaTypes::ObjectParamFormat)
+        );
+        assert_eq!(
+            ParamType::Route.object_type(),
+            Some(SpaTypes::ObjectParamRoute)
+        );
+        assert_eq!(ParamType::Control.object_type(), None);
+        assert_eq!(ParamType::from(spa_sys::SPA_PARAM_Props), ParamType::Props);
+        assert_eq!(
+            spa_sys::spa_param_type::from(ParamType::Latency),
+            spa_sys::SPA_PARAM_Latency
+        );
+    }
+}
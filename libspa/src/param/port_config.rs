@@ -0,0 +1,172 @@
+// Copyright The pipewire-rs Contributors.
+// SPDX-License-Identifier: MIT
+
+//! Types for dealing with the `PortConfig` param of a node.
+
+use nix::errno::Errno;
+
+use crate::{
+    param::{ParamObject, ParamType},
+    pod::{Object, Property, Value},
+    utils::{Direction, Id, SpaTypes},
+};
+
+/// The mode of a port configuration.
+#[derive(Copy, Clone, PartialEq, Eq)]
+pub struct ParamPortConfigMode(pub spa_sys::spa_param_port_config_mode);
+
+#[allow(non_upper_case_globals)]
+impl ParamPortConfigMode {
+    /// no configuration
+    pub const None: Self = Self(spa_sys::SPA_PARAM_PORT_CONFIG_MODE_none);
+    /// passthrough configuration
+    pub const Passthrough: Self = Self(spa_sys::SPA_PARAM_PORT_CONFIG_MODE_passthrough);
+    /// convert configuration
+    pub const Convert: Self = Self(spa_sys::SPA_PARAM_PORT_CONFIG_MODE_convert);
+    /// dsp configuration, depending on the external format. For audio, ports will be configured
+    /// for the given number of channels with F32 format.
+    pub const Dsp: Self = Self(spa_sys::SPA_PARAM_PORT_CONFIG_MODE_dsp);
+
+    pub fn from_raw(raw: spa_sys::spa_param_port_config_mode) -> Self {
+        Self(raw)
+    }
+
+    pub fn as_raw(&self) -> spa_sys::spa_param_port_config_mode {
+        self.0
+    }
+}
+
+impl std::fmt::Debug for ParamPortConfigMode {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let name = format!(
+            "ParamPortConfigMode::{}",
+            match *self {
+                Self::None => "None",
+                Self::Passthrough => "Passthrough",
+                Self::Convert => "Convert",
+                Self::Dsp => "Dsp",
+                _ => "Unknown",
+            }
+        );
+        f.write_str(&name)
+    }
+}
+
+/// A typed representation of a `PortConfig` param, used to configure the ports of a node.
+#[derive(Debug, Clone, PartialEq)]
+pub struct PortConfig {
+    /// The direction of the ports to configure.
+    pub direction: Direction,
+    /// The mode of the configuration.
+    pub mode: ParamPortConfigMode,
+    /// Whether monitor output ports should be added, for input ports.
+    pub monitor: Option<bool>,
+    /// Whether control ports should be added.
+    pub control: Option<bool>,
+    /// The format used for the ports, as a `Format` object, for example obtained from
+    /// an [`AudioInfoRaw`](crate::param::audio::AudioInfoRaw).
+    pub format: Option<Object>,
+}
+
+impl PortConfig {
+    pub fn new(direction: Direction, mode: ParamPortConfigMode) -> Self {
+        Self {
+            direction,
+            mode,
+            monitor: None,
+            control: None,
+            format: None,
+        }
+    }
+}
+
+impl ParamObject for PortConfig {
+    const OBJECT_TYPE: SpaTypes = SpaTypes::ObjectParamPortConfig;
+
+    /// Returns [`Errno::EINVAL`] if the object lacks the direction or mode properties.
+    fn from_object(object: Object) -> Result<Self, Errno> {
+        let mut direction = None;
+        let mut mode = None;
+        let mut config = Self::new(Direction::Input, ParamPortConfigMode::None);
+
+        for prop in object.properties {
+            match (prop.key, prop.value) {
+                (spa_sys::SPA_PARAM_PORT_CONFIG_direction, Value::Id(Id(v))) => {
+                    direction = Some(Direction::from_raw(v))
+                }
+                (spa_sys::SPA_PARAM_PORT_CONFIG_mode, Value::Id(Id(v))) => {
+                    mode = Some(ParamPortConfigMode::from_raw(v))
+                }
+                (spa_sys::SPA_PARAM_PORT_CONFIG_monitor, Value::Bool(v)) => {
+                    config.monitor = Some(v)
+                }
+                (spa_sys::SPA_PARAM_PORT_CONFIG_control, Value::Bool(v)) => {
+                    config.control = Some(v)
+                }
+                (spa_sys::SPA_PARAM_PORT_CONFIG_format, Value::Object(v)) => {
+                    config.format = Some(v)
+                }
+                _ => {}
+            }
+        }
+
+        config.direction = direction.ok_or(Errno::EINVAL)?;
+        config.mode = mode.ok_or(Errno::EINVAL)?;
+
+        Ok(config)
+    }
+}
+
+impl From<PortConfig> for Object {
+    fn from(value: PortConfig) -> Self {
+        let mut properties = vec![
+            Property::new(
+                spa_sys::SPA_PARAM_PORT_CONFIG_direction,
+                Value::Id(Id(value.direction.as_raw())),
+            ),
+            Property::new(
+                spa_sys::SPA_PARAM_PORT_CONFIG_mode,
+                Value::Id(Id(value.mode.as_raw())),
+            ),
+        ];
+
+        if let Some(monitor) = value.monitor {
+            properties.push(Property::new(
+                spa_sys::SPA_PARAM_PORT_CONFIG_monitor,
+                Value::Bool(monitor),
+            ));
+        }
+        if let Some(control) = value.control {
+            properties.push(Property::new(
+                spa_sys::SPA_PARAM_PORT_CONFIG_control,
+                Value::Bool(control),
+            ));
+        }
+        if let Some(format) = value.format {
+            properties.push(Property::new(
+                spa_sys::SPA_PARAM_PORT_CONFIG_format,
+                Value::Object(format),
+            ));
+        }
+
+        Object {
+            type_: SpaTypes::ObjectParamPortConfig.as_raw(),
+            id: ParamType::PortConfig.as_raw(),
+            properties,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::param::assert_roundtrip;
+
+    #[test]
+    fn roundtrip() {
+        let mut config = PortConfig::new(Direction::Input, ParamPortConfigMode::Dsp);
+        config.monitor = Some(true);
+
+        assert_roundtrip(&config);
+    }
+}
@@ -0,0 +1,165 @@
+// Copyright The pipewire-rs Contributors.
+// SPDX-License-Identifier: MIT
+
+//! Types for dealing with the `Profile` and `EnumProfile` params of a device.
+//!
+//! The profile of a sound card selects which of its nodes exist, for example
+//! `output:analog-stereo+input:analog-stereo` or `off`.
+
+use nix::errno::Errno;
+
+use crate::{
+    param::{ParamObject, ParamType},
+    pod::{Object, Property, Value},
+    utils::{Id, SpaTypes},
+};
+
+/// Whether a [`Profile`] can currently be used, for example if the headphones of
+/// a headphones profile are plugged in.
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub struct Availability(pub spa_sys::spa_param_availability);
+
+#[allow(non_upper_case_globals)]
+impl Availability {
+    /// unknown availability
+    pub const Unknown: Self = Self(spa_sys::SPA_PARAM_AVAILABILITY_unknown);
+    /// not available
+    pub const No: Self = Self(spa_sys::SPA_PARAM_AVAILABILITY_no);
+    /// available
+    pub const Yes: Self = Self(spa_sys::SPA_PARAM_AVAILABILITY_yes);
+
+    /// Obtain an [`Availability`] from a raw `spa_param_availability` variant.
+    pub fn from_raw(raw: spa_sys::spa_param_availability) -> Self {
+        Self(raw)
+    }
+
+    /// Get the raw [`spa_sys::spa_param_availability`] representing this `Availability`.
+    pub fn as_raw(&self) -> spa_sys::spa_param_availability {
+        self.0
+    }
+}
+
+/// A typed representation of a `Profile` or `EnumProfile` param.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Profile {
+    /// The index of the profile, used to select it.
+    pub index: i32,
+    /// The name of the profile, if known.
+    pub name: Option<String>,
+    /// A human readable description of the profile, if known.
+    pub description: Option<String>,
+    /// The priority of the profile, higher priorities being preferred by the session manager.
+    pub priority: Option<i32>,
+    /// Whether the profile can currently be used.
+    pub available: Availability,
+    /// Whether the profile should be saved by the session manager.
+    pub save: bool,
+}
+
+impl Profile {
+    /// Create a new profile selecting the profile `index` of the device.
+    pub fn new(index: i32) -> Self {
+        Self {
+            index,
+            name: None,
+            description: None,
+            priority: None,
+            available: Availability::Unknown,
+            save: false,
+        }
+    }
+}
+
+impl ParamObject for Profile {
+    const OBJECT_TYPE: SpaTypes = SpaTypes::ObjectParamProfile;
+
+    /// Returns [`Errno::EINVAL`] if the object lacks an index.
+    fn from_object(object: Object) -> Result<Self, Errno> {
+        let mut index = None;
+        let mut profile = Self::new(0);
+
+        for prop in object.properties {
+            match (prop.key, prop.value) {
+                (spa_sys::SPA_PARAM_PROFILE_index, Value::Int(v)) => index = Some(v),
+                (spa_sys::SPA_PARAM_PROFILE_name, Value::String(v)) => profile.name = Some(v),
+                (spa_sys::SPA_PARAM_PROFILE_description, Value::String(v)) => {
+                    profile.description = Some(v)
+                }
+                (spa_sys::SPA_PARAM_PROFILE_priority, Value::Int(v)) => profile.priority = Some(v),
+                (spa_sys::SPA_PARAM_PROFILE_available, Value::Id(Id(v))) => {
+                    profile.available = Availability::from_raw(v)
+                }
+                (spa_sys::SPA_PARAM_PROFILE_save, Value::Bool(v)) => profile.save = v,
+                _ => {}
+            }
+        }
+
+        profile.index = index.ok_or(Errno::EINVAL)?;
+
+        Ok(profile)
+    }
+}
+
+impl From<Profile> for Object {
+    fn from(value: Profile) -> Self {
+        let mut properties = vec![Property::new(
+            spa_sys::SPA_PARAM_PROFILE_index,
+            Value::Int(value.index),
+        )];
+
+        if let Some(name) = value.name {
+            properties.push(Property::new(
+                spa_sys::SPA_PARAM_PROFILE_name,
+                Value::String(name),
+            ));
+        }
+        if let Some(description) = value.description {
+            properties.push(Property::new(
+                spa_sys::SPA_PARAM_PROFILE_description,
+                Value::String(description),
+            ));
+        }
+        if let Some(priority) = value.priority {
+            properties.push(Property::new(
+                spa_sys::SPA_PARAM_PROFILE_priority,
+                Value::Int(priority),
+            ));
+        }
+        if value.available != Availability::Unknown {
+            properties.push(Property::new(
+                spa_sys::SPA_PARAM_PROFILE_available,
+                Value::Id(Id(value.available.as_raw())),
+            ));
+        }
+        if value.save {
+            properties.push(Property::new(
+                spa_sys::SPA_PARAM_PROFILE_save,
+                Value::Bool(true),
+            ));
+        }
+
+        Object {
+            type_: SpaTypes::ObjectParamProfile.as_raw(),
+            id: ParamType::Profile.as_raw(),
+            properties,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::param::assert_roundtrip;
+
+    #[test]
+    fn roundtrip() {
+        let mut profile = Profile::new(1);
+        profile.name = Some("output:analog-stereo".to_string());
+        profile.description = Some("Analog Stereo Output".to_string());
+        profile.priority = Some(6500);
+        profile.available = Availability::Yes;
+        profile.save = true;
+
+        assert_roundtrip(&profile);
+    }
+}
@@ -0,0 +1,311 @@
+// Copyright The pipewire-rs Contributors.
+// SPDX-License-Identifier: MIT
+
+//! Types for dealing with the `Profiler` objects emitted by the profiler module.
+//!
+//! The profiler module of PipeWire reports the timing of each graph cycle as a `Profiler` object,
+//! which is the data tools like `pw-top` display.
+//! Each object describes one cycle of a driver, with the nodes it drives as followers.
+
+use nix::errno::Errno;
+
+use crate::{
+    pod::{deserialize::PodDeserializer, Object, Value},
+    utils::{Fraction, SpaTypes},
+};
+
+/// Global information about the profiled graph.
+#[derive(Debug, Clone, PartialEq)]
+pub struct ProfilerInfo {
+    /// The number of the profiled cycle.
+    pub counter: i64,
+    /// The fast, medium and slow averages of the cpu load of the graph,
+    /// where `1.0` means the whole cycle duration was used.
+    pub cpu_load: [f32; 3],
+    /// The number of xruns since the profiler started.
+    pub xrun_count: i32,
+}
+
+/// The clock of the driver of the profiled cycle.
+#[derive(Debug, Clone, PartialEq)]
+pub struct ProfilerClock {
+    /// The clock flags.
+    pub flags: i32,
+    /// The id of the clock.
+    pub id: i32,
+    /// The name of the clock.
+    pub name: String,
+    /// The time of the cycle in nanoseconds, in `CLOCK_MONOTONIC`.
+    pub nsec: i64,
+    /// The rate of `position`, `duration` and `delay`.
+    pub rate: Fraction,
+    /// The position of the clock at the start of the cycle, in `rate` units.
+    pub position: i64,
+    /// The duration of the cycle in `rate` units, which is the quantum.
+    pub duration: i64,
+    /// The delay between the clock position and the device, in `rate` units.
+    pub delay: i64,
+    /// The rate difference between the clock and the monotonic time.
+    pub rate_diff: f64,
+    /// The estimated time of the next cycle in nanoseconds.
+    pub next_nsec: i64,
+}
+
+impl ProfilerClock {
+    /// The quantum of the cycle, in samples.
+    pub fn quantum(&self) -> i64 {
+        self.duration
+    }
+}
+
+/// The timing of a node during the profiled cycle, either the driver or one of its followers.
+///
+/// The timestamps are in nanoseconds, in `CLOCK_MONOTONIC`.
+#[derive(Debug, Clone, PartialEq)]
+pub struct ProfilerBlock {
+    /// The id of the node.
+    pub id: i32,
+    /// The name of the node.
+    pub name: String,
+    /// The time when the node was signaled in the previous cycle.
+    pub prev_signal: i64,
+    /// The time when the node was signaled to start processing.
+    pub signal: i64,
+    /// The time when the node started processing.
+    pub awake: i64,
+    /// The time when the node finished processing.
+    pub finish: i64,
+    /// The status of the node.
+    pub status: i32,
+    /// The latency of the node.
+    pub latency: Fraction,
+    /// The number of xruns of the node, if reported by the server.
+    pub xrun_count: Option<i32>,
+}
+
+impl ProfilerBlock {
+    /// The time between the node being signaled and it starting to process, in nanoseconds.
+    pub fn waiting(&self) -> i64 {
+        self.awake - self.signal
+    }
+
+    /// The time the node spent processing, in nanoseconds.
+    pub fn busy(&self) -> i64 {
+        self.finish - self.awake
+    }
+
+    /// The time between the previous cycle and this one, in nanoseconds.
+    pub fn period(&self) -> i64 {
+        self.signal - self.prev_signal
+    }
+}
+
+/// A typed representation of a `Profiler` object.
+///
+/// Sub-objects which are missing or could not be decoded are left out.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct Profiler {
+    /// The global information about the graph.
+    pub info: Option<ProfilerInfo>,
+    /// The clock of the driver.
+    pub clock: Option<ProfilerClock>,
+    /// The timing of the driver node.
+    pub driver: Option<ProfilerBlock>,
+    /// The timing of the nodes driven by the driver.
+    pub followers: Vec<ProfilerBlock>,
+}
+
+impl Profiler {
+    /// Decode a `Profiler` object pod.
+    ///
+    /// Returns [`Errno::EINVAL`] if the pod is not a `Profiler` object.
+    pub fn parse(pod: &crate::pod::Pod) -> Result<Self, Errno> {
+        match PodDeserializer::deserialize_any_from(pod.as_bytes()) {
+            Ok((_, Value::Object(object))) => Self::from_object(&object),
+            _ => Err(Errno::EINVAL),
+        }
+    }
+
+    /// Decode a deserialized `Profiler` object.
+    ///
+    /// Returns [`Errno::EINVAL`] if the object is not a `Profiler` object.
+    pub fn from_object(object: &Object) -> Result<Self, Errno> {
+        if object.type_ != SpaTypes::ObjectProfiler.as_raw() {
+            return Err(Errno::EINVAL);
+        }
+
+        let mut profiler = Self::default();
+
+        for prop in &object.properties {
+            let Value::Struct(fields) = &prop.value else {
+                continue;
+            };
+            let fields = Fields(fields);
+
+            match prop.key {
+                spa_sys::SPA_PROFILER_info => profiler.info = fields.info(),
+                spa_sys::SPA_PROFILER_clock => profiler.clock = fields.clock(),
+                spa_sys::SPA_PROFILER_driverBlock => profiler.driver = fields.block(),
+                spa_sys::SPA_PROFILER_followerBlock => {
+                    profiler.followers.extend(fields.block());
+                }
+                _ => {}
+            }
+        }
+
+        Ok(profiler)
+    }
+}
+
+/// Positional access to the fields of a profiler struct.
+///
+/// Newer versions of the server append fields, so extra fields are ignored.
+struct Fields<'a>(&'a [Value]);
+
+impl<'a> Fields<'a> {
+    fn int(&self, index: usize) -> Option<i32> {
+        match self.0.get(index)? {
+            Value::Int(value) => Some(*value),
+            _ => None,
+        }
+    }
+
+    fn long(&self, index: usize) -> Option<i64> {
+        match self.0.get(index)? {
+            Value::Long(value) => Some(*value),
+            _ => None,
+        }
+    }
+
+    fn float(&self, index: usize) -> Option<f32> {
+        match self.0.get(index)? {
+            Value::Float(value) => Some(*value),
+            _ => None,
+        }
+    }
+
+    fn double(&self, index: usize) -> Option<f64> {
+        match self.0.get(index)? {
+            Value::Double(value) => Some(*value),
+            _ => None,
+        }
+    }
+
+    fn string(&self, index: usize) -> Option<String> {
+        match self.0.get(index)? {
+            Value::String(value) => Some(value.clone()),
+            _ => None,
+        }
+    }
+
+    fn fraction(&self, index: usize) -> Option<Fraction> {
+        match self.0.get(index)? {
+            Value::Fraction(value) => Some(*value),
+            _ => None,
+        }
+    }
+
+    fn info(&self) -> Option<ProfilerInfo> {
+        Some(ProfilerInfo {
+            counter: self.long(0)?,
+            cpu_load: [self.float(1)?, self.float(2)?, self.float(3)?],
+            xrun_count: self.int(4)?,
+        })
+    }
+
+    fn clock(&self) -> Option<ProfilerClock> {
+        Some(ProfilerClock {
+            flags: self.int(0)?,
+            id: self.int(1)?,
+            name: self.string(2)?,
+            nsec: self.long(3)?,
+            rate: self.fraction(4)?,
+            position: self.long(5)?,
+            duration: self.long(6)?,
+            delay: self.long(7)?,
+            rate_diff: self.double(8)?,
+            next_nsec: self.long(9)?,
+        })
+    }
+
+    fn block(&self) -> Option<ProfilerBlock> {
+        Some(ProfilerBlock {
+            id: self.int(0)?,
+            name: self.string(1)?,
+            prev_signal: self.long(2)?,
+            signal: self.long(3)?,
+            awake: self.long(4)?,
+            finish: self.long(5)?,
+            status: self.int(6)?,
+            latency: self.fraction(7)?,
+            xrun_count: self.int(8),
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::pod::{serialize::PodSerializer, Pod, Property};
+
+    fn block(id: i32, name: &str) -> Value {
+        Value::Struct(vec![
+            Value::Int(id),
+            Value::String(name.to_string()),
+            Value::Long(0),
+            Value::Long(1000),
+            Value::Long(1500),
+            Value::Long(4000),
+            Value::Int(3),
+            Value::Fraction(Fraction {
+                num: 256,
+                denom: 48000,
+            }),
+        ])
+    }
+
+    #[test]
+    fn parse() {
+        let object = Object {
+            type_: SpaTypes::ObjectProfiler.as_raw(),
+            id: 0,
+            properties: vec![
+                Property::new(
+                    spa_sys::SPA_PROFILER_info,
+                    Value::Struct(vec![
+                        Value::Long(12),
+                        Value::Float(0.1),
+                        Value::Float(0.2),
+                        Value::Float(0.3),
+                        Value::Int(2),
+                    ]),
+                ),
+                Property::new(spa_sys::SPA_PROFILER_driverBlock, block(30, "alsa_output")),
+                Property::new(spa_sys::SPA_PROFILER_followerBlock, block(42, "firefox")),
+                Property::new(spa_sys::SPA_PROFILER_followerBlock, block(43, "mpv")),
+            ],
+        };
+
+        let bytes = PodSerializer::serialize_to_buf(&Value::Object(object)).unwrap();
+        let profiler = Profiler::parse(Pod::from_bytes(&bytes).unwrap()).unwrap();
+
+        assert_eq!(
+            profiler.info,
+            Some(ProfilerInfo {
+                counter: 12,
+                cpu_load: [0.1, 0.2, 0.3],
+                xrun_count: 2
+            })
+        );
+        assert_eq!(profiler.clock, None);
+
+        let driver = profiler.driver.unwrap();
+        assert_eq!(driver.name, "alsa_output");
+        assert_eq!(driver.waiting(), 500);
+        assert_eq!(driver.busy(), 2500);
+        assert_eq!(driver.xrun_count, None);
+
+        let followers: Vec<_> = profiler.followers.iter().map(|f| f.id).collect();
+        assert_eq!(followers, [42, 43]);
+    }
+}
@@ -0,0 +1,266 @@
+// Copyright The pipewire-rs Contributors.
+// SPDX-License-Identifier: MIT
+
+//! Types for dealing with the `Route` and `EnumRoute` params of a device.
+//!
+//! On many audio devices, the hardware volume and mute state is exposed through the route
+//! of a device rather than through the `Props` of its nodes.
+
+use nix::errno::Errno;
+
+use crate::{
+    param::{audio::Props, profile::Availability, ParamObject, ParamType},
+    pod::{Object, Property, Value, ValueArray},
+    utils::{Direction, Id, SpaTypes},
+};
+
+/// A typed representation of a `Route` or `EnumRoute` param.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Route {
+    /// The index of the route.
+    pub index: i32,
+    /// The device id of the route.
+    ///
+    /// This is set for the active `Route` params of a device and is required to configure a route,
+    /// while `EnumRoute` params list the devices the route can be used with in
+    /// [`devices`](Self::devices) instead.
+    pub device: Option<i32>,
+    /// The direction of the route.
+    pub direction: Direction,
+    /// The name of the route, if known.
+    pub name: Option<String>,
+    /// A human readable description of the route, if known.
+    pub description: Option<String>,
+    /// The priority of the route, higher priorities being preferred by the session manager.
+    pub priority: Option<i32>,
+    /// Whether the route is available, such as headphones being plugged in.
+    pub available: Availability,
+    /// The ids of the devices the route can be used with, as listed by `EnumRoute` params.
+    pub devices: Vec<i32>,
+    /// The volume related properties of the route.
+    ///
+    /// Fields set to [`None`] are left out when the route is serialized, so the device keeps
+    /// its current value for them.
+    pub props: Option<Props>,
+    /// Whether the route settings should be saved by the session manager.
+    pub save: bool,
+}
+
+impl Route {
+    /// Create a new route targeting the route `index` of the device `device`.
+    pub fn new(index: i32, device: i32, direction: Direction) -> Self {
+        Self {
+            index,
+            device: Some(device),
+            direction,
+            name: None,
+            description: None,
+            priority: None,
+            available: Availability::Unknown,
+            devices: Vec::new(),
+            props: None,
+            save: false,
+        }
+    }
+}
+
+impl ParamObject for Route {
+    const OBJECT_TYPE: SpaTypes = SpaTypes::ObjectParamRoute;
+
+    /// Returns [`Errno::EINVAL`] if the object lacks the index or direction properties.
+    fn from_object(object: Object) -> Result<Self, Errno> {
+        let mut index = None;
+        let mut direction = None;
+        let mut route = Self::new(0, 0, Direction::Input);
+        route.device = None;
+
+        for prop in object.properties {
+            match (prop.key, prop.value) {
+                (spa_sys::SPA_PARAM_ROUTE_index, Value::Int(v)) => index = Some(v),
+                (spa_sys::SPA_PARAM_ROUTE_device, Value::Int(v)) => route.device = Some(v),
+                (spa_sys::SPA_PARAM_ROUTE_direction, Value::Id(Id(v))) => {
+                    direction = Some(Direction::from_raw(v))
+                }
+                (spa_sys::SPA_PARAM_ROUTE_name, Value::String(v)) => route.name = Some(v),
+                (spa_sys::SPA_PARAM_ROUTE_description, Value::String(v)) => {
+                    route.description = Some(v)
+                }
+                (spa_sys::SPA_PARAM_ROUTE_priority, Value::Int(v)) => route.priority = Some(v),
+                (spa_sys::SPA_PARAM_ROUTE_available, Value::Id(Id(v))) => {
+                    route.available = Availability::from_raw(v)
+                }
+                (spa_sys::SPA_PARAM_ROUTE_devices, Value::ValueArray(ValueArray::Int(v))) => {
+                    route.devices = v
+                }
+                (spa_sys::SPA_PARAM_ROUTE_props, Value::Object(v)) => {
+                    route.props = Some(Props::from_object(v)?)
+                }
+                (spa_sys::SPA_PARAM_ROUTE_save, Value::Bool(v)) => route.save = v,
+                _ => {}
+            }
+        }
+
+        route.index = index.ok_or(Errno::EINVAL)?;
+        route.direction = direction.ok_or(Errno::EINVAL)?;
+
+        Ok(route)
+    }
+}
+
+impl From<Route> for Object {
+    fn from(value: Route) -> Self {
+        let mut properties = vec![
+            Property::new(spa_sys::SPA_PARAM_ROUTE_index, Value::Int(value.index)),
+            Property::new(
+                spa_sys::SPA_PARAM_ROUTE_direction,
+                Value::Id(Id(value.direction.as_raw())),
+            ),
+        ];
+
+        if let Some(device) = value.device {
+            properties.push(Property::new(
+                spa_sys::SPA_PARAM_ROUTE_device,
+                Value::Int(device),
+            ));
+        }
+        if let Some(name) = value.name {
+            properties.push(Property::new(
+                spa_sys::SPA_PARAM_ROUTE_name,
+                Value::String(name),
+            ));
+        }
+        if let Some(description) = value.description {
+            properties.push(Property::new(
+                spa_sys::SPA_PARAM_ROUTE_description,
+                Value::String(description),
+            ));
+        }
+        if let Some(priority) = value.priority {
+            properties.push(Property::new(
+                spa_sys::SPA_PARAM_ROUTE_priority,
+                Value::Int(priority),
+            ));
+        }
+        if value.available != Availability::Unknown {
+            properties.push(Property::new(
+                spa_sys::SPA_PARAM_ROUTE_available,
+                Value::Id(Id(value.available.as_raw())),
+            ));
+        }
+        if !value.devices.is_empty() {
+            properties.push(Property::new(
+                spa_sys::SPA_PARAM_ROUTE_devices,
+                Value::ValueArray(ValueArray::Int(value.devices)),
+            ));
+        }
+        if let Some(props) = value.props {
+            let mut props: Object = props.into();
+            // The props of a route use the id of the route param.
+            props.id = ParamType::Route.as_raw();
+            properties.push(Property::new(
+                spa_sys::SPA_PARAM_ROUTE_props,
+                Value::Object(props),
+            ));
+        }
+        if value.save {
+            properties.push(Property::new(
+                spa_sys::SPA_PARAM_ROUTE_save,
+                Value::Bool(true),
+            ));
+        }
+
+        Object {
+            type_: SpaTypes::ObjectParamRoute.as_raw(),
+            id: ParamType::Route.as_raw(),
+            properties,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        param::assert_roundtrip,
+        pod::{serialize::PodSerializer, Pod},
+    };
+
+    #[test]
+    fn roundtrip() {
+        let mut route = Route::new(2, 1, Direction::Output);
+        route.name = Some("analog-output-speaker".to_string());
+        route.priority = Some(10000);
+        route.available = Availability::Yes;
+        route.devices = vec![1];
+        route.props = Some(Props {
+            mute: Some(false),
+            volume: Some(0.5),
+            channel_volumes: Some(vec![0.25, 0.75]),
+            channel_map: None,
+        });
+        route.save = true;
+
+        assert_roundtrip(&route);
+    }
+
+    #[test]
+    fn parse_enum_route() {
+        // Shaped like the EnumRoute params of an ALSA card: no `device`, but the list of `devices`,
+        // and the info and profiles which are not modeled.
+        let object = Object {
+            type_: SpaTypes::ObjectParamRoute.as_raw(),
+            id: ParamType::EnumRoute.as_raw(),
+            properties: vec![
+                Property::new(spa_sys::SPA_PARAM_ROUTE_index, Value::Int(1)),
+                Property::new(
+                    spa_sys::SPA_PARAM_ROUTE_direction,
+                    Value::Id(Id(Direction::Output.as_raw())),
+                ),
+                Property::new(
+                    spa_sys::SPA_PARAM_ROUTE_name,
+                    Value::String("analog-output-headphones".to_string()),
+                ),
+                Property::new(
+                    spa_sys::SPA_PARAM_ROUTE_description,
+                    Value::String("Headphones".to_string()),
+                ),
+                Property::new(spa_sys::SPA_PARAM_ROUTE_priority, Value::Int(9900)),
+                Property::new(
+                    spa_sys::SPA_PARAM_ROUTE_available,
+                    Value::Id(Id(Availability::No.as_raw())),
+                ),
+                Property::new(
+                    spa_sys::SPA_PARAM_ROUTE_info,
+                    Value::Struct(vec![Value::Int(0)]),
+                ),
+                Property::new(
+                    spa_sys::SPA_PARAM_ROUTE_profiles,
+                    Value::ValueArray(ValueArray::Int(vec![1, 3])),
+                ),
+                Property::new(
+                    spa_sys::SPA_PARAM_ROUTE_devices,
+                    Value::ValueArray(ValueArray::Int(vec![4])),
+                ),
+            ],
+        };
+        let bytes = PodSerializer::serialize_to_buf(&Value::Object(object)).unwrap();
+        let route = Route::parse(bytes.as_pod().unwrap()).unwrap();
+
+        assert_eq!(route.index, 1);
+        assert_eq!(route.device, None);
+        assert_eq!(route.direction, Direction::Output);
+        assert_eq!(route.name.as_deref(), Some("analog-output-headphones"));
+        assert_eq!(route.description.as_deref(), Some("Headphones"));
+        assert_eq!(route.priority, Some(9900));
+        assert_eq!(route.available, Availability::No);
+        assert_eq!(route.devices, [4]);
+    }
+
+    #[test]
+    fn parse_invalid() {
+        let bytes = PodSerializer::serialize_to_buf(&Value::Int(1)).unwrap();
+        let pod = Pod::from_bytes(&bytes).unwrap();
+
+        assert_eq!(Route::parse(pod), Err(Errno::EINVAL));
+    }
+}
@@ -1,8 +1,10 @@
 // Copyright The pipewire-rs Contributors.
 // SPDX-License-Identifier: MIT
 
+use crate::pod::{Property, PropertyFlags, Value};
 use crate::utils::{
-    result::{Error, SpaResult, SpaSuccess},
+    self,
+    result::{Error, SpaSuccess},
     Fraction, Rectangle,
 };
 
@@ -230,15 +232,12 @@ impl VideoInfoRaw {
             format: VideoFormat::Unknown.as_raw(),
             flags: 0,
             modifier: 0,
-            size: Rectangle {
-                width: 0,
-                height: 0,
-            },
-            framerate: Fraction { num: 0, denom: 0 },
-            max_framerate: Fraction { num: 0, denom: 0 },
+            size: Rectangle::new(0, 0).as_raw(),
+            framerate: Fraction::new(0, 0).as_raw(),
+            max_framerate: Fraction::new(0, 0).as_raw(),
             views: 0,
             interlace_mode: VideoInterlaceMode::Progressive.as_raw(),
-            pixel_aspect_ratio: Fraction { num: 0, denom: 0 },
+            pixel_aspect_ratio: Fraction::new(0, 0).as_raw(),
             multiview_mode: 0,
             multiview_flags: 0,
             chroma_site: 0,
@@ -274,27 +273,27 @@ impl VideoInfoRaw {
     }
 
     pub fn set_size(&mut self, size: Rectangle) {
-        self.0.size = size;
+        self.0.size = size.as_raw();
     }
 
     pub fn size(self) -> Rectangle {
-        self.0.size
+        Rectangle::from_raw(self.0.size)
     }
 
     pub fn set_framerate(&mut self, framerate: Fraction) {
-        self.0.framerate = framerate;
+        self.0.framerate = framerate.as_raw();
     }
 
     pub fn framerate(self) -> Fraction {
-        self.0.framerate
+        Fraction::from_raw(self.0.framerate)
     }
 
     pub fn set_max_framerate(&mut self, max_framerate: Fraction) {
-        self.0.max_framerate = max_framerate;
+        self.0.max_framerate = max_framerate.as_raw();
     }
 
     pub fn max_framerate(self) -> Fraction {
-        self.0.max_framerate
+        Fraction::from_raw(self.0.max_framerate)
     }
 
     pub fn set_views(&mut self, views: u32) {
@@ -314,11 +313,11 @@ impl VideoInfoRaw {
     }
 
     pub fn set_pixel_aspect_ratio(&mut self, pixel_aspect_ratio: Fraction) {
-        self.0.pixel_aspect_ratio = pixel_aspect_ratio;
+        self.0.pixel_aspect_ratio = pixel_aspect_ratio.as_raw();
     }
 
     pub fn pixel_aspect_ratio(self) -> Fraction {
-        self.0.pixel_aspect_ratio
+        Fraction::from_raw(self.0.pixel_aspect_ratio)
     }
 
     pub fn set_multiview_mode(&mut self, multiview_mode: i32) {
@@ -380,7 +379,7 @@ impl VideoInfoRaw {
     /// helper function to parse format properties type
     pub fn parse(&mut self, format: &crate::pod::Pod) -> Result<SpaSuccess, Error> {
         let res = unsafe { spa_sys::spa_format_video_raw_parse(format.as_raw_ptr(), &mut self.0) };
-        SpaResult::from_c(res).into_result()
+        crate::spa_result!(res)
     }
 
     /// Obtain a [`VideoInfoRaw`] from a raw `spa_video_info_raw` variant.
@@ -400,6 +399,60 @@ impl Default for VideoInfoRaw {
     }
 }
 
+impl From<VideoInfoRaw> for Vec<Property> {
+    fn from(value: VideoInfoRaw) -> Self {
+        let mut props = Vec::with_capacity(6);
+        props.push(Property::new(
+            spa_sys::SPA_FORMAT_mediaType,
+            Value::Id(utils::Id(spa_sys::SPA_MEDIA_TYPE_video)),
+        ));
+        props.push(Property::new(
+            spa_sys::SPA_FORMAT_mediaSubtype,
+            Value::Id(utils::Id(spa_sys::SPA_MEDIA_SUBTYPE_raw)),
+        ));
+
+        if value.format() != VideoFormat::Unknown {
+            props.push(Property::new(
+                spa_sys::SPA_FORMAT_VIDEO_format,
+                Value::Id(utils::Id(value.format().as_raw())),
+            ));
+        }
+
+        if value.modifier() != 0 {
+            // The modifier has to be known by both sides for the buffers to be shared.
+            props.push(Property {
+                key: spa_sys::SPA_FORMAT_VIDEO_modifier,
+                flags: PropertyFlags::MANDATORY,
+                value: Value::Long(value.modifier() as i64),
+            });
+        }
+
+        let size = value.size();
+        if size.width != 0 && size.height != 0 {
+            props.push(Property::new(
+                spa_sys::SPA_FORMAT_VIDEO_size,
+                Value::Rectangle(size),
+            ));
+        }
+
+        if value.framerate().denom != 0 {
+            props.push(Property::new(
+                spa_sys::SPA_FORMAT_VIDEO_framerate,
+                Value::Fraction(value.framerate()),
+            ));
+        }
+
+        if value.max_framerate().denom != 0 {
+            props.push(Property::new(
+                spa_sys::SPA_FORMAT_VIDEO_maxFramerate,
+                Value::Fraction(value.max_framerate()),
+            ));
+        }
+
+        props
+    }
+}
+
 impl Debug for VideoInfoRaw {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
         #[cfg(feature = "v0_3_65")]
@@ -432,6 +485,46 @@ impl Debug for VideoInfoRaw {
 mod tests {
     use super::*;
 
+    #[test]
+    #[cfg_attr(miri, ignore)]
+    fn serialize_and_parse() {
+        use crate::{
+            param::ParamType,
+            pod::{serialize::PodSerializer, Object, Pod},
+            utils::SpaTypes,
+        };
+
+        for format in [
+            VideoFormat::RGBA,
+            VideoFormat::BGRx,
+            VideoFormat::YUY2,
+            VideoFormat::NV12,
+        ] {
+            let mut info = VideoInfoRaw::new();
+            info.set_format(format);
+            info.set_modifier(0x0100_0000_0000_0001);
+            info.set_size(Rectangle {
+                width: 1280,
+                height: 720,
+            });
+            info.set_framerate(Fraction { num: 30, denom: 1 });
+
+            let object = Object {
+                type_: SpaTypes::ObjectParamFormat.as_raw(),
+                id: ParamType::EnumFormat.as_raw(),
+                properties: info.into(),
+            };
+            let bytes = PodSerializer::serialize_to_buf(&Value::Object(object)).unwrap();
+
+            let mut parsed = VideoInfoRaw::new();
+            parsed.parse(Pod::from_bytes(&bytes).unwrap()).unwrap();
+            assert_eq!(parsed.format(), format);
+            assert_eq!(parsed.modifier(), info.modifier());
+            assert_eq!(parsed.size(), info.size());
+            assert_eq!(parsed.framerate(), info.framerate());
+        }
+    }
+
     #[test]
     #[cfg_attr(miri, ignore)]
     fn debug_format() {
@@ -0,0 +1,157 @@
+// Copyright The pipewire-rs Contributors.
+// SPDX-License-Identifier: MIT
+
+use std::io::{self, Seek, SeekFrom, Write};
+
+use super::Pod;
+
+/// An owned buffer of bytes, aligned on 8 bytes as required by [`Pod::from_bytes`].
+///
+/// A `Vec<u8>` only guarantees the alignment of `u8`, so serializing a pod into one
+/// may produce bytes that can not be viewed as a [`Pod`].
+/// `PodBuf` instead implements [`Write`] and [`Seek`] on top of `u64` storage,
+/// so it can be used as the output of a [`PodSerializer`](super::serialize::PodSerializer):
+/// ```
+/// use libspa::pod::{serialize::PodSerializer, PodBuf, Value};
+///
+/// let buf = PodSerializer::serialize_to_buf(&Value::Int(1)).unwrap();
+/// let pod = buf.as_pod().unwrap();
+/// assert!(pod.is_int());
+/// ```
+///
+/// It dereferences to the written bytes.
+#[derive(Clone, Default)]
+pub struct PodBuf {
+    data: Vec<u64>,
+    len: usize,
+    pos: usize,
+}
+
+impl PodBuf {
+    /// Create an empty buffer.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Create a buffer holding a copy of `bytes`.
+    pub fn from_bytes(bytes: &[u8]) -> Self {
+        let mut buf = Self::new();
+        buf.resize(bytes.len());
+        buf.as_bytes_mut().copy_from_slice(bytes);
+        buf
+    }
+
+    /// The bytes written to the buffer.
+    pub fn as_bytes(&self) -> &[u8] {
+        // SAFETY: `data` holds at least `len` initialized bytes, and a `u64` can be viewed as bytes.
+        unsafe { std::slice::from_raw_parts(self.data.as_ptr().cast(), self.len) }
+    }
+
+    fn as_bytes_mut(&mut self) -> &mut [u8] {
+        // SAFETY: see `as_bytes`.
+        unsafe { std::slice::from_raw_parts_mut(self.data.as_mut_ptr().cast(), self.len) }
+    }
+
+    /// View the content of the buffer as a pod.
+    ///
+    /// Returns `None` if it does not hold a complete pod, see [`Pod::from_bytes`].
+    pub fn as_pod(&self) -> Option<&Pod> {
+        Pod::from_bytes(self.as_bytes())
+    }
+
+    /// Get the bytes written to the buffer as a `Vec<u8>`, which is not aligned.
+    pub fn into_vec(self) -> Vec<u8> {
+        self.as_bytes().to_vec()
+    }
+
+    fn resize(&mut self, len: usize) {
+        self.data.resize(len.div_ceil(8), 0);
+        self.len = len;
+    }
+}
+
+impl std::ops::Deref for PodBuf {
+    type Target = [u8];
+
+    fn deref(&self) -> &[u8] {
+        self.as_bytes()
+    }
+}
+
+impl AsRef<[u8]> for PodBuf {
+    fn as_ref(&self) -> &[u8] {
+        self.as_bytes()
+    }
+}
+
+impl PartialEq for PodBuf {
+    fn eq(&self, other: &Self) -> bool {
+        self.as_bytes() == other.as_bytes()
+    }
+}
+
+impl Eq for PodBuf {}
+
+impl std::fmt::Debug for PodBuf {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_tuple("PodBuf").field(&self.as_bytes()).finish()
+    }
+}
+
+impl Write for PodBuf {
+    fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+        let end = self
+            .pos
+            .checked_add(buf.len())
+            .ok_or_else(|| io::Error::from(io::ErrorKind::InvalidInput))?;
+        if end > self.len {
+            self.resize(end);
+        }
+
+        let pos = self.pos;
+        self.as_bytes_mut()[pos..end].copy_from_slice(buf);
+        self.pos = end;
+
+        Ok(buf.len())
+    }
+
+    fn flush(&mut self) -> io::Result<()> {
+        Ok(())
+    }
+}
+
+impl Seek for PodBuf {
+    fn seek(&mut self, pos: SeekFrom) -> io::Result<u64> {
+        let pos = match pos {
+            SeekFrom::Start(offset) => usize::try_from(offset).ok(),
+            SeekFrom::End(offset) => self.len.checked_add_signed(offset as isize),
+            SeekFrom::Current(offset) => self.pos.checked_add_signed(offset as isize),
+        }
+        .ok_or_else(|| io::Error::from(io::ErrorKind::InvalidInput))?;
+        // Like a `Cursor`, seeking past the end is allowed, and the gap is zero-filled on write.
+        self.pos = pos;
+
+        Ok(pos as u64)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn write_and_seek() {
+        let mut buf = PodBuf::new();
+        buf.write_all(&[1, 2, 3]).unwrap();
+        assert_eq!(buf.stream_position().unwrap(), 3);
+        buf.seek(SeekFrom::Start(1)).unwrap();
+        buf.write_all(&[4]).unwrap();
+        buf.seek(SeekFrom::End(2)).unwrap();
+        buf.write_all(&[5]).unwrap();
+
+        assert_eq!(&*buf, &[1, 4, 3, 0, 0, 5]);
+        assert_eq!(buf.as_ptr().align_offset(8), 0);
+        assert!(buf.seek(SeekFrom::Current(-10)).is_err());
+        assert_eq!(PodBuf::from_bytes(&buf), buf);
+    }
+}
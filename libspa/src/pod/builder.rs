@@ -17,11 +17,32 @@ struct BuilderInner<'d> {
     data: &'d mut Vec<u8>,
 }
 
+/// A low-level incremental pod builder, wrapping `spa_pod_builder`.
+///
+/// Values are appended to a `Vec<u8>`, which grows as needed.
+/// Container pods, such as structs or objects, are opened with one of the `open_*` methods,
+/// after which the values added go into the container until it is closed with [`Builder::close`]:
+/// ```
+/// use libspa::pod::builder::Builder;
+///
+/// let mut data = Vec::new();
+/// let mut builder = Builder::new(&mut data);
+/// builder.open_struct()?;
+/// builder.add_int(1)?;
+/// builder.add_string("foo")?;
+/// builder.close()?;
+/// # Ok::<(), nix::errno::Errno>(())
+/// ```
+///
+/// For pods known at compile time, the [`builder_add!`] macro is more convenient.
 pub struct Builder<'d> {
     // Keep the actual state in a box, so that
     // we can be sure that it does not move while the builder is in use
     // This lets us access it via pointer in the overflow callback
     inner: Box<BuilderInner<'d>>,
+    // Frames opened with the `open_*` methods, innermost last.
+    // They are heap allocated, as the builder keeps pointers to them until they are closed.
+    frames: Vec<*mut spa_sys::spa_pod_frame>,
 }
 
 impl<'d> Builder<'d> {
@@ -69,7 +90,10 @@ impl<'d> Builder<'d> {
                 std::ptr::addr_of!(*inner).cast::<c_void>().cast_mut(),
             );
 
-            Self { inner }
+            Self {
+                inner,
+                frames: Vec::new(),
+            }
         }
     }
 
@@ -482,6 +506,100 @@ impl<'d> Builder<'d> {
                 .unwrap()
         }
     }
+
+    fn open_with(
+        &mut self,
+        push: impl FnOnce(*mut spa_sys::spa_pod_builder, *mut spa_sys::spa_pod_frame) -> c_int,
+    ) -> Result<(), Errno> {
+        // SAFETY: zero is a valid value for all the integer and pointer fields of a frame.
+        let frame = Box::into_raw(Box::new(unsafe {
+            MaybeUninit::<spa_sys::spa_pod_frame>::zeroed().assume_init()
+        }));
+
+        let res = push(self.as_raw_ptr(), frame);
+        // The builder links the frame even if writing the header of the pod failed,
+        // so it has to stay alive until it is closed.
+        self.frames.push(frame);
+
+        if res >= 0 {
+            Ok(())
+        } else {
+            Err(Errno::from_i32(-res))
+        }
+    }
+
+    /// Open a struct pod, to which the following values are added as fields.
+    pub fn open_struct(&mut self) -> Result<(), Errno> {
+        self.open_with(|builder, frame| unsafe {
+            spa_sys::spa_pod_builder_push_struct(builder, frame)
+        })
+    }
+
+    /// Open an object pod of type `type_` and id `id`.
+    ///
+    /// Each property is added with [`Builder::add_prop`] followed by its value.
+    pub fn open_object(&mut self, type_: u32, id: u32) -> Result<(), Errno> {
+        self.open_with(|builder, frame| unsafe {
+            spa_sys::spa_pod_builder_push_object(builder, frame, type_, id)
+        })
+    }
+
+    /// Open an array pod.
+    ///
+    /// The type of the array is the type of the first value added, and all values have to be
+    /// of the same type.
+    pub fn open_array(&mut self) -> Result<(), Errno> {
+        self.open_with(|builder, frame| unsafe {
+            spa_sys::spa_pod_builder_push_array(builder, frame)
+        })
+    }
+
+    /// Open a choice pod of type `type_`, such as [`SPA_CHOICE_Range`](crate::sys::SPA_CHOICE_Range).
+    ///
+    /// The default value is added first, followed by the alternatives, all of the same type.
+    pub fn open_choice(&mut self, type_: u32, flags: u32) -> Result<(), Errno> {
+        self.open_with(|builder, frame| unsafe {
+            spa_sys::spa_pod_builder_push_choice(builder, frame, type_, flags)
+        })
+    }
+
+    /// Open a sequence pod with offsets in `unit`.
+    ///
+    /// Each control is added with [`Builder::add_control`] followed by its value.
+    pub fn open_sequence(&mut self, unit: u32) -> Result<(), Errno> {
+        self.open_with(|builder, frame| unsafe {
+            spa_sys::spa_pod_builder_push_sequence(builder, frame, unit)
+        })
+    }
+
+    /// Close the innermost pod opened with one of the `open_*` methods.
+    ///
+    /// Any frame pushed with the unsafe `push_*` methods since then has to be popped first.
+    /// Returns [`Errno::EINVAL`] if no pod is open.
+    pub fn close(&mut self) -> Result<(), Errno> {
+        let frame = self.frames.pop().ok_or(Errno::EINVAL)?;
+
+        unsafe {
+            spa_sys::spa_pod_builder_pop(self.as_raw_ptr(), frame);
+            drop(Box::from_raw(frame));
+        }
+
+        Ok(())
+    }
+
+    /// The number of pods opened with one of the `open_*` methods that are not closed yet.
+    pub fn depth(&self) -> usize {
+        self.frames.len()
+    }
+}
+
+impl Drop for Builder<'_> {
+    fn drop(&mut self) {
+        // Pods still open are left with the size they had so far.
+        for frame in self.frames.drain(..) {
+            unsafe { drop(Box::from_raw(frame)) };
+        }
+    }
 }
 
 /// Convenience macro to build a pod from values using a spa pod builder.
@@ -502,6 +620,12 @@ impl<'d> Builder<'d> {
 /// builder_add!(<&mut libspa::pod::builder::Builder>, Fd(<i64>));
 /// builder_add!(<&mut libspa::pod::builder::Builder>, Rectangle(<libspa::utils::Rectangle>));
 /// builder_add!(<&mut libspa::pod::builder::Builder>, Fraction(<libspa::utils::Fraction>));
+/// // A choice of values of any of the types above, with the default value first.
+/// builder_add!(<&mut libspa::pod::builder::Builder>, Choice(None, Int(<value>)));
+/// builder_add!(<&mut libspa::pod::builder::Builder>, Choice(Range, Int(<default>, <min>, <max>)));
+/// builder_add!(<&mut libspa::pod::builder::Builder>, Choice(Step, Int(<default>, <min>, <max>, <step>)));
+/// builder_add!(<&mut libspa::pod::builder::Builder>, Choice(Enum, Int(<default>, <alternative>, ...)));
+/// builder_add!(<&mut libspa::pod::builder::Builder>, Choice(Flags, Int(<default>, <flag>, ...)));
 /// builder_add!(<&mut libspa::pod::builder::Builder>,
 ///     Struct {
 ///         // 0 to n fields, e.g.:
@@ -572,7 +696,30 @@ macro_rules! __builder_add__ {
     ($builder:expr, Fraction($val:expr)) => {
         $crate::pod::builder::Builder::add_fraction($builder, $val)
     };
-    // TODO: Choice
+    (
+        $builder:expr,
+        Choice($choice_type:ident, $value_type:ident($( $value:expr ),+ $(,)?))
+    ) => {
+        'outer: {
+            let res = $crate::pod::builder::Builder::open_choice(
+                $builder,
+                $crate::__builder_choice_type__!($choice_type),
+                0,
+            );
+            if res.is_err() {
+                break 'outer res;
+            }
+
+            $(
+                let res = $crate::__builder_add__!($builder, $value_type($value));
+                if res.is_err() {
+                    break 'outer res;
+                }
+            )+
+
+            $crate::pod::builder::Builder::close($builder)
+        }
+    };
     (
         $builder:expr,
         Struct {
@@ -580,8 +727,7 @@ macro_rules! __builder_add__ {
         }
     ) => {
         'outer: {
-            let mut frame: ::std::mem::MaybeUninit<$crate::sys::spa_pod_frame> = ::std::mem::MaybeUninit::uninit();
-            let res = unsafe { $crate::pod::builder::Builder::push_struct($builder, &mut frame) };
+            let res = $crate::pod::builder::Builder::open_struct($builder);
             if res.is_err() {
                 break 'outer res;
             }
@@ -593,9 +739,7 @@ macro_rules! __builder_add__ {
                 }
             )*
 
-            unsafe { $crate::pod::builder::Builder::pop($builder, frame.assume_init_mut()) }
-
-            Ok(())
+            $crate::pod::builder::Builder::close($builder)
         }
     };
     (
@@ -605,8 +749,7 @@ macro_rules! __builder_add__ {
         }
     ) => {
         'outer: {
-            let mut frame: ::std::mem::MaybeUninit<$crate::sys::spa_pod_frame> = ::std::mem::MaybeUninit::uninit();
-            let res = unsafe { $crate::pod::builder::Builder::push_object($builder, &mut frame, $type_, $id) };
+            let res = $crate::pod::builder::Builder::open_object($builder, $type_, $id);
             if res.is_err() {
                 break 'outer res;
             }
@@ -622,9 +765,7 @@ macro_rules! __builder_add__ {
                 }
             )*
 
-            unsafe { $crate::pod::builder::Builder::pop($builder, frame.assume_init_mut()) }
-
-            Ok(())
+            $crate::pod::builder::Builder::close($builder)
         }
     };
     // TODO: Sequence
@@ -632,6 +773,26 @@ macro_rules! __builder_add__ {
 }
 pub use __builder_add__ as builder_add;
 
+#[doc(hidden)]
+#[macro_export]
+macro_rules! __builder_choice_type__ {
+    (None) => {
+        $crate::sys::SPA_CHOICE_None
+    };
+    (Range) => {
+        $crate::sys::SPA_CHOICE_Range
+    };
+    (Step) => {
+        $crate::sys::SPA_CHOICE_Step
+    };
+    (Enum) => {
+        $crate::sys::SPA_CHOICE_Enum
+    };
+    (Flags) => {
+        $crate::sys::SPA_CHOICE_Flags
+    };
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -710,6 +871,49 @@ mod tests {
         assert!(res.is_ok());
     }
 
+    #[test]
+    #[cfg_attr(miri, ignore)]
+    fn build_choice() {
+        use crate::{
+            pod::{deserialize::PodDeserializer, ChoiceValue, Value},
+            utils::{Choice, ChoiceEnum, ChoiceFlags},
+        };
+
+        let mut data = Vec::new();
+        let mut builder = Builder::new(&mut data);
+        let res = builder_add!(
+            &mut builder,
+            Struct {
+                Choice(Range, Int(48000, 44100, 192000)),
+                Choice(Enum, Int(2, 1, 2)),
+            }
+        );
+
+        assert!(res.is_ok());
+
+        let (_, value) = PodDeserializer::deserialize_any_from(&data).unwrap();
+        assert_eq!(
+            value,
+            Value::Struct(vec![
+                Value::Choice(ChoiceValue::Int(Choice(
+                    ChoiceFlags::empty(),
+                    ChoiceEnum::Range {
+                        default: 48000,
+                        min: 44100,
+                        max: 192000
+                    }
+                ))),
+                Value::Choice(ChoiceValue::Int(Choice(
+                    ChoiceFlags::empty(),
+                    ChoiceEnum::Enum {
+                        default: 2,
+                        alternatives: vec![1, 2]
+                    }
+                ))),
+            ])
+        );
+    }
+
     #[test]
     #[cfg_attr(miri, ignore)]
     fn build_empty_object() {
@@ -751,4 +955,43 @@ mod tests {
 
         assert!(res.is_ok());
     }
+
+    #[test]
+    #[cfg_attr(miri, ignore)]
+    fn build_incrementally() {
+        use crate::pod::{deserialize::PodDeserializer, Object, Property, Value, ValueArray};
+
+        let mut data = Vec::new();
+        let mut builder = Builder::new(&mut data);
+        builder.open_object(3, 7).unwrap();
+        builder.add_prop(1, 0).unwrap();
+        builder.add_float(0.5).unwrap();
+        builder.add_prop(2, 0).unwrap();
+        builder.open_array().unwrap();
+        for i in 0..3 {
+            builder.add_int(i).unwrap();
+        }
+        assert_eq!(builder.depth(), 2);
+        builder.close().unwrap();
+        builder.add_prop(3, 0).unwrap();
+        builder.add_string("foo").unwrap();
+        builder.close().unwrap();
+        assert_eq!(builder.depth(), 0);
+        assert_eq!(builder.close(), Err(Errno::EINVAL));
+        drop(builder);
+
+        let (_, value) = PodDeserializer::deserialize_any_from(&data).unwrap();
+        assert_eq!(
+            value,
+            Value::Object(Object {
+                type_: 3,
+                id: 7,
+                properties: vec![
+                    Property::new(1, Value::Float(0.5)),
+                    Property::new(2, Value::ValueArray(ValueArray::Int(vec![0, 1, 2]))),
+                    Property::new(3, Value::String("foo".to_string())),
+                ],
+            })
+        );
+    }
 }
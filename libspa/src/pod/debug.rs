@@ -0,0 +1,299 @@
+// Copyright The pipewire-rs Contributors.
+// SPDX-License-Identifier: MIT
+
+use std::{
+    ffi::CStr,
+    fmt::{self, Write},
+};
+
+use super::{deserialize::PodDeserializer, ChoiceValue, Object, Value, ValueArray};
+use crate::utils::{Choice, ChoiceEnum, Id, SpaTypes};
+
+/// Render the pod in `bytes` as a human readable tree, for debugging.
+///
+/// Each value is printed with its type, one per line and indented by its depth.
+/// The names of object types, property keys and ids are resolved using the SPA type
+/// information where available, similar to `spa_debug_pod`:
+///
+/// ```text
+/// Object: type Spa:Pod:Object:Param:Format, id EnumFormat
+///   mediaType: Id 1 (audio)
+///   mediaSubtype: Id 1 (raw)
+///   format: Choice Enum Id
+///     default: Id 283 (F32LE)
+///     alternatives: [Id 283 (F32LE), Id 282 (S16LE)]
+/// ```
+///
+/// If `bytes` is not a valid pod, a short description of the error is returned instead.
+pub fn to_debug_string(bytes: &[u8]) -> String {
+    match PodDeserializer::deserialize_any_from(bytes) {
+        Ok((_, value)) => {
+            let mut out = String::new();
+            write_value(&mut out, &value, 0, std::ptr::null())
+                .expect("Writing to a String cannot fail");
+            out
+        }
+        Err(_) => String::from("<invalid pod>"),
+    }
+}
+
+/// Find the type information for `type_` in the null terminated `infos` table.
+fn find_info(
+    infos: *const spa_sys::spa_type_info,
+    type_: u32,
+) -> Option<&'static spa_sys::spa_type_info> {
+    if infos.is_null() {
+        return None;
+    }
+
+    unsafe { spa_sys::spa_debug_type_find(infos, type_).as_ref() }
+}
+
+/// Get the short name for `type_` in the `infos` table.
+fn short_name(infos: *const spa_sys::spa_type_info, type_: u32) -> Option<String> {
+    if infos.is_null() {
+        return None;
+    }
+
+    unsafe {
+        let name = spa_sys::spa_debug_type_find_short_name(infos, type_);
+        (!name.is_null()).then(|| CStr::from_ptr(name).to_string_lossy().into_owned())
+    }
+}
+
+fn write_indent(out: &mut String, indent: usize) -> fmt::Result {
+    write!(out, "{:width$}", "", width = indent * 2)
+}
+
+fn fmt_id(id: Id, names: *const spa_sys::spa_type_info) -> String {
+    match short_name(names, id.0) {
+        Some(name) => format!("Id {} ({})", id.0, name),
+        None => format!("Id {}", id.0),
+    }
+}
+
+/// Write `value` at the current position, followed by a newline.
+///
+/// `names` is the type information table used to resolve the names of [`Value::Id`] values.
+fn write_value(
+    out: &mut String,
+    value: &Value,
+    indent: usize,
+    names: *const spa_sys::spa_type_info,
+) -> fmt::Result {
+    match value {
+        Value::None => writeln!(out, "None"),
+        Value::Bool(v) => writeln!(out, "Bool {}", v),
+        Value::Id(id) => writeln!(out, "{}", fmt_id(*id, names)),
+        Value::Int(v) => writeln!(out, "Int {}", v),
+        Value::Long(v) => writeln!(out, "Long {}", v),
+        Value::Float(v) => writeln!(out, "Float {}", v),
+        Value::Double(v) => writeln!(out, "Double {}", v),
+        Value::String(v) => writeln!(out, "String {:?}", v),
+        Value::Bytes(v) => writeln!(out, "Bytes {:02x?}", v),
+        Value::Rectangle(v) => writeln!(out, "Rectangle {}x{}", v.width, v.height),
+        Value::Fraction(v) => writeln!(out, "Fraction {}/{}", v.num, v.denom),
+        Value::Fd(v) => writeln!(out, "Fd {}", v.0),
+        Value::Pointer(type_, ptr) => writeln!(out, "Pointer {} {:?}", type_, ptr),
+        Value::ValueArray(array) => writeln!(out, "Array {}", fmt_array(array, names)),
+        Value::Struct(fields) => {
+            writeln!(out, "Struct")?;
+            for field in fields {
+                write_indent(out, indent + 1)?;
+                write_value(out, field, indent + 1, names)?;
+            }
+            Ok(())
+        }
+        Value::Object(object) => write_object(out, object, indent),
+        Value::Choice(choice) => write_choice(out, choice, indent, names),
+    }
+}
+
+fn write_object(out: &mut String, object: &Object, indent: usize) -> fmt::Result {
+    let type_info = find_info(unsafe { spa_sys::spa_types }, object.type_);
+    let type_name = type_info
+        .filter(|info| !info.name.is_null())
+        .map(|info| unsafe { CStr::from_ptr(info.name).to_string_lossy().into_owned() })
+        .unwrap_or_else(|| object.type_.to_string());
+    let keys = type_info.map_or(std::ptr::null(), |info| info.values);
+
+    let is_param = (SpaTypes::ObjectParamPropInfo.as_raw()
+        ..=SpaTypes::ObjectParamProcessLatency.as_raw())
+        .contains(&object.type_)
+        && object.type_ != SpaTypes::ObjectProfiler.as_raw();
+    let id = if is_param {
+        short_name(unsafe { spa_sys::spa_type_param }, object.id)
+    } else {
+        None
+    }
+    .unwrap_or_else(|| object.id.to_string());
+
+    writeln!(out, "Object: type {}, id {}", type_name, id)?;
+
+    for prop in &object.properties {
+        let key_info = find_info(keys, prop.key);
+        let values = key_info.map_or(std::ptr::null(), |info| info.values);
+
+        write_indent(out, indent + 1)?;
+        match short_name(keys, prop.key) {
+            Some(name) => write!(out, "{}: ", name)?,
+            None => write!(out, "{}: ", prop.key)?,
+        }
+        write_value(out, &prop.value, indent + 1, values)?;
+    }
+
+    Ok(())
+}
+
+fn fmt_list<T>(values: &[T], fmt: impl Fn(&T) -> String) -> String {
+    let values: Vec<_> = values.iter().map(fmt).collect();
+    format!("[{}]", values.join(", "))
+}
+
+fn fmt_array(array: &ValueArray, names: *const spa_sys::spa_type_info) -> String {
+    match array {
+        ValueArray::None(v) => fmt_list(v, |_| String::from("None")),
+        ValueArray::Bool(v) => fmt_list(v, |v| format!("Bool {}", v)),
+        ValueArray::Id(v) => fmt_list(v, |v| fmt_id(*v, names)),
+        ValueArray::Int(v) => fmt_list(v, |v| format!("Int {}", v)),
+        ValueArray::Long(v) => fmt_list(v, |v| format!("Long {}", v)),
+        ValueArray::Float(v) => fmt_list(v, |v| format!("Float {}", v)),
+        ValueArray::Double(v) => fmt_list(v, |v| format!("Double {}", v)),
+        ValueArray::Rectangle(v) => fmt_list(v, |v| format!("Rectangle {}x{}", v.width, v.height)),
+        ValueArray::Fraction(v) => fmt_list(v, |v| format!("Fraction {}/{}", v.num, v.denom)),
+        ValueArray::Fd(v) => fmt_list(v, |v| format!("Fd {}", v.0)),
+    }
+}
+
+fn write_choice(
+    out: &mut String,
+    choice: &ChoiceValue,
+    indent: usize,
+    names: *const spa_sys::spa_type_info,
+) -> fmt::Result {
+    match choice {
+        ChoiceValue::Bool(c) => {
+            write_choice_enum(out, "Bool", c, indent, |v| format!("Bool {}", v))
+        }
+        ChoiceValue::Int(c) => write_choice_enum(out, "Int", c, indent, |v| format!("Int {}", v)),
+        ChoiceValue::Long(c) => {
+            write_choice_enum(out, "Long", c, indent, |v| format!("Long {}", v))
+        }
+        ChoiceValue::Float(c) => {
+            write_choice_enum(out, "Float", c, indent, |v| format!("Float {}", v))
+        }
+        ChoiceValue::Double(c) => {
+            write_choice_enum(out, "Double", c, indent, |v| format!("Double {}", v))
+        }
+        ChoiceValue::Id(c) => write_choice_enum(out, "Id", c, indent, |v| fmt_id(*v, names)),
+        ChoiceValue::Rectangle(c) => write_choice_enum(out, "Rectangle", c, indent, |v| {
+            format!("Rectangle {}x{}", v.width, v.height)
+        }),
+        ChoiceValue::Fraction(c) => write_choice_enum(out, "Fraction", c, indent, |v| {
+            format!("Fraction {}/{}", v.num, v.denom)
+        }),
+        ChoiceValue::Fd(c) => write_choice_enum(out, "Fd", c, indent, |v| format!("Fd {}", v.0)),
+    }
+}
+
+fn write_choice_enum<T: crate::pod::CanonicalFixedSizedPod>(
+    out: &mut String,
+    type_name: &str,
+    choice: &Choice<T>,
+    indent: usize,
+    fmt: impl Fn(&T) -> String,
+) -> fmt::Result {
+    let field = |out: &mut String, name: &str, value: String| -> fmt::Result {
+        write_indent(out, indent + 1)?;
+        writeln!(out, "{}: {}", name, value)
+    };
+
+    match &choice.1 {
+        ChoiceEnum::None(value) => {
+            writeln!(out, "Choice None {}", type_name)?;
+            field(out, "value", fmt(value))
+        }
+        ChoiceEnum::Range { default, min, max } => {
+            writeln!(out, "Choice Range {}", type_name)?;
+            field(out, "default", fmt(default))?;
+            field(out, "min", fmt(min))?;
+            field(out, "max", fmt(max))
+        }
+        ChoiceEnum::Step {
+            default,
+            min,
+            max,
+            step,
+        } => {
+            writeln!(out, "Choice Step {}", type_name)?;
+            field(out, "default", fmt(default))?;
+            field(out, "min", fmt(min))?;
+            field(out, "max", fmt(max))?;
+            field(out, "step", fmt(step))
+        }
+        ChoiceEnum::Enum {
+            default,
+            alternatives,
+        } => {
+            writeln!(out, "Choice Enum {}", type_name)?;
+            field(out, "default", fmt(default))?;
+            field(out, "alternatives", fmt_list(alternatives, &fmt))
+        }
+        ChoiceEnum::Flags { default, flags } => {
+            writeln!(out, "Choice Flags {}", type_name)?;
+            field(out, "default", fmt(default))?;
+            field(out, "flags", fmt_list(flags, &fmt))
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::pod::{serialize::PodSerializer, Property};
+
+    #[test]
+    #[cfg_attr(miri, ignore)]
+    fn debug_string() {
+        let object = Object {
+            type_: SpaTypes::ObjectParamFormat.as_raw(),
+            id: spa_sys::SPA_PARAM_EnumFormat,
+            properties: vec![
+                Property::new(
+                    spa_sys::SPA_FORMAT_mediaType,
+                    Value::Id(Id(spa_sys::SPA_MEDIA_TYPE_audio)),
+                ),
+                Property::new(spa_sys::SPA_FORMAT_AUDIO_channels, Value::Int(2)),
+                Property::new(
+                    spa_sys::SPA_FORMAT_AUDIO_rate,
+                    Value::Choice(ChoiceValue::Int(Choice(
+                        crate::utils::ChoiceFlags::empty(),
+                        ChoiceEnum::Range {
+                            default: 48000,
+                            min: 1,
+                            max: 384000,
+                        },
+                    ))),
+                ),
+            ],
+        };
+        let bytes =
+            PodSerializer::serialize(std::io::Cursor::new(Vec::new()), &Value::Object(object))
+                .unwrap()
+                .0
+                .into_inner();
+
+        assert_eq!(
+            to_debug_string(&bytes),
+            "Object: type Spa:Pod:Object:Param:Format, id EnumFormat
+  mediaType: Id 1 (audio)
+  channels: Int 2
+  rate: Choice Range Int
+    default: Int 48000
+    min: Int 1
+    max: Int 384000
+"
+        );
+        assert_eq!(to_debug_string(&[1, 2, 3]), "<invalid pod>");
+    }
+}
@@ -7,11 +7,17 @@
 //! The entire serialization and deserialization approach is inspired by and similar to the excellent `serde` crate,
 //! but is much more specialized to fit the SPA pod format.
 
+mod buf;
 pub mod builder;
+mod debug;
 pub mod deserialize;
 pub mod parser;
+pub mod sequence;
 pub mod serialize;
 
+pub use buf::PodBuf;
+pub use debug::to_debug_string;
+
 use std::{
     ffi::c_void,
     io::{Seek, Write},
@@ -106,26 +112,39 @@ impl Pod {
 
     /// Construct a pod from raw bytes.
     ///
-    /// The provided slice must be big enough to fit the entire pod including padding.
+    /// The provided slice must be big enough to fit the entire pod including padding,
+    /// and must start on an 8 byte boundary, as pods are 8 byte aligned.
+    /// Use a [`PodBuf`] to own bytes with that alignment, for example as the output of
+    /// [`PodSerializer::serialize_to_buf`](serialize::PodSerializer::serialize_to_buf).
+    ///
+    /// Returns `None` if these requirements are not met, so that malformed input is rejected
+    /// instead of being read out of bounds.
+    /// Only the header of the pod is checked, its body is validated when it is read.
     pub fn from_bytes(bytes: &[u8]) -> Option<&Self> {
         // Ensure bytes contains at least a readable pod header
         // that we can read the pods size from
 
         const HEADER_SIZE: usize = std::mem::size_of::<spa_sys::spa_pod>();
+        const POD_ALIGN: usize = 8;
 
         if bytes.len() < HEADER_SIZE {
             return None;
         }
 
+        if bytes.as_ptr().align_offset(POD_ALIGN) != 0 {
+            return None;
+        }
+
         let pod: *const spa_sys::spa_pod = bytes.as_ptr().cast();
 
         // `pod` now points to a valid pod header that we can read
-        let size: usize = unsafe { *pod }.size.try_into().unwrap();
+        let size: usize = unsafe { *pod }.size.try_into().ok()?;
 
         let padding = (8 - (size % 8)) % 8;
 
         // Now, ensure that `bytes` is big enough to fit the entire pod
-        if HEADER_SIZE + size + padding <= bytes.len() {
+        let total = HEADER_SIZE.checked_add(size)?.checked_add(padding)?;
+        if total <= bytes.len() {
             // Bytes is big enough to fit the entire header, body and padding.
             // We can safely convert this to a &Pod
             Some(unsafe { Self::from_raw(pod) })
@@ -351,7 +370,7 @@ impl Pod {
             let res = spa_sys::spa_pod_get_rectangle(self.as_raw_ptr(), rectangle.as_mut_ptr());
 
             if res >= 0 {
-                Ok(rectangle.assume_init())
+                Ok(rectangle.assume_init().into())
             } else {
                 Err(Errno::from_i32(-res))
             }
@@ -369,7 +388,7 @@ impl Pod {
             let res = spa_sys::spa_pod_get_fraction(self.as_raw_ptr(), fraction.as_mut_ptr());
 
             if res >= 0 {
-                Ok(fraction.assume_init())
+                Ok(fraction.assume_init().into())
             } else {
                 Err(Errno::from_i32(-res))
             }
@@ -9,7 +9,10 @@ use std::{
 
 use nix::errno::Errno;
 
-use crate::utils::{Fraction, Id, Rectangle};
+use crate::{
+    pod::{deserialize::PodDeserializer, ChoiceValue, Value},
+    utils::{Fraction, Id, Rectangle},
+};
 
 /// Low-level wrapper around `spa_pod_parser`.
 ///
@@ -280,7 +283,7 @@ impl<'d> Parser<'d> {
             let mut rect: MaybeUninit<spa_sys::spa_rectangle> = MaybeUninit::uninit();
             let res = spa_sys::spa_pod_parser_get_rectangle(self.as_raw_ptr(), rect.as_mut_ptr());
             if res >= 0 {
-                Ok(rect.assume_init())
+                Ok(rect.assume_init().into())
             } else {
                 Err(Errno::from_i32(-res))
             }
@@ -292,7 +295,7 @@ impl<'d> Parser<'d> {
             let mut frac: MaybeUninit<spa_sys::spa_fraction> = MaybeUninit::uninit();
             let res = spa_sys::spa_pod_parser_get_fraction(self.as_raw_ptr(), frac.as_mut_ptr());
             if res >= 0 {
-                Ok(frac.assume_init())
+                Ok(frac.assume_init().into())
             } else {
                 Err(Errno::from_i32(-res))
             }
@@ -316,6 +319,21 @@ impl<'d> Parser<'d> {
         }
     }
 
+    /// Get a choice pod, decoded into a [`ChoiceValue`].
+    ///
+    /// Returns [`Errno::EINVAL`] if the next pod is not a choice.
+    pub fn get_choice(&mut self) -> Result<ChoiceValue, Errno> {
+        let pod = self.get_pod()?;
+        if !pod.is_choice() {
+            return Err(Errno::EINVAL);
+        }
+
+        match PodDeserializer::deserialize_any_from(pod.as_bytes()) {
+            Ok((_, Value::Choice(choice))) => Ok(choice),
+            _ => Err(Errno::EINVAL),
+        }
+    }
+
     /// # Safety
     /// The provided frame must not be moved or destroyed before it is popped again.
     ///
@@ -515,6 +533,16 @@ macro_rules! __parser_get__ {
             res.map(|_| {})
         }
     };
+    ($parser:expr, Choice($val:expr)) => {
+        {
+            let val: &mut $crate::pod::ChoiceValue = $val;
+            let res = $crate::pod::parser::Parser::get_choice($parser);
+            if let Ok(choice) = res {
+                *val = choice;
+            }
+            res.map(|_| {})
+        }
+    };
     ($parser:expr, Struct { $( $field_type:tt $field:tt ),* $(,)? }) => {
         'outer: {
             let mut frame: ::std::mem::MaybeUninit<$crate::sys::spa_pod_frame> = ::std::mem::MaybeUninit::uninit();
@@ -696,4 +724,43 @@ mod tests {
             }
         );
     }
+
+    #[test]
+    #[cfg_attr(miri, ignore)]
+    fn parse_choice() {
+        use crate::{
+            pod::{
+                builder::{builder_add, Builder},
+                ChoiceValue,
+            },
+            utils::{Choice, ChoiceEnum, ChoiceFlags},
+        };
+
+        let mut pod = Vec::new();
+        let mut builder = Builder::new(&mut pod);
+        builder_add!(&mut builder, Choice(Step, Int(4, 2, 8, 2))).unwrap();
+
+        let mut parser = Parser::new(&pod);
+        let mut choice = ChoiceValue::Bool(Choice(ChoiceFlags::empty(), ChoiceEnum::None(false)));
+
+        let res = parser_get!(&mut parser, Choice(&mut choice));
+
+        assert!(res.is_ok());
+        assert_eq!(
+            choice,
+            ChoiceValue::Int(Choice(
+                ChoiceFlags::empty(),
+                ChoiceEnum::Step {
+                    default: 4,
+                    min: 2,
+                    max: 8,
+                    step: 2
+                }
+            ))
+        );
+
+        let mut parser = Parser::new(&pod);
+        let mut int = 0;
+        assert!(parser_get!(&mut parser, Int(&mut int)).is_err());
+    }
 }
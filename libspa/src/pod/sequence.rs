@@ -0,0 +1,329 @@
+// Copyright The pipewire-rs Contributors.
+// SPDX-License-Identifier: MIT
+
+//! Reading and writing sequence pods, which carry timed control events such as MIDI.
+//!
+//! Streams with the `application/control` media type, such as MIDI streams, carry a single
+//! sequence pod in the data of each buffer. A sequence is laid out as follows,
+//! with all integers in native endianness:
+//!
+//! | Field    | Size | Description |
+//! |----------|------|-------------|
+//! | `size`   | 4    | The size of the pod, excluding this header |
+//! | `type`   | 4    | [`SpaTypes::Sequence`] |
+//! | `unit`   | 4    | The unit of the offsets of the controls, usually 0 for samples |
+//! | `pad`    | 4    | Padding, 0 |
+//! | controls |      | The controls, each padded to 8 bytes |
+//!
+//! And each control:
+//!
+//! | Field    | Size | Description |
+//! |----------|------|-------------|
+//! | `offset` | 4    | The offset of the event in the current cycle, in `unit` |
+//! | `type`   | 4    | The [`ControlType`] of the event |
+//! | `value`  |      | The value of the event, as a pod. For MIDI events, a `Bytes` pod with the raw MIDI message |
+//!
+//! Controls are sorted by offset.
+//!
+//! To read MIDI events from a buffer, parse the valid region of its data, as given by its chunk:
+//! ```no_run
+//! # fn read(data: &[u8], offset: usize, size: usize) -> Result<(), nix::errno::Errno> {
+//! use libspa::pod::sequence::Sequence;
+//!
+//! let sequence = Sequence::from_bytes(&data[offset..offset + size])?;
+//! for control in &sequence {
+//!     if let Some(midi) = control.midi() {
+//!         println!("{}: {:02x?}", control.offset, midi);
+//!     }
+//! }
+//! # Ok(())
+//! # }
+//! ```
+//!
+//! And to write events, use a [`SequenceWriter`] on the data of the buffer, then set
+//! the size of the chunk to the length returned by [`SequenceWriter::finish`].
+
+use nix::errno::Errno;
+
+use crate::utils::SpaTypes;
+
+const POD_HEADER_SIZE: usize = 8;
+const SEQUENCE_HEADER_SIZE: usize = POD_HEADER_SIZE + 8;
+const CONTROL_HEADER_SIZE: usize = 8;
+
+/// The type of a control in a sequence.
+#[derive(Copy, Clone, PartialEq, Eq, Debug)]
+pub struct ControlType(spa_sys::spa_control_type);
+
+#[allow(non_upper_case_globals)]
+impl ControlType {
+    pub const Invalid: Self = Self(spa_sys::SPA_CONTROL_Invalid);
+    /// The value is an object pod with properties.
+    pub const Properties: Self = Self(spa_sys::SPA_CONTROL_Properties);
+    /// The value is a bytes pod with a raw MIDI message.
+    pub const Midi: Self = Self(spa_sys::SPA_CONTROL_Midi);
+    /// The value is a bytes pod with an OSC packet.
+    pub const OSC: Self = Self(spa_sys::SPA_CONTROL_OSC);
+
+    pub fn from_raw(raw: spa_sys::spa_control_type) -> Self {
+        Self(raw)
+    }
+
+    pub fn as_raw(&self) -> spa_sys::spa_control_type {
+        self.0
+    }
+}
+
+fn read_u32(bytes: &[u8], at: usize) -> Option<u32> {
+    let bytes = bytes.get(at..at.checked_add(4)?)?;
+    Some(u32::from_ne_bytes(bytes.try_into().unwrap()))
+}
+
+fn round_up_8(len: usize) -> usize {
+    (len + 7) & !7
+}
+
+/// A sequence pod, borrowed from raw bytes.
+#[derive(Debug, Clone, Copy)]
+pub struct Sequence<'a> {
+    unit: u32,
+    controls: &'a [u8],
+}
+
+impl<'a> Sequence<'a> {
+    /// Parse a sequence from the raw bytes of a sequence pod.
+    ///
+    /// Returns [`Errno::EINVAL`] if the bytes do not start with a sequence pod,
+    /// or are too short for the size in its header.
+    pub fn from_bytes(bytes: &'a [u8]) -> Result<Self, Errno> {
+        let size = read_u32(bytes, 0).ok_or(Errno::EINVAL)? as usize;
+        let type_ = read_u32(bytes, 4).ok_or(Errno::EINVAL)?;
+        let unit = read_u32(bytes, 8).ok_or(Errno::EINVAL)?;
+
+        if type_ != SpaTypes::Sequence.as_raw() || size < SEQUENCE_HEADER_SIZE - POD_HEADER_SIZE {
+            return Err(Errno::EINVAL);
+        }
+        let end = POD_HEADER_SIZE.checked_add(size).ok_or(Errno::EINVAL)?;
+        let controls = bytes.get(SEQUENCE_HEADER_SIZE..end).ok_or(Errno::EINVAL)?;
+
+        Ok(Self { unit, controls })
+    }
+
+    /// Parse a sequence from a pod.
+    pub fn from_pod(pod: &'a crate::pod::Pod) -> Result<Self, Errno> {
+        Self::from_bytes(pod.as_bytes())
+    }
+
+    /// The unit of the offsets of the controls, usually 0 for samples.
+    pub fn unit(&self) -> u32 {
+        self.unit
+    }
+
+    /// Iterate over the controls of the sequence.
+    ///
+    /// Iteration stops at the first malformed control.
+    pub fn iter(&self) -> ControlIter<'a> {
+        ControlIter {
+            data: self.controls,
+        }
+    }
+}
+
+impl<'a> IntoIterator for &Sequence<'a> {
+    type Item = Control<'a>;
+    type IntoIter = ControlIter<'a>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.iter()
+    }
+}
+
+/// A control in a [`Sequence`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Control<'a> {
+    /// The offset of the control in the current cycle, in the [`unit`](Sequence::unit) of the sequence.
+    pub offset: u32,
+    /// The type of the control.
+    pub type_: ControlType,
+    /// The type of the value pod.
+    pub value_type: SpaTypes,
+    /// The body of the value pod.
+    pub value: &'a [u8],
+}
+
+impl<'a> Control<'a> {
+    /// Get the raw MIDI message of the control, if it is a MIDI event.
+    pub fn midi(&self) -> Option<&'a [u8]> {
+        (self.type_ == ControlType::Midi && self.value_type == SpaTypes::Bytes)
+            .then_some(self.value)
+    }
+}
+
+/// An iterator over the controls of a [`Sequence`], see [`Sequence::iter`].
+#[derive(Debug, Clone)]
+pub struct ControlIter<'a> {
+    data: &'a [u8],
+}
+
+impl<'a> Iterator for ControlIter<'a> {
+    type Item = Control<'a>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let offset = read_u32(self.data, 0)?;
+        let type_ = read_u32(self.data, 4)?;
+        let value_size = read_u32(self.data, CONTROL_HEADER_SIZE)? as usize;
+        let value_type = read_u32(self.data, CONTROL_HEADER_SIZE + 4)?;
+
+        let start = CONTROL_HEADER_SIZE + POD_HEADER_SIZE;
+        let end = start.checked_add(value_size)?;
+        let Some(value) = self.data.get(start..end) else {
+            self.data = &[];
+            return None;
+        };
+
+        // The padding of the last control may be missing.
+        self.data = self.data.get(round_up_8(end)..).unwrap_or_default();
+
+        Some(Control {
+            offset,
+            type_: ControlType::from_raw(type_),
+            value_type: SpaTypes::from_raw(value_type),
+            value,
+        })
+    }
+}
+
+/// Writes a sequence pod into a byte buffer, such as the data of a buffer of a MIDI stream.
+///
+/// Controls have to be pushed in increasing order of offset.
+#[derive(Debug)]
+pub struct SequenceWriter<'a> {
+    buffer: &'a mut [u8],
+    len: usize,
+}
+
+impl<'a> SequenceWriter<'a> {
+    /// Start writing a sequence with the given `unit` into `buffer`.
+    ///
+    /// Returns [`Errno::ENOSPC`] if `buffer` is too small for the header of the sequence.
+    pub fn new(buffer: &'a mut [u8], unit: u32) -> Result<Self, Errno> {
+        if buffer.len() < SEQUENCE_HEADER_SIZE {
+            return Err(Errno::ENOSPC);
+        }
+
+        let mut writer = Self { buffer, len: 0 };
+        writer.write_u32(0);
+        writer.write_u32(SpaTypes::Sequence.as_raw());
+        writer.write_u32(unit);
+        writer.write_u32(0);
+
+        Ok(writer)
+    }
+
+    fn write_u32(&mut self, value: u32) {
+        self.buffer[self.len..self.len + 4].copy_from_slice(&value.to_ne_bytes());
+        self.len += 4;
+    }
+
+    /// Append a control with a value pod of type `value_type` and body `value`.
+    ///
+    /// Returns [`Errno::ENOSPC`] if the control does not fit in the remaining space,
+    /// in which case nothing is written.
+    pub fn push(
+        &mut self,
+        offset: u32,
+        type_: ControlType,
+        value_type: SpaTypes,
+        value: &[u8],
+    ) -> Result<(), Errno> {
+        let value_size = u32::try_from(value.len()).map_err(|_| Errno::ENOSPC)?;
+        let control_len = round_up_8(CONTROL_HEADER_SIZE + POD_HEADER_SIZE + value.len());
+        if self.buffer.len() - self.len < control_len {
+            return Err(Errno::ENOSPC);
+        }
+
+        let end = self.len + control_len;
+        self.write_u32(offset);
+        self.write_u32(type_.as_raw());
+        self.write_u32(value_size);
+        self.write_u32(value_type.as_raw());
+        self.buffer[self.len..self.len + value.len()].copy_from_slice(value);
+        self.buffer[self.len + value.len()..end].fill(0);
+        self.len = end;
+
+        Ok(())
+    }
+
+    /// Append a MIDI event with the raw MIDI message `event`.
+    pub fn push_midi(&mut self, offset: u32, event: &[u8]) -> Result<(), Errno> {
+        self.push(offset, ControlType::Midi, SpaTypes::Bytes, event)
+    }
+
+    /// Finish the sequence by writing its size, and return the number of bytes written.
+    pub fn finish(self) -> usize {
+        let size = (self.len - POD_HEADER_SIZE) as u32;
+        self.buffer[..4].copy_from_slice(&size.to_ne_bytes());
+        self.len
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::pod::Pod;
+
+    #[test]
+    #[cfg_attr(miri, ignore)]
+    fn write_and_read_midi() {
+        let note_on = [0x90, 60, 100];
+        let note_off = [0x80, 60, 0];
+
+        let mut aligned = [0u64; 16];
+        let buffer: &mut [u8] = unsafe {
+            std::slice::from_raw_parts_mut(
+                aligned.as_mut_ptr().cast(),
+                std::mem::size_of_val(&aligned),
+            )
+        };
+
+        let mut writer = SequenceWriter::new(buffer, 0).unwrap();
+        writer.push_midi(0, &note_on).unwrap();
+        writer.push_midi(128, &note_off).unwrap();
+        let len = writer.finish();
+        // Header and two controls of 8 + 8 + 3 bytes, padded to 24.
+        assert_eq!(len, 16 + 2 * 24);
+
+        let pod = Pod::from_bytes(&buffer[..len]).unwrap();
+        assert!(pod.is_sequence());
+
+        let sequence = Sequence::from_pod(pod).unwrap();
+        assert_eq!(sequence.unit(), 0);
+        let events: Vec<_> = sequence
+            .iter()
+            .map(|control| (control.offset, control.midi().unwrap()))
+            .collect();
+        assert_eq!(events, [(0, &note_on[..]), (128, &note_off[..])]);
+    }
+
+    #[test]
+    fn malformed() {
+        assert_eq!(Sequence::from_bytes(&[0; 4]).unwrap_err(), Errno::EINVAL);
+
+        let mut buffer = [0u8; 40];
+        let mut writer = SequenceWriter::new(&mut buffer, 0).unwrap();
+        assert_eq!(writer.push_midi(0, &[0; 9]), Err(Errno::ENOSPC));
+        writer.push_midi(0, &[0x90, 60, 100]).unwrap();
+        let len = writer.finish();
+
+        // Truncated sequence.
+        assert_eq!(
+            Sequence::from_bytes(&buffer[..len - 8]).unwrap_err(),
+            Errno::EINVAL
+        );
+
+        // A control claiming a value larger than the sequence is skipped.
+        buffer[16 + 8..16 + 12].copy_from_slice(&64u32.to_ne_bytes());
+        let sequence = Sequence::from_bytes(&buffer[..len]).unwrap();
+        assert_eq!(sequence.iter().count(), 0);
+    }
+}
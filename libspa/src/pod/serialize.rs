@@ -1,7 +1,7 @@
 //! This module deals with serializing rust types into raw SPA pods.
 //!
 //! A raw pod can be serialized by passing a implementor of the [`PodSerialize`] trait
-//! to [`PodSerializer::serialize`].
+//! to [`PodSerializer::serialize`], or to [`PodSerializer::serialize_to_buf`] to get an aligned [`PodBuf`].
 //!
 //! The crate provides a number of implementors of this trait either directly,
 //! or through [`FixedSizedPod`](`super::FixedSizedPod`).
@@ -31,7 +31,7 @@ use crate::{
     utils::{Choice, ChoiceEnum},
 };
 
-use super::{CanonicalFixedSizedPod, FixedSizedPod, PropertyFlags, Value, ValueArray};
+use super::{CanonicalFixedSizedPod, FixedSizedPod, PodBuf, PropertyFlags, Value, ValueArray};
 
 /// Implementors of this trait are able to serialize themselves into a SPA pod by using a [`PodSerializer`].
 ///
@@ -257,6 +257,19 @@ pub struct PodSerializer<O: Write + Seek> {
     out: Option<O>,
 }
 
+impl PodSerializer<PodBuf> {
+    /// Serialize the provided POD into a new [`PodBuf`].
+    ///
+    /// Unlike serializing into a `Vec<u8>`, the resulting bytes are aligned,
+    /// so they can be viewed as a [`Pod`](super::Pod) using [`PodBuf::as_pod`].
+    pub fn serialize_to_buf<P>(pod: &P) -> Result<PodBuf, GenError>
+    where
+        P: PodSerialize + ?Sized,
+    {
+        Self::serialize(PodBuf::new(), pod).map(|(buf, _)| buf)
+    }
+}
+
 impl<O: Write + Seek> PodSerializer<O> {
     /// Serialize the provided POD into the raw pod format, writing it into `out`.
     ///
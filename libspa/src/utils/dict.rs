@@ -2,6 +2,21 @@
 // SPDX-License-Identifier: MIT
 
 //! Dictionary types and traits.
+//!
+//! All the dictionaries, whether borrowed from the C API or owned such as the `Properties`
+//! of the `pipewire` crate, can be read as a [`DictRef`].
+//! Code reading any kind of dictionary can be written once against the [`ReadableDict`] trait,
+//! which is implemented by all of them:
+//!
+//! ```
+//! use libspa::utils::dict::ReadableDict;
+//!
+//! fn media_class(props: &impl ReadableDict) -> Option<&str> {
+//!     props.get("media.class")
+//! }
+//! ```
+//!
+//! `AsRef<DictRef>`, also implemented by all of them, gives access to the whole [`DictRef`] API instead.
 
 use bitflags::bitflags;
 // re-exported as used in the static_dict! macro implementation
@@ -132,6 +147,53 @@ impl DictRef {
     }
 }
 
+/// A dictionary that can be read, implemented by all the dictionary types.
+///
+/// This allows writing the code reading a dictionary once for all of them, see the [module documentation](self).
+pub trait ReadableDict {
+    /// Get the value associated with the provided key, see [`DictRef::get`].
+    fn get(&self, key: &str) -> Option<&str>;
+
+    /// An iterator over all key-value pairs that are valid utf-8, see [`DictRef::iter`].
+    fn iter(&self) -> Iter<'_>;
+
+    /// The number of items in the dictionary, including the ones that are not valid utf-8.
+    fn len(&self) -> usize;
+
+    /// Returns `true` if the dictionary has no items.
+    fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+}
+
+impl ReadableDict for DictRef {
+    fn get(&self, key: &str) -> Option<&str> {
+        DictRef::get(self, key)
+    }
+
+    fn iter(&self) -> Iter<'_> {
+        DictRef::iter(self)
+    }
+
+    fn len(&self) -> usize {
+        DictRef::len(self)
+    }
+}
+
+impl ReadableDict for StaticDict {
+    fn get(&self, key: &str) -> Option<&str> {
+        DictRef::get(self, key)
+    }
+
+    fn iter(&self) -> Iter<'_> {
+        DictRef::iter(self)
+    }
+
+    fn len(&self) -> usize {
+        DictRef::len(self)
+    }
+}
+
 impl AsRef<Self> for DictRef {
     fn as_ref(&self) -> &Self {
         self
@@ -322,6 +384,13 @@ impl<'a> Iterator for Values<'a> {
 
 /// A collection of static key/value pairs.
 ///
+/// The dict and its items are created at compile time by the [`static_dict!`](crate::static_dict) macro,
+/// and are stored in static memory without any heap allocation,
+/// making it suitable for fixed sets of properties that are passed repeatedly.
+///
+/// The raw `spa_dict` can be passed to C functions using [`as_ptr`](Self::as_ptr).
+/// Where an owned, heap-backed dict is needed instead, it can be copied using pipewire's `Properties::from_dict`.
+///
 /// # Examples
 /// Create a `StaticDict` and access the stored values by key:
 /// ```rust
@@ -349,6 +418,13 @@ impl StaticDict {
     pub const unsafe fn from_ptr(ptr: ptr::NonNull<spa_sys::spa_dict>) -> Self {
         Self { ptr }
     }
+
+    /// Returns the pointer to the raw `spa_dict` of this dict.
+    ///
+    /// The pointer is valid for the whole lifetime of the `StaticDict`, and must not be used to modify the dict.
+    pub const fn as_ptr(&self) -> *const spa_sys::spa_dict {
+        self.ptr.as_ptr()
+    }
 }
 
 /// A macro for creating a new [`StaticDict`] with predefined key-value pairs.
@@ -416,7 +492,7 @@ unsafe impl Sync for StaticDict {}
 
 #[cfg(test)]
 mod tests {
-    use super::{DictRef, Flags, StaticDict};
+    use super::{DictRef, Flags, ReadableDict, StaticDict};
     use spa_sys::spa_dict;
     use std::ptr;
 
@@ -481,6 +557,24 @@ mod tests {
         assert_eq!(Some("V0"), dict.get("K0"));
     }
 
+    #[test]
+    fn test_readable_dict() {
+        fn read(dict: &impl ReadableDict) -> (Option<&str>, usize, Vec<(&str, &str)>) {
+            (dict.get("K1"), dict.len(), dict.iter().collect())
+        }
+
+        let dict = static_dict! {
+            "K0" => "V0",
+            "K1" => "V1"
+        };
+        let expected = (Some("V1"), 2, vec![("K0", "V0"), ("K1", "V1")]);
+
+        let dict_ref: &DictRef = &dict;
+        assert_eq!(expected, read(&dict));
+        assert_eq!(expected, read(dict_ref));
+        assert!(!ReadableDict::is_empty(&dict));
+    }
+
     #[test]
     fn test_debug() {
         let dict = static_dict! {
@@ -518,6 +612,17 @@ mod tests {
         assert_eq!(DICT.get("K1"), Some("V1"));
     }
 
+    #[test]
+    fn static_dict_as_ptr() {
+        static DICT: StaticDict = static_dict! {
+            "K0" => "V0"
+        };
+
+        let raw = unsafe { &*DICT.as_ptr() };
+        assert_eq!(raw.n_items, 1);
+        assert_eq!(DICT.as_ptr(), DICT.as_raw() as *const spa_dict);
+    }
+
     #[test]
     fn parse() {
         use super::ParseValueError;
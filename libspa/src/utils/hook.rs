@@ -29,6 +29,10 @@ pub fn remove(mut hook: spa_sys::spa_hook) {
 ///
 /// The macro returns whatever the called method returns, for example an `i32`, or `()` if the method returns nothing.
 ///
+/// # Panics
+/// Panics if the interface does not implement the method, see [`spa_interface_call_method_checked!`]
+/// to get an error instead.
+///
 /// # Examples
 /// Here we call the sync method on a `pipewire_sys::pw_core` object.
 /// ```
@@ -54,8 +58,80 @@ macro_rules! spa_interface_call_method {
     ($interface_ptr:expr, $methods_struct:ty, $method:ident, $( $arg:expr ),*) => {{
         let iface: *mut spa_sys::spa_interface = $interface_ptr.cast();
         let funcs: *const $methods_struct = (*iface).cb.funcs.cast();
-        let f = (*funcs).$method.unwrap();
+        let f = funcs
+            .as_ref()
+            .and_then(|funcs| funcs.$method)
+            .expect(concat!("interface does not implement ", stringify!($method)));
 
         f((*iface).cb.data, $($arg),*)
     }};
 }
+
+/// Call a method on a spa_interface, if the interface implements it.
+///
+/// This takes the same arguments as [`spa_interface_call_method!`], but returns a
+/// `Result` with whatever the called method returns, or an [`Error`](crate::utils::result::Error)
+/// with `ENOTSUP` if the interface has no methods or the method is not set,
+/// for example when the interface is of an older version.
+///
+/// This needs to be called from within an `unsafe` block.
+#[macro_export]
+macro_rules! spa_interface_call_method_checked {
+    ($interface_ptr:expr, $methods_struct:ty, $method:ident, $( $arg:expr ),*) => {{
+        let iface: *mut spa_sys::spa_interface = $interface_ptr.cast();
+        let funcs: *const $methods_struct = (*iface).cb.funcs.cast();
+
+        match funcs.as_ref().and_then(|funcs| funcs.$method) {
+            Some(f) => Ok(f((*iface).cb.data, $($arg),*)),
+            None => Err($crate::utils::hook::method_not_supported()),
+        }
+    }};
+}
+
+/// The error returned by [`spa_interface_call_method_checked!`] for a missing method.
+#[doc(hidden)]
+pub fn method_not_supported() -> crate::utils::result::Error {
+    nix::errno::Errno::ENOTSUP.into()
+}
+
+#[cfg(test)]
+mod tests {
+    use std::os::raw::{c_int, c_void};
+
+    use nix::errno::Errno;
+
+    #[repr(C)]
+    struct Methods {
+        sync: Option<unsafe extern "C" fn(object: *mut c_void, seq: c_int) -> c_int>,
+    }
+
+    unsafe extern "C" fn sync(_object: *mut c_void, seq: c_int) -> c_int {
+        seq + 1
+    }
+
+    fn interface(methods: &Methods) -> spa_sys::spa_interface {
+        spa_sys::spa_interface {
+            type_: std::ptr::null(),
+            version: 0,
+            cb: spa_sys::spa_callbacks {
+                funcs: (methods as *const Methods).cast(),
+                data: std::ptr::null_mut(),
+            },
+        }
+    }
+
+    #[test]
+    fn call_method_checked() {
+        let methods = Methods { sync: Some(sync) };
+        let mut iface = interface(&methods);
+        let ptr: *mut spa_sys::spa_interface = &mut iface;
+        let res = unsafe { spa_interface_call_method_checked!(ptr, Methods, sync, 41) };
+        assert_eq!(res, Ok(42));
+
+        let methods = Methods { sync: None };
+        let mut iface = interface(&methods);
+        let ptr: *mut spa_sys::spa_interface = &mut iface;
+        let res = unsafe { spa_interface_call_method_checked!(ptr, Methods, sync, 41) };
+        assert_eq!(res.map_err(|e| e.errno()), Err(Errno::ENOTSUP));
+    }
+}
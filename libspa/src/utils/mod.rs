@@ -6,14 +6,13 @@ pub use direction::*;
 pub mod hook;
 pub mod list;
 pub mod result;
+mod values;
+pub use values::*;
 
 use bitflags::bitflags;
 use convert_case::{Case, Casing};
 use std::{ffi::CStr, fmt::Debug, os::raw::c_uint};
 
-pub use spa_sys::spa_fraction as Fraction;
-pub use spa_sys::spa_rectangle as Rectangle;
-
 use crate::pod::CanonicalFixedSizedPod;
 
 /// An enumerated value in a pod
@@ -150,6 +149,18 @@ impl SpaTypes {
     }
 }
 
+impl From<c_uint> for SpaTypes {
+    fn from(raw: c_uint) -> Self {
+        Self::from_raw(raw)
+    }
+}
+
+impl From<SpaTypes> for c_uint {
+    fn from(type_: SpaTypes) -> Self {
+        type_.as_raw()
+    }
+}
+
 impl Debug for SpaTypes {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
         match *self {
@@ -224,4 +235,18 @@ mod tests {
             format!("{:?}", SpaTypes::VendorOther)
         );
     }
+
+    #[test]
+    fn fraction_rectangle() {
+        let framerate = Fraction::new(30000, 1001);
+        assert_eq!(framerate.to_string(), "30000/1001");
+        assert!((framerate.to_f64().unwrap() - 29.97).abs() < 0.01);
+        assert_eq!(Fraction::new(1, 0).to_f64(), None);
+
+        let size = Rectangle::new(1920, 1080);
+        assert_eq!(size.to_string(), "1920x1080");
+        assert_eq!(size.area(), 2_073_600);
+
+        assert_eq!(Point::new(-1, 2).to_string(), "-1,2");
+    }
 }
@@ -7,8 +7,40 @@ use std::{convert::TryInto, fmt};
 
 use nix::errno::Errno;
 
+/// Convert the `i32` returned by a C SPA or PipeWire method into a Rust [`Result`],
+/// turning negative errno values into an [`Error`].
+///
+/// This is a shortcut for [`SpaResult::from_c`] followed by one of its conversions:
+/// - `spa_result!(res)` uses [`SpaResult::into_result`],
+/// - `spa_result!(sync res)` uses [`SpaResult::into_sync_result`],
+/// - `spa_result!(async res)` uses [`SpaResult::into_async_result`].
+///
+/// ```
+/// use libspa::{spa_result, utils::result::SpaSuccess};
+///
+/// assert_eq!(spa_result!(0), Ok(SpaSuccess::Sync(0)));
+/// assert_eq!(spa_result!(sync 1), Ok(1));
+/// assert_eq!(
+///     spa_result!(sync -libc::EINVAL).unwrap_err().errno(),
+///     nix::errno::Errno::EINVAL
+/// );
+/// ```
+#[macro_export]
+macro_rules! spa_result {
+    (sync $res:expr) => {
+        $crate::utils::result::SpaResult::from_c($res).into_sync_result()
+    };
+    (async $res:expr) => {
+        $crate::utils::result::SpaResult::from_c($res).into_async_result()
+    };
+    ($res:expr) => {
+        $crate::utils::result::SpaResult::from_c($res).into_result()
+    };
+}
+
 /// A result returned by a SPA method, usually to be converted to
-/// a Rust result using [`SpaResult::into_result`] or [`SpaResult::into_async_result`].
+/// a Rust result using [`SpaResult::into_result`] or [`SpaResult::into_async_result`],
+/// or directly with [`spa_result!`](crate::spa_result).
 #[derive(Debug, Eq, PartialEq)]
 pub struct SpaResult(i32);
 
@@ -135,6 +167,26 @@ impl Error {
 
         Self(Errno::from_i32(e))
     }
+
+    /// Create an error from an errno value, either positive such as `libc::EINVAL`,
+    /// or negative as returned by failing SPA and PipeWire methods, such as `-libc::EINVAL`.
+    ///
+    /// # Panics
+    /// Panics if `errno` is `0`, as it does not represent an error.
+    pub fn from_errno(errno: i32) -> Self {
+        Self::new(errno.checked_abs().expect("errno out of range"))
+    }
+
+    /// The errno of the error, which can be used to match on specific errors.
+    pub fn errno(&self) -> Errno {
+        self.0
+    }
+}
+
+impl From<Errno> for Error {
+    fn from(errno: Errno) -> Self {
+        Self(errno)
+    }
 }
 
 impl std::error::Error for Error {}
@@ -176,6 +228,51 @@ mod tests {
         assert!(res.is_err());
     }
 
+    #[test]
+    #[cfg_attr(miri, ignore)]
+    fn from_errno() {
+        assert_eq!(
+            Error::from_errno(-libc::EINVAL),
+            Error::from_errno(libc::EINVAL)
+        );
+        assert_eq!(Error::from_errno(-libc::ENOENT).errno(), Errno::ENOENT);
+        assert_eq!(Error::from(Errno::EPERM), Error::from_errno(-libc::EPERM));
+
+        assert_eq!(
+            Error::from_errno(-libc::EINVAL).to_string(),
+            "EINVAL: Invalid argument"
+        );
+        assert_eq!(
+            Error::from_errno(-libc::ENOENT).to_string(),
+            "ENOENT: No such file or directory"
+        );
+        assert_eq!(
+            Error::from_errno(-libc::EPERM).to_string(),
+            "EPERM: Operation not permitted"
+        );
+    }
+
+    #[test]
+    #[cfg_attr(miri, ignore)]
+    fn spa_result_macro() {
+        assert_eq!(crate::spa_result!(2), Ok(SpaSuccess::Sync(2)));
+        assert_eq!(crate::spa_result!(sync 0), Ok(0));
+        assert_eq!(
+            crate::spa_result!(async SpaResult::new_return_async(3).0),
+            Ok(AsyncSeq::from_seq(3))
+        );
+        assert_eq!(
+            crate::spa_result!(sync - libc::EPERM),
+            Err(Error::from_errno(-libc::EPERM))
+        );
+    }
+
+    #[should_panic]
+    #[test]
+    fn from_errno_zero_panic() {
+        Error::from_errno(0);
+    }
+
     #[test]
     fn async_seq() {
         assert_eq!(AsyncSeq::from_seq(0).seq(), 0);
@@ -0,0 +1,154 @@
+// Copyright The pipewire-rs Contributors.
+// SPDX-License-Identifier: MIT
+
+//! Plain value types: fractions, rectangles and points.
+//!
+//! Each type has the same layout as its raw SPA equivalent, and converts from and into it.
+
+use std::fmt;
+
+/// A size, such as the size of a video frame, as found in `Rectangle` pods.
+#[repr(C)]
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct Rectangle {
+    pub width: u32,
+    pub height: u32,
+}
+
+impl Rectangle {
+    pub const fn new(width: u32, height: u32) -> Self {
+        Self { width, height }
+    }
+
+    /// The area of the rectangle, in pixels.
+    pub const fn area(&self) -> u64 {
+        self.width as u64 * self.height as u64
+    }
+
+    pub const fn from_raw(raw: spa_sys::spa_rectangle) -> Self {
+        Self::new(raw.width, raw.height)
+    }
+
+    pub const fn as_raw(&self) -> spa_sys::spa_rectangle {
+        spa_sys::spa_rectangle {
+            width: self.width,
+            height: self.height,
+        }
+    }
+}
+
+impl From<spa_sys::spa_rectangle> for Rectangle {
+    fn from(raw: spa_sys::spa_rectangle) -> Self {
+        Self::from_raw(raw)
+    }
+}
+
+impl From<Rectangle> for spa_sys::spa_rectangle {
+    fn from(rectangle: Rectangle) -> Self {
+        rectangle.as_raw()
+    }
+}
+
+/// Formats the rectangle as `<width>x<height>`, such as `1920x1080`.
+impl fmt::Display for Rectangle {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}x{}", self.width, self.height)
+    }
+}
+
+/// A fraction, such as a video framerate or a sample rate, as found in `Fraction` pods.
+#[repr(C)]
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct Fraction {
+    pub num: u32,
+    pub denom: u32,
+}
+
+impl Fraction {
+    pub const fn new(num: u32, denom: u32) -> Self {
+        Self { num, denom }
+    }
+
+    /// The value of the fraction as a floating point number, or `None` if the denominator is zero.
+    pub fn to_f64(&self) -> Option<f64> {
+        if self.denom == 0 {
+            None
+        } else {
+            Some(f64::from(self.num) / f64::from(self.denom))
+        }
+    }
+
+    pub const fn from_raw(raw: spa_sys::spa_fraction) -> Self {
+        Self::new(raw.num, raw.denom)
+    }
+
+    pub const fn as_raw(&self) -> spa_sys::spa_fraction {
+        spa_sys::spa_fraction {
+            num: self.num,
+            denom: self.denom,
+        }
+    }
+}
+
+impl From<spa_sys::spa_fraction> for Fraction {
+    fn from(raw: spa_sys::spa_fraction) -> Self {
+        Self::from_raw(raw)
+    }
+}
+
+impl From<Fraction> for spa_sys::spa_fraction {
+    fn from(fraction: Fraction) -> Self {
+        fraction.as_raw()
+    }
+}
+
+/// Formats the fraction as `<num>/<denom>`, such as `30/1`.
+impl fmt::Display for Fraction {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}/{}", self.num, self.denom)
+    }
+}
+
+/// A position, such as the position of a region in a video frame.
+#[repr(C)]
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct Point {
+    pub x: i32,
+    pub y: i32,
+}
+
+impl Point {
+    pub const fn new(x: i32, y: i32) -> Self {
+        Self { x, y }
+    }
+
+    pub const fn from_raw(raw: spa_sys::spa_point) -> Self {
+        Self::new(raw.x, raw.y)
+    }
+
+    pub const fn as_raw(&self) -> spa_sys::spa_point {
+        spa_sys::spa_point {
+            x: self.x,
+            y: self.y,
+        }
+    }
+}
+
+impl From<spa_sys::spa_point> for Point {
+    fn from(raw: spa_sys::spa_point) -> Self {
+        Self::from_raw(raw)
+    }
+}
+
+impl From<Point> for spa_sys::spa_point {
+    fn from(point: Point) -> Self {
+        point.as_raw()
+    }
+}
+
+/// Formats the point as `<x>,<y>`.
+impl fmt::Display for Point {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{},{}", self.x, self.y)
+    }
+}
@@ -6,7 +6,8 @@ use libspa::{
             StructPodDeserializer, Visitor,
         },
         serialize::{PodSerialize, PodSerializer, SerializeSuccess},
-        CanonicalFixedSizedPod, ChoiceValue, Object, Property, PropertyFlags, Value, ValueArray,
+        CanonicalFixedSizedPod, ChoiceValue, Object, Pod, PodBuf, Property, PropertyFlags, Value,
+        ValueArray,
     },
     utils::{Choice, ChoiceEnum, ChoiceFlags, Fd, Fraction, Id, Rectangle},
 };
@@ -2255,3 +2256,32 @@ fn audio_info_raw() {
     assert_eq!(vec_rs, vec_c);
     assert!(unsafe { c::parse_audio_info_raw(vec_c.as_mut_ptr()) } > 0);
 }
+
+#[test]
+#[cfg_attr(miri, ignore)]
+fn from_bytes_validation() {
+    let buf = PodSerializer::serialize_to_buf(&Value::Int(7)).unwrap();
+    // Header, 4 bytes of body and 4 bytes of padding.
+    assert_eq!(buf.len(), 16);
+
+    let pod = buf.as_pod().unwrap();
+    assert_eq!(pod.get_int(), Ok(7));
+    assert_eq!(pod.as_bytes(), &buf[..12]);
+
+    // Too short for the header, the body, or the padding.
+    assert!(Pod::from_bytes(&buf[..4]).is_none());
+    assert!(Pod::from_bytes(&buf[..10]).is_none());
+    assert!(Pod::from_bytes(&buf[..12]).is_none());
+
+    // Misaligned, including on 4 bytes.
+    let mut shifted = vec![0; 4];
+    shifted.extend_from_slice(&buf);
+    let shifted = PodBuf::from_bytes(&shifted);
+    assert!(Pod::from_bytes(&shifted[4..]).is_none());
+    assert!(Pod::from_bytes(&shifted[1..]).is_none());
+
+    // A size larger than the buffer.
+    let mut oversized = buf.into_vec();
+    oversized[..4].copy_from_slice(&u32::MAX.to_ne_bytes());
+    assert!(PodBuf::from_bytes(&oversized).as_pod().is_none());
+}
@@ -0,0 +1,143 @@
+// Copyright The pipewire-rs Contributors.
+// SPDX-License-Identifier: MIT
+
+//! Benchmarks of the registry event paths.
+//!
+//! The events are emitted through the C trampolines using a fake registry, so no daemon is needed:
+//!
+//! ```sh
+//! cargo bench -p pipewire --bench registry
+//! ```
+
+use std::{cell::Cell, ffi::CStr, hint::black_box, rc::Rc, time::Instant};
+
+use pipewire as pw;
+use pw::{
+    properties::properties,
+    registry::{GlobalObject, RegistryListener},
+    spa::utils::dict::DictRef,
+    types::ObjectType,
+};
+
+#[path = "../tests/common/mod.rs"]
+mod common;
+use common::FakeRegistry;
+
+const ITERATIONS: u32 = 1_000_000;
+
+/// Run `f` `ITERATIONS` times, after a warm up, and print the mean time of an iteration.
+fn bench(name: &str, mut f: impl FnMut(u32)) {
+    for i in 0..ITERATIONS / 10 {
+        f(i);
+    }
+
+    let start = Instant::now();
+    for i in 0..ITERATIONS {
+        f(black_box(i));
+    }
+    let elapsed = start.elapsed();
+
+    println!(
+        "{name:<40} {:>8.1} ns/iter",
+        elapsed.as_nanos() as f64 / f64::from(ITERATIONS)
+    );
+}
+
+fn global_remove() {
+    let fake = FakeRegistry::new();
+    let removed: Rc<Cell<u32>> = Rc::default();
+    let removed_clone = removed.clone();
+    let _listener = fake
+        .registry()
+        .add_listener_local()
+        .global_remove(move |id| removed_clone.set(removed_clone.get().wrapping_add(id)))
+        .register();
+    bench("global_remove/closure", |id| fake.global_remove(id));
+
+    let listener = fake
+        .registry()
+        .add_listener_local_with_user_data(0u32)
+        .global_remove(|removed, _registry, id| *removed = removed.wrapping_add(id))
+        .register();
+    bench("global_remove/user_data", |id| fake.global_remove(id));
+
+    black_box((removed.get(), *listener.state()));
+}
+
+const NODE: &CStr = c"PipeWire:Interface:Node";
+const PORT: &CStr = c"PipeWire:Interface:Port";
+
+/// Counts the globals it receives, like the closure listener of the `global` benchmark.
+#[derive(Default)]
+struct CountingListener {
+    count: Cell<u32>,
+}
+
+impl RegistryListener for CountingListener {
+    fn global(&self, global: &GlobalObject<&DictRef>) {
+        self.count.set(self.count.get().wrapping_add(global.id));
+    }
+}
+
+fn global() {
+    let fake = FakeRegistry::new();
+    let props = properties! {
+        *pw::keys::NODE_NAME => "bench",
+    };
+
+    let count: Rc<Cell<u32>> = Rc::default();
+    let count_clone = count.clone();
+    let _listener = fake
+        .registry()
+        .add_listener_local()
+        .global(move |global| count_clone.set(count_clone.get().wrapping_add(global.id)))
+        .register();
+    bench("global/closure", |id| fake.global(id, NODE, Some(&props)));
+
+    let listener = fake
+        .registry()
+        .add_listener_local_with(CountingListener::default());
+    bench("global/trait", |id| fake.global(id, NODE, Some(&props)));
+
+    black_box((count.get(), listener.get().count.get()));
+}
+
+/// Only keep the nodes among port globals, the most common kind of global in a graph.
+fn global_filter() {
+    let fake = FakeRegistry::new();
+    let props = properties! {
+        *pw::keys::PORT_NAME => "bench",
+    };
+
+    let count: Rc<Cell<u32>> = Rc::default();
+    let count_clone = count.clone();
+    let _listener = fake
+        .registry()
+        .add_listener_local()
+        .global(move |global| {
+            if global.type_ == ObjectType::Node {
+                count_clone.set(count_clone.get().wrapping_add(global.id));
+            }
+        })
+        .register();
+    bench("global/in_callback", |id| {
+        fake.global(id, PORT, Some(&props))
+    });
+
+    let count_clone = count.clone();
+    let _listener = fake
+        .registry()
+        .add_listener_local()
+        .filter(|_id, type_| *type_ == ObjectType::Node)
+        .global(move |global| count_clone.set(count_clone.get().wrapping_add(global.id)))
+        .register();
+    bench("global/filter", |id| fake.global(id, PORT, Some(&props)));
+
+    black_box(count.get());
+}
+
+fn main() {
+    global_remove();
+    global();
+    global_filter();
+}
@@ -11,8 +11,6 @@ use pw::{properties::properties, spa};
 use spa::param::format::{MediaSubtype, MediaType};
 use spa::param::format_utils;
 use spa::pod::Pod;
-#[cfg(feature = "v0_3_44")]
-use spa::WritableDict;
 use std::convert::TryInto;
 use std::mem;
 
@@ -166,13 +164,9 @@ pub fn main() -> Result<(), pw::Error> {
         id: pw::spa::param::ParamType::EnumFormat.as_raw(),
         properties: audio_info.into(),
     };
-    let values: Vec<u8> = pw::spa::pod::serialize::PodSerializer::serialize(
-        std::io::Cursor::new(Vec::new()),
-        &pw::spa::pod::Value::Object(obj),
-    )
-    .unwrap()
-    .0
-    .into_inner();
+    let values =
+        pw::spa::pod::serialize::PodSerializer::serialize_to_buf(&pw::spa::pod::Value::Object(obj))
+            .unwrap();
 
     let mut params = [Pod::from_bytes(&values).unwrap()];
 
@@ -0,0 +1,218 @@
+// Copyright The pipewire-rs Contributors.
+// SPDX-License-Identifier: MIT
+
+//! Watch devices being plugged and unplugged, printing their profiles, and optionally
+//! switch each new device to a preferred profile, for example `output:hdmi-stereo`.
+//! Devices already present when the monitor starts are listed, but left on their current profile.
+
+use anyhow::Result;
+use clap::Parser;
+use pipewire as pw;
+use pw::{
+    device::{Device, DeviceListener},
+    loop_::Signal,
+    properties::properties,
+    registry::GlobalObject,
+    spa,
+    types::ObjectType,
+};
+use spa::{
+    param::{
+        profile::{Availability, Profile},
+        ParamObject, ParamType,
+    },
+    utils::dict::DictRef,
+};
+use std::{
+    cell::Cell,
+    collections::HashMap,
+    rc::{Rc, Weak},
+};
+
+#[derive(Parser)]
+#[clap(name = "pw-device-monitor", about = "Watch devices and their profiles")]
+struct Opt {
+    #[clap(short, long, help = "The name of the remote to connect to")]
+    remote: Option<String>,
+    #[clap(
+        short,
+        long,
+        help = "The name of the profile to switch new devices to, when they have it"
+    )]
+    profile: Option<String>,
+}
+
+/// A device being watched.
+struct Watched {
+    name: String,
+    // Declared before the device so that it is dropped first.
+    _listener: DeviceListener,
+    _device: Rc<Device>,
+}
+
+/// Get a human readable name for the device global `obj`.
+fn device_name(obj: &GlobalObject<&DictRef>) -> String {
+    obj.props
+        .and_then(|props| {
+            props
+                .get(*pw::keys::DEVICE_DESCRIPTION)
+                .or_else(|| props.get(*pw::keys::DEVICE_NAME))
+        })
+        .unwrap_or("unnamed device")
+        .to_string()
+}
+
+/// Print the profile received in a `param` event of the device `id`, and switch to it
+/// if it is the `preferred` one, when there is one.
+fn on_profile(
+    device: &Weak<Device>,
+    id: u32,
+    param_id: ParamType,
+    profile: &Profile,
+    preferred: Option<&str>,
+) {
+    let name = profile.name.as_deref().unwrap_or("?");
+
+    if param_id == ParamType::Profile {
+        println!("device {}: active profile {}", id, name);
+        return;
+    }
+
+    println!(
+        "device {}: profile {} {} ({}, {:?})",
+        id,
+        profile.index,
+        name,
+        profile.description.as_deref().unwrap_or(""),
+        profile.available,
+    );
+
+    if preferred.is_some()
+        && profile.name.as_deref() == preferred
+        && profile.available != Availability::No
+    {
+        if let Some(device) = device.upgrade() {
+            println!("device {}: switching to profile {}", id, name);
+            device.set_profile(profile.index);
+        }
+    }
+}
+
+fn monitor(opt: Opt) -> Result<()> {
+    let main_loop = pw::main_loop::MainLoop::new(None)?;
+
+    let main_loop_weak = main_loop.downgrade();
+    let _signals = main_loop.add_signals_local(&[Signal::SIGINT, Signal::SIGTERM], move |_| {
+        if let Some(main_loop) = main_loop_weak.upgrade() {
+            main_loop.quit();
+        }
+    });
+
+    let context = pw::context::Context::new(&main_loop)?;
+    let props = opt.remote.map(|remote| {
+        properties! {
+            *pw::keys::REMOTE_NAME => remote
+        }
+    });
+    let core = context.connect(props)?;
+    let registry = core.registry()?;
+
+    // The registry announces the existing devices first, set once they all have been received.
+    let synced = Rc::new(Cell::new(false));
+    let pending = core.sync(0)?;
+    let synced_clone = synced.clone();
+    let _core_listener = core
+        .add_listener_local()
+        .done(move |id, seq| {
+            if id == pw::core::PW_ID_CORE && seq == pending {
+                synced_clone.set(true);
+            }
+        })
+        .register();
+
+    let preferred: Rc<Option<String>> = Rc::new(opt.profile);
+    let no_preference: Rc<Option<String>> = Rc::new(None);
+
+    let _registry_listener = registry
+        .add_listener_local_with_user_data(HashMap::<u32, Watched>::new())
+        .global(move |devices, registry, obj| {
+            if obj.type_ != ObjectType::Device {
+                return;
+            }
+
+            let name = device_name(obj);
+            println!("device {} added: {}", obj.id, name);
+
+            let device = match registry.bind_device(obj) {
+                Ok(device) => Rc::new(device),
+                Err(e) => {
+                    eprintln!("device {}: failed to bind: {}", obj.id, e);
+                    return;
+                }
+            };
+
+            let id = obj.id;
+            let device_weak = Rc::downgrade(&device);
+            // Only switch the profile of the devices plugged while monitoring.
+            let preferred = if synced.get() {
+                preferred.clone()
+            } else {
+                no_preference.clone()
+            };
+            let listener = device
+                .add_listener_local()
+                .param(move |_seq, param_id, _index, _next, param| {
+                    let Some(param) = param else {
+                        return;
+                    };
+                    if param_id != ParamType::EnumProfile && param_id != ParamType::Profile {
+                        return;
+                    }
+
+                    match Profile::parse(param) {
+                        Ok(profile) => {
+                            on_profile(&device_weak, id, param_id, &profile, preferred.as_deref())
+                        }
+                        Err(e) => eprintln!("device {}: invalid profile: {}", id, e),
+                    }
+                })
+                .register();
+
+            device.enum_active_profile(0);
+            device.enum_profiles(0);
+
+            devices.insert(
+                id,
+                Watched {
+                    name,
+                    _listener: listener,
+                    _device: device,
+                },
+            );
+        })
+        .global_remove(|devices, _registry, id| {
+            // Drop the proxy and its listener along with the global.
+            if let Some(device) = devices.remove(&id) {
+                println!("device {} removed: {}", id, device.name);
+            }
+        })
+        .register();
+
+    eprintln!("Watching devices, press Ctrl-C to stop");
+    main_loop.run();
+
+    Ok(())
+}
+
+fn main() -> Result<()> {
+    pw::init();
+
+    let opt = Opt::parse();
+    monitor(opt)?;
+
+    unsafe {
+        pw::deinit();
+    }
+
+    Ok(())
+}
@@ -0,0 +1,157 @@
+// Copyright The pipewire-rs Contributors.
+// SPDX-License-Identifier: MIT
+
+//! A simplified rust equivalent of the `pw-dot` tool, writing the graph of nodes, ports and links
+//! as a Graphviz `dot` file.
+//!
+//! Render the resulting file with `dot -Tsvg pw.dot > pw.svg`.
+
+use anyhow::{bail, Result};
+use clap::Parser;
+use pipewire as pw;
+use std::{
+    fmt::Write as _,
+    io::Write as _,
+    path::PathBuf,
+    time::{Duration, Instant},
+};
+
+use pw::{
+    registry::{cache::RegistryCache, OwnedGlobalObject},
+    types::ObjectType,
+};
+
+#[derive(Parser)]
+#[clap(
+    name = "pw-dot",
+    about = "Write the PipeWire graph as a Graphviz dot file"
+)]
+struct Opt {
+    #[clap(short, long, help = "The name of the remote to connect to")]
+    remote: Option<String>,
+    #[clap(
+        short,
+        long,
+        default_value = "pw.dot",
+        help = "The file to write the graph to, or `-` for the standard output"
+    )]
+    output: PathBuf,
+}
+
+fn prop<'a>(global: &'a OwnedGlobalObject, key: &str) -> Option<&'a str> {
+    global.props.as_ref().and_then(|props| props.get(key))
+}
+
+fn prop_u32(global: &OwnedGlobalObject, key: &str) -> Option<u32> {
+    prop(global, key).and_then(|value| value.parse().ok())
+}
+
+/// Quote a string to be used as a dot label.
+fn quote(label: &str) -> String {
+    format!("\"{}\"", label.replace('\\', "\\\\").replace('"', "\\\""))
+}
+
+/// Build the dot graph, with each node as a cluster containing its ports, and links as edges.
+fn dot(cache: &RegistryCache) -> Result<String> {
+    let mut dot = String::new();
+    writeln!(dot, "digraph pipewire {{")?;
+    writeln!(dot, "  rankdir=LR;")?;
+    writeln!(dot, "  node [shape=box];")?;
+
+    for node in cache.of_type(&ObjectType::Node) {
+        let name = prop(&node, *pw::keys::NODE_DESCRIPTION)
+            .or_else(|| prop(&node, *pw::keys::NODE_NAME))
+            .unwrap_or_default();
+
+        writeln!(dot, "  subgraph cluster_{} {{", node.id)?;
+        writeln!(
+            dot,
+            "    label={};",
+            quote(&format!("{} ({})", name, node.id))
+        )?;
+
+        for port in cache.ports_of_node(node.id) {
+            let name = prop(&port, *pw::keys::PORT_NAME).unwrap_or_default();
+            let color = match prop(&port, *pw::keys::PORT_DIRECTION) {
+                Some("in") => "lightblue",
+                _ => "lightpink",
+            };
+
+            writeln!(
+                dot,
+                "    port_{} [label={}, style=filled, fillcolor={}];",
+                port.id,
+                quote(name),
+                color
+            )?;
+        }
+
+        writeln!(dot, "  }}")?;
+    }
+
+    for link in cache.of_type(&ObjectType::Link) {
+        if let (Some(output), Some(input)) = (
+            prop_u32(&link, *pw::keys::LINK_OUTPUT_PORT),
+            prop_u32(&link, *pw::keys::LINK_INPUT_PORT),
+        ) {
+            writeln!(dot, "  port_{} -> port_{};", output, input)?;
+        }
+    }
+
+    writeln!(dot, "}}")?;
+
+    Ok(dot)
+}
+
+fn run(opt: Opt) -> Result<()> {
+    let props = opt.remote.map(|remote| {
+        pw::properties::properties! {
+            *pw::keys::REMOTE_NAME => remote
+        }
+    });
+    let connection = pw::connect(props)?;
+
+    // Give up if the server does not enumerate its globals in time, instead of waiting forever.
+    const SYNC_TIMEOUT: Duration = Duration::from_secs(10);
+
+    let cache = RegistryCache::new(connection.registry());
+    let deadline = Instant::now() + SYNC_TIMEOUT;
+    while !cache.is_synced() {
+        let remaining = deadline.saturating_duration_since(Instant::now());
+        if remaining.is_zero() {
+            bail!(
+                "Timed out after {:?} waiting for the globals of the server",
+                SYNC_TIMEOUT
+            );
+        }
+
+        connection
+            .main_loop()
+            .loop_()
+            .iterate(remaining.min(Duration::from_secs(1)));
+    }
+
+    let dot = dot(&cache)?;
+
+    if opt.output.as_os_str() == "-" {
+        std::io::stdout().write_all(dot.as_bytes())?;
+    } else {
+        std::fs::write(&opt.output, dot)?;
+        eprintln!("Wrote the graph to {}", opt.output.display());
+    }
+
+    Ok(())
+}
+
+fn main() -> Result<()> {
+    pw::init();
+
+    let opt = Opt::parse();
+    run(opt)?;
+
+    unsafe {
+        pw::deinit();
+    }
+
+    Ok(())
+}
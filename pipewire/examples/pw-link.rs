@@ -0,0 +1,349 @@
+// Copyright The pipewire-rs Contributors.
+// SPDX-License-Identifier: MIT
+
+//! A simplified rust equivalent of the `pw-link` tool, listing, creating and destroying links between ports.
+
+use anyhow::{anyhow, bail, Result};
+use clap::Parser;
+use pipewire as pw;
+use std::{cell::Cell, rc::Rc};
+
+use pw::{
+    registry::{cache::RegistryCache, OwnedGlobalObject},
+    types::ObjectType,
+};
+
+#[derive(Parser)]
+#[clap(
+    name = "pw-link",
+    about = "List, create and destroy links between ports"
+)]
+struct Opt {
+    #[clap(short, long, help = "The name of the remote to connect to")]
+    remote: Option<String>,
+    #[clap(short, long, help = "List the existing links instead of the ports")]
+    links: bool,
+    #[clap(
+        short,
+        long,
+        help = "Destroy the link between the ports instead of creating it"
+    )]
+    disconnect: bool,
+    #[clap(
+        short,
+        long = "prop",
+        help = "An extra `key=value` property of the created links, may be repeated"
+    )]
+    props: Vec<String>,
+    #[clap(
+        help = "The output port, as an id or as `node:port`, or the output node to link all ports of"
+    )]
+    output: Option<String>,
+    #[clap(
+        help = "The input port, as an id or as `node:port`, or the input node to link all ports of"
+    )]
+    input: Option<String>,
+}
+
+fn prop<'a>(global: &'a OwnedGlobalObject, key: &str) -> Option<&'a str> {
+    global.props.as_ref().and_then(|props| props.get(key))
+}
+
+fn prop_u32(global: &OwnedGlobalObject, key: &str) -> Option<u32> {
+    prop(global, key).and_then(|value| value.parse().ok())
+}
+
+/// Get the `node:port` name of a port.
+fn port_name(cache: &RegistryCache, port: &OwnedGlobalObject) -> String {
+    let node_name = prop_u32(port, *pw::keys::NODE_ID)
+        .and_then(|node_id| cache.get(node_id))
+        .and_then(|node| prop(&node, *pw::keys::NODE_NAME).map(str::to_string))
+        .unwrap_or_default();
+    let port_name = prop(port, *pw::keys::PORT_NAME).unwrap_or_default();
+
+    format!("{}:{}", node_name, port_name)
+}
+
+/// Find a port with the provided `direction`, either by id or by `node:port` name.
+fn find_port(cache: &RegistryCache, name: &str, direction: &str) -> Result<OwnedGlobalObject> {
+    cache
+        .of_type(&ObjectType::Port)
+        .into_iter()
+        .filter(|port| prop(port, *pw::keys::PORT_DIRECTION) == Some(direction))
+        .find(|port| name.parse::<u32>().ok() == Some(port.id) || port_name(cache, port) == name)
+        .ok_or_else(|| anyhow!("No {} port named {}", direction, name))
+}
+
+/// Find a node either by id or by name.
+fn find_node(cache: &RegistryCache, name: &str) -> Option<OwnedGlobalObject> {
+    cache.of_type(&ObjectType::Node).into_iter().find(|node| {
+        name.parse::<u32>().ok() == Some(node.id) || prop(node, *pw::keys::NODE_NAME) == Some(name)
+    })
+}
+
+/// Pair the output ports of the node `output` with the input ports of the node `input`.
+///
+/// If all the ports have an `audio.channel` property, ports are paired by channel position,
+/// so that `FL` is linked to `FL` and `FR` to `FR`.
+/// Otherwise, they are paired by their index in the node.
+fn match_ports(
+    cache: &RegistryCache,
+    output: u32,
+    input: u32,
+) -> Vec<(OwnedGlobalObject, OwnedGlobalObject)> {
+    let ports = |node_id, direction| {
+        cache
+            .ports_of_node(node_id)
+            .into_iter()
+            .filter(|port| prop(port, *pw::keys::PORT_DIRECTION) == Some(direction))
+            .collect::<Vec<_>>()
+    };
+    let outputs = ports(output, "out");
+    let mut inputs = ports(input, "in");
+
+    let channel =
+        |port: &OwnedGlobalObject| prop(port, *pw::keys::AUDIO_CHANNEL).map(str::to_string);
+    let by_channel = outputs
+        .iter()
+        .chain(inputs.iter())
+        .all(|port| channel(port).is_some());
+
+    if by_channel {
+        outputs
+            .into_iter()
+            .filter_map(|output| {
+                let index = inputs
+                    .iter()
+                    .position(|input| channel(input) == channel(&output))?;
+                Some((output, inputs.remove(index)))
+            })
+            .collect()
+    } else {
+        outputs.into_iter().zip(inputs).collect()
+    }
+}
+
+/// Get the pairs of ports to link, either the two ports provided, or the matching ports of two nodes.
+fn resolve(
+    cache: &RegistryCache,
+    output: &str,
+    input: &str,
+) -> Result<Vec<(OwnedGlobalObject, OwnedGlobalObject)>> {
+    if let (Some(output_node), Some(input_node)) =
+        (find_node(cache, output), find_node(cache, input))
+    {
+        let pairs = match_ports(cache, output_node.id, input_node.id);
+        if pairs.is_empty() {
+            bail!("No matching ports between {} and {}", output, input);
+        }
+        Ok(pairs)
+    } else {
+        Ok(vec![(
+            find_port(cache, output, "out")?,
+            find_port(cache, input, "in")?,
+        )])
+    }
+}
+
+/// Process all pending events, including the reply to any method called before.
+fn roundtrip(connection: &pw::Connection) -> Result<()> {
+    let done = Rc::new(Cell::new(false));
+    let done_clone = done.clone();
+    let pending = connection.core().sync(0)?;
+
+    let _listener = connection
+        .core()
+        .add_listener_local()
+        .done(move |id, seq| {
+            if id == pw::core::PW_ID_CORE && seq == pending {
+                done_clone.set(true);
+            }
+        })
+        .register();
+
+    while !done.get() {
+        connection
+            .main_loop()
+            .loop_()
+            .iterate(std::time::Duration::from_secs(1));
+    }
+
+    Ok(())
+}
+
+fn list_ports(cache: &RegistryCache) {
+    for direction in ["out", "in"] {
+        println!(
+            "{} ports:",
+            if direction == "out" {
+                "Output"
+            } else {
+                "Input"
+            }
+        );
+
+        for port in cache
+            .of_type(&ObjectType::Port)
+            .iter()
+            .filter(|port| prop(port, *pw::keys::PORT_DIRECTION) == Some(direction))
+        {
+            println!("  {:>4} {}", port.id, port_name(cache, port));
+        }
+    }
+}
+
+fn list_links(cache: &RegistryCache) {
+    for link in cache.of_type(&ObjectType::Link) {
+        let name = |key| {
+            prop_u32(&link, key)
+                .and_then(|id| cache.get(id).map(|port| port_name(cache, &port)))
+                .unwrap_or_else(|| String::from("?"))
+        };
+
+        println!(
+            "{:>4} {} -> {}",
+            link.id,
+            name(*pw::keys::LINK_OUTPUT_PORT),
+            name(*pw::keys::LINK_INPUT_PORT)
+        );
+    }
+}
+
+/// Link the output and input ports, with the extra `key=value` link properties in `props`.
+///
+/// The link factory only honors the port and node ids, `object.linger`, `link.passive` and
+/// `link.feedback`: the format of a link is always negotiated from the formats enumerated by
+/// both ports, and cannot be constrained by the link itself.
+/// For bit-perfect routing, the format has to be fixed on the nodes instead, for example with
+/// the `audio.format` and `audio.rate` properties of a stream.
+fn link(
+    connection: &pw::Connection,
+    cache: &RegistryCache,
+    output: &str,
+    input: &str,
+    props: &[String],
+) -> Result<()> {
+    let factory = cache
+        .of_type(&ObjectType::Factory)
+        .into_iter()
+        .find(|factory| {
+            prop(factory, *pw::keys::FACTORY_TYPE_NAME) == Some(ObjectType::Link.to_str())
+        })
+        .ok_or_else(|| anyhow!("No link factory found"))?;
+    let factory_name =
+        prop(&factory, *pw::keys::FACTORY_NAME).ok_or_else(|| anyhow!("Factory has no name"))?;
+
+    let extra = props
+        .iter()
+        .map(|prop| {
+            prop.split_once('=')
+                .ok_or_else(|| anyhow!("Invalid property {}, expected key=value", prop))
+        })
+        .collect::<Result<Vec<_>>>()?;
+
+    let node_id = |port: &OwnedGlobalObject| {
+        prop(port, *pw::keys::NODE_ID)
+            .unwrap_or_default()
+            .to_string()
+    };
+    let mut links = Vec::new();
+    for (output, input) in resolve(cache, output, input)? {
+        let mut props = pw::properties::properties! {
+            *pw::keys::LINK_OUTPUT_NODE => node_id(&output),
+            *pw::keys::LINK_OUTPUT_PORT => output.id.to_string(),
+            *pw::keys::LINK_INPUT_NODE => node_id(&input),
+            *pw::keys::LINK_INPUT_PORT => input.id.to_string(),
+            // Keep the link on the remote once we exit.
+            *pw::keys::OBJECT_LINGER => "1"
+        };
+        for (key, value) in &extra {
+            props.insert(*key, *value);
+        }
+
+        links.push(
+            connection
+                .core()
+                .create_object::<pw::link::Link>(factory_name, &props)?,
+        );
+    }
+
+    // Wait for the links to be created on the server before exiting.
+    roundtrip(connection)
+}
+
+fn unlink(
+    connection: &pw::Connection,
+    cache: &RegistryCache,
+    output: &str,
+    input: &str,
+) -> Result<()> {
+    for (output, input) in resolve(cache, output, input)? {
+        let link = cache
+            .of_type(&ObjectType::Link)
+            .into_iter()
+            .find(|link| {
+                prop_u32(link, *pw::keys::LINK_OUTPUT_PORT) == Some(output.id)
+                    && prop_u32(link, *pw::keys::LINK_INPUT_PORT) == Some(input.id)
+            })
+            .ok_or_else(|| {
+                anyhow!(
+                    "{} and {} are not linked",
+                    port_name(cache, &output),
+                    port_name(cache, &input)
+                )
+            })?;
+
+        connection
+            .registry()
+            .destroy_global(link.id)
+            .into_sync_result()?;
+    }
+
+    roundtrip(connection)
+}
+
+fn run(opt: Opt) -> Result<()> {
+    let props = opt.remote.map(|remote| {
+        pw::properties::properties! {
+            *pw::keys::REMOTE_NAME => remote
+        }
+    });
+    let connection = pw::connect(props)?;
+
+    let cache = RegistryCache::new(connection.registry());
+    while !cache.is_synced() {
+        connection
+            .main_loop()
+            .loop_()
+            .iterate(std::time::Duration::from_secs(1));
+    }
+
+    match (opt.output, opt.input) {
+        (Some(output), Some(input)) if opt.disconnect => {
+            unlink(&connection, &cache, &output, &input)
+        }
+        (Some(output), Some(input)) => link(&connection, &cache, &output, &input, &opt.props),
+        (None, None) if opt.links => {
+            list_links(&cache);
+            Ok(())
+        }
+        (None, None) => {
+            list_ports(&cache);
+            Ok(())
+        }
+        _ => bail!("Both an output and an input port are needed"),
+    }
+}
+
+fn main() -> Result<()> {
+    pw::init();
+
+    let opt = Opt::parse();
+    run(opt)?;
+
+    unsafe {
+        pw::deinit();
+    }
+
+    Ok(())
+}
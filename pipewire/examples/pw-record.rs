@@ -0,0 +1,311 @@
+// Copyright The pipewire-rs Contributors.
+// SPDX-License-Identifier: MIT
+
+//! A simplified rust equivalent of the `pw-record` tool, recording stereo audio to a WAV file
+//! until interrupted with Ctrl-C.
+
+use anyhow::{bail, Result};
+use clap::Parser;
+use pipewire as pw;
+use pw::{
+    loop_::Signal,
+    properties::properties,
+    spa,
+    stream::{StreamFlags, StreamTarget},
+};
+use spa::{
+    param::{
+        audio::{AudioFormat, AudioInfoRaw, ChannelPosition},
+        format::{MediaSubtype, MediaType},
+        format_utils, ParamType,
+    },
+    pod::{serialize::PodSerializer, ChoiceValue, Object, Pod, PodBuf, Property, Value},
+    utils::{Choice, ChoiceEnum, ChoiceFlags, Id, SpaTypes},
+};
+use std::{
+    cell::RefCell,
+    fs::File,
+    io::{BufWriter, Seek, SeekFrom, Write},
+    path::PathBuf,
+    rc::Rc,
+};
+
+const CHANNELS: u32 = 2;
+
+#[derive(Parser)]
+#[clap(name = "pw-record", about = "Record stereo audio to a WAV file")]
+struct Opt {
+    #[clap(short, long, help = "The name of the remote to connect to")]
+    remote: Option<String>,
+    #[clap(
+        short,
+        long,
+        help = "The node to record from, as an id or, with PipeWire 0.3.44 or later, a name"
+    )]
+    target: Option<String>,
+    #[clap(short = 'R', long, default_value_t = 48000, help = "The sample rate")]
+    rate: u32,
+    #[clap(help = "The WAV file to write")]
+    file: PathBuf,
+}
+
+/// A WAV file being written.
+///
+/// The header is written with empty sizes when the file is created,
+/// and filled in by [`finish`](Self::finish) once the size of the data is known.
+struct WavWriter {
+    writer: BufWriter<File>,
+    format: Option<AudioInfoRaw>,
+    data_len: u32,
+}
+
+impl WavWriter {
+    const HEADER_LEN: u32 = 44;
+    /// The largest data size that the RIFF size of the header, which includes the header, can represent.
+    /// The sizes of longer recordings are capped to it.
+    const MAX_DATA_LEN: u32 = u32::MAX - Self::HEADER_LEN;
+
+    fn create(path: &PathBuf) -> Result<Self> {
+        let mut writer = BufWriter::new(File::create(path)?);
+        writer.write_all(&[0; Self::HEADER_LEN as usize])?;
+
+        Ok(Self {
+            writer,
+            format: None,
+            data_len: 0,
+        })
+    }
+
+    fn write(&mut self, data: &[u8]) -> Result<()> {
+        self.writer.write_all(data)?;
+        let len = u32::try_from(data.len()).unwrap_or(u32::MAX);
+        self.data_len = self.data_len.saturating_add(len).min(Self::MAX_DATA_LEN);
+        Ok(())
+    }
+
+    /// Write the final header, using the negotiated format.
+    fn finish(mut self) -> Result<()> {
+        let Some(format) = self.format else {
+            bail!("No format was negotiated, nothing was recorded");
+        };
+
+        // The WAVE_FORMAT tags for PCM and IEEE float samples.
+        let (tag, bits): (u16, u16) = match format.format() {
+            AudioFormat::S16LE => (1, 16),
+            AudioFormat::F32LE => (3, 32),
+            other => bail!("Unsupported sample format {:?}", other),
+        };
+        let channels = format.channels() as u16;
+        let block_align = channels * bits / 8;
+
+        let mut header = Vec::with_capacity(Self::HEADER_LEN as usize);
+        header.extend_from_slice(b"RIFF");
+        header.extend_from_slice(
+            &(Self::HEADER_LEN - 8)
+                .saturating_add(self.data_len)
+                .to_le_bytes(),
+        );
+        header.extend_from_slice(b"WAVEfmt ");
+        header.extend_from_slice(&16u32.to_le_bytes());
+        header.extend_from_slice(&tag.to_le_bytes());
+        header.extend_from_slice(&channels.to_le_bytes());
+        header.extend_from_slice(&format.rate().to_le_bytes());
+        header.extend_from_slice(&(format.rate() * block_align as u32).to_le_bytes());
+        header.extend_from_slice(&block_align.to_le_bytes());
+        header.extend_from_slice(&bits.to_le_bytes());
+        header.extend_from_slice(b"data");
+        header.extend_from_slice(&self.data_len.to_le_bytes());
+
+        self.writer.seek(SeekFrom::Start(0))?;
+        self.writer.write_all(&header)?;
+        self.writer.flush()?;
+
+        Ok(())
+    }
+}
+
+/// Build the `EnumFormat` param, accepting interleaved stereo F32 or S16 samples at `rate`.
+fn enum_format(rate: u32) -> PodBuf {
+    let mut info = AudioInfoRaw::new();
+    info.set_format(AudioFormat::F32LE);
+    info.set_rate(rate);
+    info.set_channels(CHANNELS);
+    let mut position = [0; 64];
+    position[0] = ChannelPosition::FL.as_raw();
+    position[1] = ChannelPosition::FR.as_raw();
+    info.set_position(position);
+
+    // Replace the fixed format by a choice, preferring F32.
+    let mut properties: Vec<Property> = info.into();
+    for property in &mut properties {
+        if property.key == spa::sys::SPA_FORMAT_AUDIO_format {
+            property.value = Value::Choice(ChoiceValue::Id(Choice(
+                ChoiceFlags::empty(),
+                ChoiceEnum::Enum {
+                    default: Id(AudioFormat::F32LE.as_raw()),
+                    alternatives: vec![
+                        Id(AudioFormat::F32LE.as_raw()),
+                        Id(AudioFormat::S16LE.as_raw()),
+                    ],
+                },
+            )));
+        }
+    }
+
+    let object = Object {
+        type_: SpaTypes::ObjectParamFormat.as_raw(),
+        id: ParamType::EnumFormat.as_raw(),
+        properties,
+    };
+
+    PodSerializer::serialize_to_buf(&Value::Object(object)).expect("Failed to serialize the format")
+}
+
+fn target(target: Option<String>) -> Result<StreamTarget> {
+    match target {
+        None => Ok(StreamTarget::Default),
+        Some(target) => match target.parse() {
+            Ok(id) => Ok(StreamTarget::NodeId(id)),
+            Err(_) => target_by_name(target),
+        },
+    }
+}
+
+#[cfg(feature = "v0_3_44")]
+fn target_by_name(name: String) -> Result<StreamTarget> {
+    Ok(StreamTarget::Name(name))
+}
+
+#[cfg(not(feature = "v0_3_44"))]
+fn target_by_name(_name: String) -> Result<StreamTarget> {
+    bail!("Targets by name require PipeWire 0.3.44 or later, use the node id instead")
+}
+
+fn record(opt: Opt) -> Result<()> {
+    let main_loop = pw::main_loop::MainLoop::new(None)?;
+
+    let main_loop_weak = main_loop.downgrade();
+    let _signals = main_loop.add_signals_local(&[Signal::SIGINT, Signal::SIGTERM], move |_| {
+        if let Some(main_loop) = main_loop_weak.upgrade() {
+            main_loop.quit();
+        }
+    });
+
+    let context = pw::context::Context::new(&main_loop)?;
+    let props = opt.remote.map(|remote| {
+        properties! {
+            *pw::keys::REMOTE_NAME => remote
+        }
+    });
+    let core = context.connect(props)?;
+
+    let wav = Rc::new(RefCell::new(WavWriter::create(&opt.file)?));
+
+    let stream = pw::stream::Stream::new(
+        &core,
+        "pw-record",
+        properties! {
+            *pw::keys::MEDIA_TYPE => "Audio",
+            *pw::keys::MEDIA_CATEGORY => "Capture",
+            *pw::keys::MEDIA_ROLE => "Production",
+        },
+    )?;
+
+    let format = enum_format(opt.rate);
+
+    let main_loop_weak = main_loop.downgrade();
+    let offered = format.clone();
+    let listener = stream
+        .add_local_listener_with_user_data(wav.clone())
+        .state_changed(move |_, _, _, new| {
+            if let pw::stream::StreamState::Error(message) = new {
+                eprintln!("Stream error: {}", message);
+                // Most errors are negotiation failures, show what was offered to the graph.
+                eprintln!("Offered formats:\n{}", spa::pod::to_debug_string(&offered));
+                if let Some(main_loop) = main_loop_weak.upgrade() {
+                    main_loop.quit();
+                }
+            }
+        })
+        .param_changed(|_, wav, id, param| {
+            let Some(param) = param else {
+                return;
+            };
+            if id != ParamType::Format.as_raw() {
+                return;
+            }
+
+            let mut format = AudioInfoRaw::new();
+            let is_raw_audio = matches!(
+                format_utils::parse_format(param),
+                Ok((MediaType::Audio, MediaSubtype::Raw))
+            );
+            if !is_raw_audio || format.parse(param).is_err() {
+                eprintln!(
+                    "Ignoring unsupported format:\n{}",
+                    spa::pod::to_debug_string(param.as_bytes())
+                );
+                return;
+            }
+
+            eprintln!(
+                "Recording {:?} at {} Hz, {} channels",
+                format.format(),
+                format.rate(),
+                format.channels()
+            );
+            wav.borrow_mut().format = Some(format);
+        })
+        .process(|stream, wav| {
+            let Some(mut buffer) = stream.dequeue_buffer() else {
+                return;
+            };
+            let Some(data) = buffer.datas_mut().first_mut() else {
+                return;
+            };
+
+            let offset = data.chunk().offset() as usize;
+            let size = data.chunk().size() as usize;
+            if let Some(samples) = data.data().and_then(|d| d.get(offset..offset + size)) {
+                if let Err(e) = wav.borrow_mut().write(samples) {
+                    eprintln!("Failed to write the samples: {}", e);
+                }
+            }
+        })
+        .register()?;
+
+    let mut params = [Pod::from_bytes(&format).unwrap()];
+
+    stream.connect_to(
+        spa::utils::Direction::Input,
+        &target(opt.target)?,
+        StreamFlags::AUTOCONNECT | StreamFlags::MAP_BUFFERS,
+        &mut params,
+    )?;
+
+    eprintln!("Recording to {}, press Ctrl-C to stop", opt.file.display());
+    main_loop.run();
+
+    // Stop the stream before writing the final header.
+    stream.disconnect()?;
+    drop(listener);
+
+    let wav = Rc::try_unwrap(wav)
+        .ok()
+        .expect("The listener holding the file was dropped")
+        .into_inner();
+    wav.finish()
+}
+
+fn main() -> Result<()> {
+    pw::init();
+
+    let opt = Opt::parse();
+    record(opt)?;
+
+    unsafe {
+        pw::deinit();
+    }
+
+    Ok(())
+}
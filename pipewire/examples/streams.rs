@@ -177,13 +177,9 @@ pub fn main() -> Result<(), pw::Error> {
             }
         ),
     );
-    let values: Vec<u8> = pw::spa::pod::serialize::PodSerializer::serialize(
-        std::io::Cursor::new(Vec::new()),
-        &pw::spa::pod::Value::Object(obj),
-    )
-    .unwrap()
-    .0
-    .into_inner();
+    let values =
+        pw::spa::pod::serialize::PodSerializer::serialize_to_buf(&pw::spa::pod::Value::Object(obj))
+            .unwrap();
 
     let mut params = [Pod::from_bytes(&values).unwrap()];
 
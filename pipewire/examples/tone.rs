@@ -4,7 +4,11 @@
 //! This file is a rustic interpretation of the the [PipeWire Tutorial 4][tut]
 //!
 //! tut: https://docs.pipewire.org/page_tutorial4.html
+//!
+//! It plays a sine wave until interrupted, to the default device or to the node given with `--target`.
 
+use anyhow::{bail, Result};
+use clap::Parser;
 use pipewire as pw;
 use pw::{properties::properties, spa};
 use spa::pod::Pod;
@@ -15,7 +19,26 @@ pub const DEFAULT_VOLUME: f64 = 0.7;
 pub const PI_2: f64 = std::f64::consts::PI + std::f64::consts::PI;
 pub const CHAN_SIZE: usize = std::mem::size_of::<i16>();
 
-pub fn main() -> Result<(), pw::Error> {
+#[derive(Parser)]
+#[clap(name = "tone", about = "Play a sine wave")]
+struct Opt {
+    #[clap(
+        short,
+        long,
+        default_value_t = 440.0,
+        help = "The frequency of the tone, in Hz"
+    )]
+    freq: f64,
+    #[clap(short, long, help = "The id of the node to play to")]
+    target: Option<u32>,
+}
+
+pub fn main() -> Result<()> {
+    let opt = Opt::parse();
+    if !(opt.freq > 0.0 && opt.freq < DEFAULT_RATE as f64 / 2.0) {
+        bail!("The frequency must be between 0 and half the sample rate");
+    }
+
     pw::init();
     let mainloop = pw::main_loop::MainLoop::new(None)?;
     let context = pw::context::Context::new(&mainloop)?;
@@ -36,7 +59,7 @@ pub fn main() -> Result<(), pw::Error> {
 
     let _listener = stream
         .add_local_listener_with_user_data(data)
-        .process(|stream, acc| match stream.dequeue_buffer() {
+        .process(move |stream, acc| match stream.dequeue_buffer() {
             None => println!("No buffer received"),
             Some(mut buffer) => {
                 let datas = buffer.datas_mut();
@@ -45,7 +68,7 @@ pub fn main() -> Result<(), pw::Error> {
                 let n_frames = if let Some(slice) = data.data() {
                     let n_frames = slice.len() / stride;
                     for i in 0..n_frames {
-                        *acc += PI_2 * 440.0 / DEFAULT_RATE as f64;
+                        *acc += PI_2 * opt.freq / DEFAULT_RATE as f64;
                         if *acc >= PI_2 {
                             *acc -= PI_2
                         }
@@ -78,23 +101,20 @@ pub fn main() -> Result<(), pw::Error> {
     position[1] = spa_sys::SPA_AUDIO_CHANNEL_FR;
     audio_info.set_position(position);
 
-    let values: Vec<u8> = pw::spa::pod::serialize::PodSerializer::serialize(
-        std::io::Cursor::new(Vec::new()),
+    let values = pw::spa::pod::serialize::PodSerializer::serialize_to_buf(
         &pw::spa::pod::Value::Object(pw::spa::pod::Object {
             type_: spa_sys::SPA_TYPE_OBJECT_Format,
             id: spa_sys::SPA_PARAM_EnumFormat,
             properties: audio_info.into(),
         }),
     )
-    .unwrap()
-    .0
-    .into_inner();
+    .unwrap();
 
     let mut params = [Pod::from_bytes(&values).unwrap()];
 
     stream.connect(
         spa::utils::Direction::Output,
-        None,
+        opt.target,
         pw::stream::StreamFlags::AUTOCONNECT
             | pw::stream::StreamFlags::MAP_BUFFERS
             | pw::stream::StreamFlags::RT_PROCESS,
@@ -1,6 +1,9 @@
 use super::stream::StreamRef;
 
-use spa::buffer::Data;
+use spa::buffer::{
+    meta::{Meta, MetaType},
+    Data,
+};
 use std::convert::TryFrom;
 use std::ptr::NonNull;
 
@@ -37,6 +40,28 @@ impl Buffer<'_> {
         slice_of_data
     }
 
+    /// Get all the metadata attached to the buffer.
+    pub fn metas(&self) -> &[Meta] {
+        let buffer: *mut spa_sys::spa_buffer = unsafe { self.buf.as_ref().buffer };
+
+        if !buffer.is_null() && unsafe { (*buffer).n_metas > 0 && !(*buffer).metas.is_null() } {
+            unsafe {
+                let metas = (*buffer).metas as *const Meta;
+                std::slice::from_raw_parts(metas, usize::try_from((*buffer).n_metas).unwrap())
+            }
+        } else {
+            &[]
+        }
+    }
+
+    /// Find the metadata of the given type attached to the buffer, if any.
+    ///
+    /// The returned [`Meta`] can be interpreted with its typed accessors,
+    /// such as [`Meta::header`] for a [`MetaType::Header`].
+    pub fn find_meta(&self, type_: MetaType) -> Option<&Meta> {
+        self.metas().iter().find(|meta| meta.type_() == type_)
+    }
+
     #[cfg(feature = "v0_3_49")]
     pub fn requested(&self) -> u64 {
         unsafe { self.buf.as_ref().requested }
@@ -50,3 +75,79 @@ impl Drop for Buffer<'_> {
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use spa::buffer::meta::MetaHeaderFlags;
+    use spa::utils::{Point, Rectangle};
+    use std::mem;
+
+    fn meta<T>(type_: MetaType, data: &mut T) -> spa_sys::spa_meta {
+        spa_sys::spa_meta {
+            type_: type_.as_raw(),
+            size: mem::size_of::<T>() as u32,
+            data: (data as *mut T).cast(),
+        }
+    }
+
+    #[test]
+    fn find_meta() {
+        let mut header: spa_sys::spa_meta_header = unsafe { mem::zeroed() };
+        header.flags = spa_sys::SPA_META_HEADER_FLAG_DISCONT;
+        header.pts = 1_000_000;
+        header.seq = 7;
+
+        let mut crop: spa_sys::spa_meta_region = unsafe { mem::zeroed() };
+        crop.region.position = Point::new(10, 20).as_raw();
+        crop.region.size = Rectangle::new(640, 480).as_raw();
+
+        let mut cursor: spa_sys::spa_meta_cursor = unsafe { mem::zeroed() };
+        cursor.id = 1;
+        cursor.position = Point::new(100, 200).as_raw();
+
+        let mut metas = [
+            meta(MetaType::Header, &mut header),
+            meta(MetaType::VideoCrop, &mut crop),
+            meta(MetaType::Cursor, &mut cursor),
+        ];
+        let mut spa_buffer: spa_sys::spa_buffer = unsafe { mem::zeroed() };
+        spa_buffer.n_metas = metas.len() as u32;
+        spa_buffer.metas = metas.as_mut_ptr();
+        let mut pw_buffer: pw_sys::pw_buffer = unsafe { mem::zeroed() };
+        pw_buffer.buffer = &mut spa_buffer;
+
+        // `pw_stream` is an opaque, zero-sized type, so a dangling reference to it is valid.
+        // The stream is only used to queue the buffer back when it is dropped, which is skipped.
+        let stream: &StreamRef = unsafe { NonNull::dangling().as_ref() };
+        let buffer =
+            mem::ManuallyDrop::new(unsafe { Buffer::from_raw(&mut pw_buffer, stream) }.unwrap());
+
+        assert_eq!(buffer.metas().len(), 3);
+
+        let header = buffer
+            .find_meta(MetaType::Header)
+            .unwrap()
+            .header()
+            .unwrap();
+        assert_eq!(header.flags(), MetaHeaderFlags::DISCONT);
+        assert_eq!(header.pts(), 1_000_000);
+        assert_eq!(header.seq(), 7);
+
+        let crop = buffer.find_meta(MetaType::VideoCrop).unwrap();
+        assert!(crop.header().is_none());
+        let crop = crop.video_crop().unwrap();
+        assert_eq!(crop.position(), Point::new(10, 20));
+        assert_eq!(crop.size(), Rectangle::new(640, 480));
+
+        let cursor = buffer
+            .find_meta(MetaType::Cursor)
+            .unwrap()
+            .cursor()
+            .unwrap();
+        assert_eq!(cursor.id(), 1);
+        assert_eq!(cursor.position(), Point::new(100, 200));
+
+        assert!(buffer.find_meta(MetaType::VideoDamage).is_none());
+    }
+}
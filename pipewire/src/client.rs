@@ -5,18 +5,16 @@ use bitflags::bitflags;
 use libc::c_void;
 use std::ops::Deref;
 use std::pin::Pin;
-use std::{
-    ffi::{CStr, CString},
-    ptr,
-};
+use std::{ffi::CStr, ptr};
 use std::{fmt, mem};
 
 use crate::{
+    listener_tracking::TrackedListener,
     permissions::Permission,
     proxy::{Listener, Proxy, ProxyT},
     types::ObjectType,
 };
-use spa::spa_interface_call_method;
+use spa::{spa_interface_call_method, spa_interface_call_method_checked};
 
 #[derive(Debug)]
 pub struct Client {
@@ -54,10 +52,15 @@ impl Client {
         }
     }
 
-    pub fn error(&self, id: u32, res: i32, message: &str) {
-        let message = CString::new(message).expect("Null byte in message parameter");
+    /// Send an error to the client.
+    ///
+    /// # Errors
+    /// Returns [`Error::NulByte`](crate::Error::NulByte) if `message` contains a 0 byte.
+    pub fn error(&self, id: u32, res: i32, message: &str) -> Result<(), crate::Error> {
+        let message = crate::utils::null_terminated(message)?;
         let message_cstr = message.as_c_str();
-        Client::error_cstr(self, id, res, message_cstr)
+        Client::error_cstr(self, id, res, message_cstr);
+        Ok(())
     }
 
     pub fn error_cstr(&self, id: u32, res: i32, message: &CStr) {
@@ -103,7 +106,7 @@ impl Client {
                 pw_sys::pw_client_methods,
                 update_permissions,
                 permissions.len() as u32,
-                permissions.as_ptr().cast()
+                Permission::slice_as_raw(permissions).as_ptr()
             );
         }
     }
@@ -222,6 +225,7 @@ pub struct ClientListener {
     listener: Pin<Box<spa_sys::spa_hook>>,
     #[allow(dead_code)]
     data: Box<ListenerLocalCallbacks>,
+    _tracked: TrackedListener,
 }
 
 impl Listener for ClientListener {}
@@ -250,8 +254,19 @@ impl<'a> ClientListenerLocalBuilder<'a> {
         self
     }
 
+    /// Register the listener.
+    ///
+    /// # Panics
+    /// If the client does not implement `add_listener`. Use [`try_register`](Self::try_register) to handle this error.
     #[must_use]
     pub fn register(self) -> ClientListener {
+        self.try_register()
+            .expect("client does not implement add_listener")
+    }
+
+    /// Register the listener, returning an [`ENOTSUP`](nix::errno::Errno::ENOTSUP) error if the client
+    /// does not implement `add_listener`.
+    pub fn try_register(self) -> Result<ClientListener, crate::Error> {
         unsafe extern "C" fn client_events_info(
             data: *mut c_void,
             info: *const pw_sys::pw_client_info,
@@ -270,8 +285,10 @@ impl<'a> ClientListenerLocalBuilder<'a> {
             permissions: *const pw_sys::pw_permission,
         ) {
             let callbacks = (data as *mut ListenerLocalCallbacks).as_ref().unwrap();
-            let permissions =
-                std::slice::from_raw_parts(permissions.cast(), n_permissions as usize);
+            let permissions = Permission::slice_from_raw(std::slice::from_raw_parts(
+                permissions,
+                n_permissions as usize,
+            ));
 
             callbacks.permissions.as_ref().unwrap()(index, permissions);
         }
@@ -297,7 +314,7 @@ impl<'a> ClientListenerLocalBuilder<'a> {
             let mut listener: Pin<Box<spa_sys::spa_hook>> = Box::pin(mem::zeroed());
             let listener_ptr: *mut spa_sys::spa_hook = listener.as_mut().get_unchecked_mut();
 
-            spa_interface_call_method!(
+            let res = spa_interface_call_method_checked!(
                 client,
                 pw_sys::pw_client_methods,
                 add_listener,
@@ -306,13 +323,17 @@ impl<'a> ClientListenerLocalBuilder<'a> {
                 data as *mut _
             );
 
-            (listener, Box::from_raw(data))
+            let data = Box::from_raw(data);
+            res?;
+
+            (listener, data)
         };
 
-        ClientListener {
+        Ok(ClientListener {
             events: e,
             listener,
             data,
-        }
+            _tracked: TrackedListener::new("Client", self.client.proxy.as_ptr().cast()),
+        })
     }
 }
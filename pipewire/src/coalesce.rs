@@ -0,0 +1,115 @@
+// Copyright The pipewire-rs Contributors.
+// SPDX-License-Identifier: MIT
+
+//! Coalescing of bursts of updates, such as info events, into one update per loop iteration.
+
+use std::{cell::RefCell, rc::Rc};
+
+use crate::loop_::{EventSource, LoopRef};
+
+/// Coalesces consecutive updates for the same key, delivering only the latest one.
+///
+/// Updates are queued with [`push`](Self::push), and delivered to the callback on the next
+/// iteration of the loop, at most once per key.
+/// This is useful for UIs receiving many `info` or `param` events in a burst, for example during
+/// graph setup, to redraw once instead of for every event.
+///
+/// This trades latency for fewer updates: each update is delayed until the loop has dispatched
+/// all pending events, and intermediate updates for a key are dropped.
+///
+/// As the callbacks of info events only borrow their info, an owned representation
+/// of the info needs to be pushed.
+///
+/// ```no_run
+/// use pipewire::{coalesce::Coalescer, main_loop::MainLoop};
+///
+/// let mainloop = MainLoop::new(None).unwrap();
+/// let coalescer = Coalescer::new(mainloop.loop_(), |id: u32, state: String| {
+///     println!("node {} is now {}", id, state);
+/// });
+///
+/// // In a node info callback:
+/// coalescer.push(42, String::from("running"));
+/// ```
+pub struct Coalescer<'l, K, T> {
+    pending: Rc<RefCell<Vec<(K, T)>>>,
+    event: EventSource<'l>,
+}
+
+impl<'l, K, T> Coalescer<'l, K, T>
+where
+    K: PartialEq + 'static,
+    T: 'static,
+{
+    /// Create a new coalescer on `loop_`, delivering updates to `callback`.
+    #[must_use]
+    pub fn new<F>(loop_: &'l LoopRef, callback: F) -> Self
+    where
+        F: Fn(K, T) + 'static,
+    {
+        let pending: Rc<RefCell<Vec<(K, T)>>> = Rc::default();
+
+        let pending_clone = pending.clone();
+        let event = loop_.add_event(move || {
+            // Take the pending updates first, so the callback can push new ones.
+            let updates = pending_clone.take();
+            for (key, update) in updates {
+                callback(key, update);
+            }
+        });
+
+        Self { pending, event }
+    }
+
+    /// Queue an update for `key`, replacing any update for it that was not delivered yet.
+    pub fn push(&self, key: K, update: T) {
+        let mut pending = self.pending.borrow_mut();
+
+        match pending.iter_mut().find(|(k, _)| *k == key) {
+            Some((_, pending_update)) => *pending_update = update,
+            None => pending.push((key, update)),
+        }
+        drop(pending);
+
+        self.event.signal();
+    }
+
+    /// Returns `true` if there are updates waiting to be delivered.
+    pub fn has_pending(&self) -> bool {
+        !self.pending.borrow().is_empty()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::main_loop::MainLoop;
+    use std::time::Duration;
+
+    #[test]
+    fn coalesce_updates() {
+        let mainloop = MainLoop::new(None).unwrap();
+        let delivered: Rc<RefCell<Vec<(u32, &str)>>> = Rc::default();
+
+        let delivered_clone = delivered.clone();
+        let coalescer = Coalescer::new(mainloop.loop_(), move |id, state| {
+            delivered_clone.borrow_mut().push((id, state));
+        });
+
+        coalescer.push(1, "creating");
+        coalescer.push(2, "suspended");
+        coalescer.push(1, "running");
+        assert!(coalescer.has_pending());
+        assert!(delivered.borrow().is_empty());
+
+        for _ in 0..10 {
+            if !coalescer.has_pending() {
+                break;
+            }
+            mainloop.loop_().iterate(Duration::from_millis(100));
+        }
+
+        assert!(!coalescer.has_pending());
+        assert_eq!(*delivered.borrow(), [(1, "running"), (2, "suspended")]);
+    }
+}
@@ -0,0 +1,75 @@
+// Copyright The pipewire-rs Contributors.
+// SPDX-License-Identifier: MIT
+
+use crate::{
+    context::Context, core::Core, main_loop::MainLoop, properties::Properties, registry::Registry,
+    Error,
+};
+
+/// The basic objects needed by most programs, as created by [`connect`].
+///
+/// The objects are dropped in the right order, the [`Registry`] first and the [`MainLoop`] last.
+#[derive(Debug)]
+pub struct Connection {
+    core: Core,
+    context: Context,
+    main_loop: MainLoop,
+}
+
+impl Connection {
+    pub fn main_loop(&self) -> &MainLoop {
+        &self.main_loop
+    }
+
+    pub fn context(&self) -> &Context {
+        &self.context
+    }
+
+    pub fn core(&self) -> &Core {
+        &self.core
+    }
+
+    pub fn registry(&self) -> &Registry {
+        self.core
+            .registry()
+            .expect("registry is created by connect()")
+    }
+}
+
+/// Create a new [`MainLoop`] and [`Context`], connect to the remote and get its [`Registry`].
+///
+/// The registry is the one shared by [`Core::registry`].
+///
+/// This is a shortcut for the usual sequence of calls, with `properties` being passed to
+/// [`Context::connect`], for example to select the remote to connect to with [`REMOTE_NAME`](crate::keys::REMOTE_NAME).
+/// When more control is needed, such as using a [`ThreadLoop`](crate::thread_loop::ThreadLoop) or custom context properties,
+/// create each object individually instead.
+///
+/// ```no_run
+/// fn main() -> Result<(), pipewire::Error> {
+///     let connection = pipewire::connect(None)?;
+///
+///     let _listener = connection
+///         .registry()
+///         .add_listener_local()
+///         .global(|global| println!("New global: {}", global))
+///         .register();
+///
+///     connection.main_loop().run();
+///
+///     Ok(())
+/// }
+/// ```
+pub fn connect(properties: Option<Properties>) -> Result<Connection, Error> {
+    let main_loop = MainLoop::new(None)?;
+    let context = Context::new(&main_loop)?;
+    let core = context.connect(properties)?;
+    // Create the shared registry now, so that `Connection::registry` can not fail.
+    core.registry()?;
+
+    Ok(Connection {
+        core,
+        context,
+        main_loop,
+    })
+}
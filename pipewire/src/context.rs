@@ -7,6 +7,8 @@ use std::{
     os::unix::prelude::{IntoRawFd, OwnedFd},
     ptr,
     rc::Rc,
+    sync::atomic::{AtomicBool, Ordering},
+    time::Duration,
 };
 
 use crate::core::Core;
@@ -26,6 +28,21 @@ impl ContextRef {
         std::ptr::addr_of!(self.0).cast_mut()
     }
 
+    /// Get the effective properties of the context.
+    ///
+    /// Besides the properties the context was created with, these include the ones filled in
+    /// from the configuration file and by the library, so they can be used to check which settings
+    /// actually took effect:
+    /// ```no_run
+    /// # fn main() -> Result<(), pipewire::Error> {
+    /// let mainloop = pipewire::main_loop::MainLoop::new(None)?;
+    /// let context = pipewire::context::Context::new(&mainloop)?;
+    /// println!("mlock: {:?}", context.properties().get(*pipewire::keys::MEM_ALLOW_MLOCK));
+    /// # Ok(())
+    /// # }
+    /// ```
+    ///
+    /// Use [`to_owned`](PropertiesRef::to_owned) to keep a snapshot of the properties.
     pub fn properties(&self) -> &PropertiesRef {
         unsafe {
             let props = pw_sys::pw_context_get_properties(self.as_raw_ptr());
@@ -38,6 +55,51 @@ impl ContextRef {
             pw_sys::pw_context_update_properties(self.as_raw_ptr(), properties.as_raw_ptr());
         }
     }
+
+    /// Load the module `name` into the context, with the optional `args` in SPA-JSON format.
+    ///
+    /// The module stays loaded for as long as the context is alive.
+    ///
+    /// # Examples
+    /// Loading the realtime module with a custom priority, when it is not already loaded
+    /// by the configuration of the context:
+    /// ```no_run
+    /// # fn main() -> Result<(), pipewire::Error> {
+    /// let mainloop = pipewire::main_loop::MainLoop::new(None)?;
+    /// let context = pipewire::context::Context::new(&mainloop)?;
+    /// context.load_module(
+    ///     "libpipewire-module-rt",
+    ///     Some("{ rt.prio = 88 rt.time.soft = 200000 rt.time.hard = 200000 }"),
+    ///     None,
+    /// )?;
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn load_module(
+        &self,
+        name: &str,
+        args: Option<&str>,
+        properties: Option<Properties>,
+    ) -> Result<(), Error> {
+        let name = crate::utils::null_terminated(name)?;
+        let args = args.map(crate::utils::null_terminated).transpose()?;
+        let properties = properties.map_or(ptr::null_mut(), |props| props.into_raw());
+
+        let module = unsafe {
+            pw_sys::pw_context_load_module(
+                self.as_raw_ptr(),
+                name.as_ptr(),
+                args.as_deref().map_or(ptr::null(), std::ffi::CStr::as_ptr),
+                properties,
+            )
+        };
+
+        if module.is_null() {
+            Err(Error::CreationFailed)
+        } else {
+            Ok(())
+        }
+    }
 }
 
 #[derive(Clone, Debug)]
@@ -49,7 +111,7 @@ pub struct ContextInner {
     ptr: ptr::NonNull<pw_sys::pw_context>,
     /// Store the loop here, so that the loop is not dropped before the context, which may lead to
     /// undefined behaviour.
-    _loop: Box<dyn AsRef<LoopRef>>,
+    _loop: Rc<dyn AsRef<LoopRef>>,
 }
 
 impl fmt::Debug for ContextInner {
@@ -62,7 +124,7 @@ impl fmt::Debug for ContextInner {
 
 impl Context {
     fn new_internal<T: IsLoopRc>(loop_: &T, properties: Option<Properties>) -> Result<Self, Error> {
-        let loop_: Box<dyn AsRef<LoopRef>> = Box::new(loop_.clone());
+        let loop_: Rc<dyn AsRef<LoopRef>> = Rc::new(loop_.clone());
         let props = properties.map_or(ptr::null(), |props| props.into_raw()) as *mut _;
         let context = unsafe {
             pw_sys::pw_context_new((*loop_).as_ref().as_raw() as *const _ as *mut _, props, 0)
@@ -85,6 +147,11 @@ impl Context {
         Self::new_internal(loop_, Some(properties))
     }
 
+    /// Create a new context configured with the provided [`ContextOptions`].
+    pub fn with_options<T: IsLoopRc>(loop_: &T, options: ContextOptions) -> Result<Self, Error> {
+        Self::new_internal(loop_, Some(options.into_properties()))
+    }
+
     pub fn connect(&self, properties: Option<Properties>) -> Result<Core, Error> {
         let properties = properties.map_or(ptr::null_mut(), |p| p.into_raw());
 
@@ -96,6 +163,63 @@ impl Context {
         }
     }
 
+    /// Connect to the PipeWire daemon, retrying up to `max_attempts` times if the connection fails.
+    ///
+    /// This is useful for applications started before the daemon, as connecting fails until its
+    /// socket exists.
+    /// The thread sleeps for `backoff` after the first failed attempt, and the delay doubles after each
+    /// further failure, up to 5 seconds. A `backoff` of 100 to 250 milliseconds with 5 to 10 attempts
+    /// usually covers the startup of a desktop session.
+    ///
+    /// If `cancel` is set to `true`, for example from another thread, no further attempt is made
+    /// and [`Error::Cancelled`] is returned. The flag is also checked while sleeping.
+    ///
+    /// Returns the error of the last attempt if all of them failed.
+    ///
+    /// # Panics
+    /// If `max_attempts` is `0`.
+    pub fn connect_retry(
+        &self,
+        properties: Option<Properties>,
+        max_attempts: u32,
+        backoff: Duration,
+        cancel: Option<&AtomicBool>,
+    ) -> Result<Core, Error> {
+        const MAX_BACKOFF: Duration = Duration::from_secs(5);
+        const CANCEL_POLL: Duration = Duration::from_millis(50);
+
+        assert!(max_attempts > 0, "max_attempts must be at least 1");
+
+        let cancelled = || cancel.map_or(false, |c| c.load(Ordering::Relaxed));
+        let mut delay = backoff;
+        let mut attempt = 1;
+
+        loop {
+            if cancelled() {
+                return Err(Error::Cancelled);
+            }
+
+            match self.connect(properties.clone()) {
+                Ok(core) => return Ok(core),
+                Err(e) if attempt >= max_attempts => return Err(e),
+                Err(_) => {}
+            }
+
+            let mut remaining = delay;
+            while !remaining.is_zero() {
+                if cancelled() {
+                    return Err(Error::Cancelled);
+                }
+                let step = remaining.min(CANCEL_POLL);
+                std::thread::sleep(step);
+                remaining -= step;
+            }
+
+            delay = delay.saturating_mul(2).min(MAX_BACKOFF);
+            attempt += 1;
+        }
+    }
+
     pub fn connect_fd(&self, fd: OwnedFd, properties: Option<Properties>) -> Result<Core, Error> {
         let properties = properties.map_or(ptr::null_mut(), |p| p.into_raw());
 
@@ -109,6 +233,74 @@ impl Context {
     }
 }
 
+/// Typed options for the creation of a [`Context`].
+///
+/// This covers the commonly tuned context properties. Anything not modeled here can be set
+/// through [`properties`](Self::properties), which the typed options take precedence over.
+///
+/// ```no_run
+/// use pipewire::context::{Context, ContextOptions};
+///
+/// let mainloop = pipewire::main_loop::MainLoop::new(None).unwrap();
+/// let options = ContextOptions {
+///     mem_allow_mlock: Some(false),
+///     ..Default::default()
+/// };
+/// let context = Context::with_options(&mainloop, options).unwrap();
+/// ```
+///
+/// # Realtime priority
+/// The priority of the data threads is not a context property: it is set by `libpipewire-module-rt`,
+/// using its `rt.prio`, `rt.time.soft`, `rt.time.hard` and `nice.level` arguments.
+/// The module is usually loaded by the `context.modules` section of the configuration file,
+/// so the priority can be changed by using a custom configuration file with [`config_name`](Self::config_name).
+/// Otherwise, the module can be loaded with the needed arguments using [`ContextRef::load_module`].
+#[derive(Debug, Default, Clone)]
+pub struct ContextOptions {
+    /// The name of the configuration file to load, see [`CONFIG_NAME`](crate::keys::CONFIG_NAME).
+    pub config_name: Option<String>,
+    /// The name of the core, see [`CORE_NAME`](crate::keys::CORE_NAME).
+    pub core_name: Option<String>,
+    /// Whether memory may be locked with `mlock`, see [`MEM_ALLOW_MLOCK`](crate::keys::MEM_ALLOW_MLOCK).
+    pub mem_allow_mlock: Option<bool>,
+    /// Whether to warn about failures to lock memory, see [`MEM_WARN_MLOCK`](crate::keys::MEM_WARN_MLOCK).
+    pub mem_warn_mlock: Option<bool>,
+    /// The maximum alignment used for CPU optimizations, see [`CPU_MAX_ALIGN`](crate::keys::CPU_MAX_ALIGN).
+    pub cpu_max_align: Option<u32>,
+    /// Whether to load the D-Bus support plugin.
+    pub support_dbus: Option<bool>,
+    /// Raw properties, for anything that is not covered by the other options.
+    pub properties: Option<Properties>,
+}
+
+impl ContextOptions {
+    /// Convert the options into the raw [`Properties`] passed to the context.
+    pub fn into_properties(self) -> Properties {
+        let mut props = self.properties.unwrap_or_default();
+
+        if let Some(config_name) = self.config_name {
+            props.insert(*crate::keys::CONFIG_NAME, config_name);
+        }
+        if let Some(core_name) = self.core_name {
+            props.insert(*crate::keys::CORE_NAME, core_name);
+        }
+        if let Some(allow) = self.mem_allow_mlock {
+            props.insert(*crate::keys::MEM_ALLOW_MLOCK, allow.to_string());
+        }
+        if let Some(warn) = self.mem_warn_mlock {
+            props.insert(*crate::keys::MEM_WARN_MLOCK, warn.to_string());
+        }
+        if let Some(align) = self.cpu_max_align {
+            props.insert(*crate::keys::CPU_MAX_ALIGN, align.to_string());
+        }
+        if let Some(dbus) = self.support_dbus {
+            props.insert("support.dbus", dbus.to_string());
+        }
+
+        props
+    }
+}
+
 impl std::convert::AsRef<ContextRef> for Context {
     fn as_ref(&self) -> &ContextRef {
         self.deref()
@@ -123,6 +315,19 @@ impl std::ops::Deref for Context {
     }
 }
 
+impl ContextInner {
+    /// The loop the context was created with.
+    pub(crate) fn loop_ref(&self) -> &LoopRef {
+        (*self._loop).as_ref()
+    }
+
+    /// A handle keeping the loop of the context alive, for objects borrowing from it beyond
+    /// the lifetime of a borrow of the context.
+    pub(crate) fn loop_handle(&self) -> Rc<dyn AsRef<LoopRef>> {
+        self._loop.clone()
+    }
+}
+
 impl std::convert::AsRef<ContextRef> for ContextInner {
     fn as_ref(&self) -> &ContextRef {
         self.deref()
@@ -4,21 +4,28 @@
 use bitflags::bitflags;
 use libc::{c_char, c_void};
 use std::{
-    ffi::{CStr, CString},
+    cell::{Cell, OnceCell, RefCell},
+    ffi::CStr,
     rc::Rc,
+    sync::atomic::{AtomicBool, Ordering},
+    time::{Duration, Instant},
 };
 use std::{fmt, mem, ptr};
 use std::{ops::Deref, pin::Pin};
 
 use crate::{
+    listener_tracking::TrackedListener,
+    metadata::Metadata,
+    properties::{Properties, PropertiesRef},
     proxy::{Proxy, ProxyT},
-    registry::Registry,
+    registry::{GlobalObject, Registry},
+    types::ObjectType,
     Error,
 };
-use spa::{
-    spa_interface_call_method,
-    utils::result::{AsyncSeq, SpaResult},
-};
+use spa::{spa_interface_call_method, spa_interface_call_method_checked, utils::result::AsyncSeq};
+
+mod reconnecting;
+pub use reconnecting::ReconnectingCore;
 
 pub const PW_ID_CORE: u32 = pw_sys::PW_ID_CORE;
 
@@ -43,41 +50,142 @@ impl CoreRef {
         }
     }
 
-    pub fn get_registry(&self) -> Result<Registry, Error> {
-        let registry = unsafe {
-            spa_interface_call_method!(
+    pub fn sync(&self, seq: i32) -> Result<AsyncSeq, Error> {
+        let res = unsafe {
+            spa_interface_call_method_checked!(
                 self.as_raw_ptr(),
                 pw_sys::pw_core_methods,
-                get_registry,
-                pw_sys::PW_VERSION_REGISTRY,
-                0
+                sync,
+                PW_ID_CORE,
+                seq
             )
-        };
-        let registry = ptr::NonNull::new(registry).ok_or(Error::CreationFailed)?;
+        }?;
 
-        Ok(Registry::new(registry))
+        let res = spa::spa_result!(async res)?;
+        Ok(res)
     }
 
-    pub fn sync(&self, seq: i32) -> Result<AsyncSeq, Error> {
+    /// Get the local properties of the core, describing this client to the remote.
+    ///
+    /// Use [`to_owned`](PropertiesRef::to_owned) to get a copy which can be modified
+    /// and passed to [`update_properties`](Self::update_properties).
+    pub fn properties(&self) -> &PropertiesRef {
+        unsafe {
+            let props = pw_sys::pw_core_get_properties(self.as_raw_ptr());
+            let props = ptr::NonNull::new(props.cast_mut()).expect("core properties is NULL");
+            props.cast().as_ref()
+        }
+    }
+
+    /// Get the id of the client global representing this connection on the remote.
+    ///
+    /// The id is sent by the remote after connecting, so this returns `None` until then:
+    /// it is known once a first [`sync`](Self::sync) has completed.
+    ///
+    /// This can be used to skip the own client of the program when listing the clients of the
+    /// registry. The properties assigned to the client by the remote, such as its access and
+    /// the process id, are those of the registry global with this id, and the client can be bound
+    /// using [`Registry::bind_client`] to get its info and permissions.
+    pub fn client_id(&self) -> Option<u32> {
+        unsafe {
+            let client = pw_sys::pw_core_get_client(self.as_raw_ptr());
+            if client.is_null() {
+                return None;
+            }
+
+            let id = pw_sys::pw_proxy_get_bound_id(client.cast());
+            (id != crate::constants::ID_ANY).then_some(id)
+        }
+    }
+
+    /// Update the local properties of the core with `properties`, and send them to the remote
+    /// to update the properties of the client.
+    ///
+    /// Returns the number of properties that changed.
+    ///
+    /// # Examples
+    /// Changing the application name of the client:
+    /// ```no_run
+    /// # fn main() -> Result<(), pipewire::Error> {
+    /// let connection = pipewire::connect(None)?;
+    /// let core = connection.core();
+    ///
+    /// let mut props = core.properties().to_owned();
+    /// let name = props.get(*pipewire::keys::APP_NAME).unwrap_or("pipewire-rs").to_string();
+    /// props.insert(*pipewire::keys::APP_NAME, format!("{} (renamed)", name));
+    /// core.update_properties(props.dict());
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn update_properties(&self, properties: &spa::utils::dict::DictRef) -> i32 {
+        unsafe { pw_sys::pw_core_update_properties(self.as_raw_ptr(), properties.as_raw_ptr()) }
+    }
+
+    /// Destroy the object on the remote server represented by the provided proxy.
+    ///
+    /// The proxy will be destroyed alongside the server side resource, as it is no longer needed.
+    pub fn destroy_object<P: ProxyT>(&self, proxy: P) -> Result<AsyncSeq, Error> {
         let res = unsafe {
-            spa_interface_call_method!(
+            spa_interface_call_method_checked!(
                 self.as_raw_ptr(),
                 pw_sys::pw_core_methods,
-                sync,
-                PW_ID_CORE,
-                seq
+                destroy,
+                proxy.upcast_ref().as_ptr() as *mut c_void
             )
-        };
+        }?;
 
-        let res = SpaResult::from_c(res).into_async_result()?;
+        let res = spa::spa_result!(async res)?;
         Ok(res)
     }
+}
+
+#[derive(Debug, Clone)]
+pub struct Core {
+    inner: Rc<CoreInner>,
+}
+
+impl Core {
+    pub(crate) fn from_ptr(
+        ptr: ptr::NonNull<pw_sys::pw_core>,
+        _context: crate::context::Context,
+    ) -> Self {
+        let inner = CoreInner::from_ptr(ptr, _context);
+        Self {
+            inner: Rc::new(inner),
+        }
+    }
+
+    /// Create a new [`Registry`] proxy.
+    ///
+    /// Each call creates a new proxy, on which the remote announces all the globals again.
+    /// Most programs only need one, see [`Core::registry`] to share a single registry.
+    pub fn get_registry(&self) -> Result<Registry, Error> {
+        let registry = unsafe {
+            spa_interface_call_method_checked!(
+                self.as_raw_ptr(),
+                pw_sys::pw_core_methods,
+                get_registry,
+                pw_sys::PW_VERSION_REGISTRY,
+                0
+            )
+        }?;
+        let registry = ptr::NonNull::new(registry).ok_or(Error::CreationFailed)?;
+
+        Ok(Registry::new(registry, Some(self.inner._context.clone())))
+    }
 
     /// Create a new object on the PipeWire server from a factory.
     ///
     /// You will need specify what type you are expecting to be constructed by either using type inference or the
     /// turbofish syntax.
     ///
+    /// The object is owned by the returned proxy: it is destroyed on the server when the proxy is dropped,
+    /// so the proxy has to be kept alive for as long as the object is needed.
+    /// To create an object that stays in the graph after the proxy is dropped, for example a persistent link,
+    /// set [`OBJECT_LINGER`](crate::keys::OBJECT_LINGER) in `properties`, or use
+    /// [`create_object_detached`](Self::create_object_detached).
+    /// Lingering cannot be enabled once the object is created.
+    ///
     /// # Parameters
     /// - `factory_name` the name of the factory to use
     /// - `properties` extra properties that the new object will have
@@ -126,9 +234,9 @@ impl CoreRef {
         factory_name: &str,
         properties: &impl AsRef<spa::utils::dict::DictRef>,
     ) -> Result<P, Error> {
-        let factory_name = CString::new(factory_name).expect("Null byte in factory_name parameter");
+        let factory_name = crate::utils::null_terminated(factory_name)?;
         let factory_name_cstr = factory_name.as_c_str();
-        CoreRef::create_object_cstr(self, factory_name_cstr, properties)
+        self.create_object_cstr(factory_name_cstr, properties)
     }
 
     pub fn create_object_cstr<P: ProxyT>(
@@ -137,11 +245,10 @@ impl CoreRef {
         properties: &impl AsRef<spa::utils::dict::DictRef>,
     ) -> Result<P, Error> {
         let type_ = P::type_();
-        let type_str = CString::new(type_.to_string())
-            .expect("Null byte in string representation of type_ parameter");
+        let type_str = type_.as_type_info().ok_or(Error::WrongProxyType)?;
 
         let res = unsafe {
-            spa_interface_call_method!(
+            spa_interface_call_method_checked!(
                 self.as_raw_ptr(),
                 pw_sys::pw_core_methods,
                 create_object,
@@ -151,45 +258,159 @@ impl CoreRef {
                 properties.as_ref().as_raw_ptr(),
                 0
             )
-        };
+        }?;
 
         let ptr = ptr::NonNull::new(res.cast()).ok_or(Error::CreationFailed)?;
 
-        Proxy::new(ptr).downcast().map_err(|(_, e)| e)
+        Proxy::new(ptr, Some(self.inner._context.clone()))
+            .downcast()
+            .map_err(|(_, e)| e)
     }
 
-    /// Destroy the object on the remote server represented by the provided proxy.
+    /// Create a new object on the PipeWire server from a factory, without keeping a proxy for it.
     ///
-    /// The proxy will be destroyed alongside the server side resource, as it is no longer needed.
-    pub fn destroy_object<P: ProxyT>(&self, proxy: P) -> Result<AsyncSeq, Error> {
-        let res = unsafe {
-            spa_interface_call_method!(
-                self.as_raw_ptr(),
-                pw_sys::pw_core_methods,
-                destroy,
-                proxy.upcast_ref().as_ptr() as *mut c_void
-            )
-        };
+    /// [`OBJECT_LINGER`](crate::keys::OBJECT_LINGER) is added to `properties`, so that the object stays
+    /// in the graph until it is destroyed, for example using [`Registry::destroy_global`](crate::registry::Registry::destroy_global),
+    /// or until the server exits, instead of being destroyed with the proxy like with [`create_object`](Self::create_object).
+    ///
+    /// As no proxy is kept, errors of the factory are only reported to the [`error`](ListenerLocalBuilder::error)
+    /// listener of the core.
+    pub fn create_object_detached<P: ProxyT>(
+        &self,
+        factory_name: &str,
+        properties: &impl AsRef<spa::utils::dict::DictRef>,
+    ) -> Result<(), Error> {
+        let mut properties = Properties::from_dict(properties.as_ref());
+        properties.insert(*crate::keys::OBJECT_LINGER, "true");
 
-        let res = SpaResult::from_c(res).into_async_result()?;
-        Ok(res)
+        // Dropping the proxy only releases our handle to the lingering object.
+        self.create_object::<P>(factory_name, &properties)?;
+        Ok(())
     }
-}
 
-#[derive(Debug, Clone)]
-pub struct Core {
-    inner: Rc<CoreInner>,
-}
+    /// Get the registry of the core, shared by all the callers.
+    ///
+    /// The registry is created by the first call and kept alive as long as the core is,
+    /// so that all the parts of a program can use the same proxy instead of each calling
+    /// [`get_registry`](Self::get_registry).
+    ///
+    /// The registry is borrowed from the core, so it can not outlive it: it is destroyed
+    /// with the last clone of the core.
+    /// Use [`get_registry`](Self::get_registry) to get a registry that can be stored independently,
+    /// which keeps the context alive until it is dropped.
+    pub fn registry(&self) -> Result<&Registry, Error> {
+        if let Some(registry) = self.inner.registry.get() {
+            return Ok(registry);
+        }
 
-impl Core {
-    pub(crate) fn from_ptr(
-        ptr: ptr::NonNull<pw_sys::pw_core>,
-        _context: crate::context::Context,
-    ) -> Self {
-        let inner = CoreInner::from_ptr(ptr, _context);
-        Self {
-            inner: Rc::new(inner),
+        let registry = self.get_registry()?;
+        Ok(self.inner.registry.get_or_init(|| registry))
+    }
+
+    /// Find the metadata object with the provided `name` and bind it.
+    ///
+    /// A new registry is created to scan the existing globals for a [`Metadata`] global whose
+    /// [`METADATA_NAME`](crate::keys::METADATA_NAME) property matches `name`, until all of them have
+    /// been enumerated: a registry only announces each global once, so the shared [`registry`](Self::registry)
+    /// would miss the globals it already reported.
+    /// Returns [`Error::NotFound`] if no such metadata exists, or [`Error::Timeout`] if the
+    /// globals were not all enumerated within `timeout`, for example because the server is not responding.
+    ///
+    /// If `cancel` is set to `true`, for example from a signal handler or another thread,
+    /// waiting stops and [`Error::Cancelled`] is returned. The flag is checked at least every 50 milliseconds.
+    ///
+    /// This blocks while iterating the loop of the context, so it must be called from the thread
+    /// of that loop, and must not be used on a started [`ThreadLoop`](crate::thread_loop::ThreadLoop).
+    ///
+    /// To bind a metadata global already announced by a registry, use [`Registry::bind_metadata`] instead.
+    pub fn bind_metadata_by_name(
+        &self,
+        name: &str,
+        timeout: Duration,
+        cancel: Option<&AtomicBool>,
+    ) -> Result<Metadata, Error> {
+        let registry = self.get_registry()?;
+
+        let found: Rc<RefCell<Option<GlobalObject<Properties>>>> = Rc::new(RefCell::new(None));
+        let done = Rc::new(Cell::new(false));
+
+        let found_clone = found.clone();
+        let done_clone = done.clone();
+        let name = name.to_owned();
+        let _listener = registry
+            .add_listener_local()
+            .global(move |global| {
+                if global.type_ == ObjectType::Metadata
+                    && global
+                        .props
+                        .and_then(|props| props.get(*crate::keys::METADATA_NAME))
+                        == Some(name.as_str())
+                {
+                    found_clone.replace(Some(global.to_owned()));
+                }
+            })
+            .initial_sync_done(move || done_clone.set(true))
+            .try_register()?;
+
+        const CANCEL_POLL: Duration = Duration::from_millis(50);
+
+        // A timeout too large to be represented is the same as no timeout.
+        let deadline = Instant::now().checked_add(timeout);
+        let loop_ = self.inner._context.loop_ref();
+        while !done.get() {
+            let remaining = match deadline {
+                Some(deadline) => deadline.saturating_duration_since(Instant::now()),
+                None => Duration::from_secs(1),
+            };
+            if remaining.is_zero() {
+                return Err(Error::Timeout);
+            }
+
+            let iteration = match cancel {
+                Some(cancel) if cancel.load(Ordering::Relaxed) => return Err(Error::Cancelled),
+                Some(_) => remaining.min(CANCEL_POLL),
+                None => remaining.min(Duration::from_secs(1)),
+            };
+            let res = loop_.iterate(iteration);
+            spa::spa_result!(sync res)?;
         }
+
+        let global = found.take().ok_or(Error::NotFound)?;
+        registry.bind(&global)
+    }
+
+    /// Create a new metadata object named `name` on the server, using the `metadata` factory.
+    ///
+    /// The metadata is owned by our client, and destroyed with the returned proxy unless
+    /// [`OBJECT_LINGER`](crate::keys::OBJECT_LINGER) is set in `properties`.
+    /// Other clients can find it with [`bind_metadata_by_name`](Self::bind_metadata_by_name).
+    /// `properties` can be used to set properties of the metadata object besides its name.
+    ///
+    /// Returns an `EINVAL` error if `name` is empty.
+    pub fn create_metadata(
+        &self,
+        name: &str,
+        properties: Option<Properties>,
+    ) -> Result<Metadata, Error> {
+        if name.is_empty() {
+            return Err(Error::SpaError(nix::errno::Errno::EINVAL.into()));
+        }
+
+        let mut properties = properties.unwrap_or_default();
+        properties.insert(*crate::keys::METADATA_NAME, name);
+
+        self.create_object("metadata", &properties)
+    }
+
+    /// Bind the `default` metadata object, holding among others the default sink and source.
+    ///
+    /// See [`bind_metadata_by_name`](Self::bind_metadata_by_name) for details.
+    pub fn default_metadata(
+        &self,
+        timeout: Duration,
+        cancel: Option<&AtomicBool>,
+    ) -> Result<Metadata, Error> {
+        self.bind_metadata_by_name("default", timeout, cancel)
     }
 }
 
@@ -210,12 +431,18 @@ impl AsRef<CoreRef> for Core {
 #[derive(Debug)]
 struct CoreInner {
     ptr: ptr::NonNull<pw_sys::pw_core>,
+    // Declared before the context so the registry proxy is destroyed first.
+    registry: OnceCell<Registry>,
     _context: crate::context::Context,
 }
 
 impl CoreInner {
     fn from_ptr(ptr: ptr::NonNull<pw_sys::pw_core>, _context: crate::context::Context) -> Self {
-        Self { ptr, _context }
+        Self {
+            ptr,
+            registry: OnceCell::new(),
+            _context,
+        }
     }
 }
 
@@ -241,6 +468,7 @@ pub struct Listener {
     listener: Pin<Box<spa_sys::spa_hook>>,
     #[allow(dead_code)]
     data: Box<ListenerLocalCallbacks>,
+    _tracked: TrackedListener,
 }
 
 impl Listener {
@@ -283,8 +511,19 @@ impl<'a> ListenerLocalBuilder<'a> {
         self
     }
 
+    /// Register the listener.
+    ///
+    /// # Panics
+    /// If the core does not implement `add_listener`. Use [`try_register`](Self::try_register) to handle this error.
     #[must_use]
     pub fn register(self) -> Listener {
+        self.try_register()
+            .expect("core does not implement add_listener")
+    }
+
+    /// Register the listener, returning an [`ENOTSUP`](nix::errno::Errno::ENOTSUP) error if the core
+    /// does not implement `add_listener`.
+    pub fn try_register(self) -> Result<Listener, crate::Error> {
         unsafe extern "C" fn core_events_info(
             data: *mut c_void,
             info: *const pw_sys::pw_core_info,
@@ -337,7 +576,7 @@ impl<'a> ListenerLocalBuilder<'a> {
             // sys crate, see https://github.com/rust-lang/rust-bindgen/issues/1929
             let listener_ptr: *mut spa_sys::spa_hook = listener.as_mut().get_unchecked_mut();
 
-            spa_interface_call_method!(
+            let res = spa_interface_call_method_checked!(
                 ptr,
                 pw_sys::pw_core_methods,
                 add_listener,
@@ -346,14 +585,18 @@ impl<'a> ListenerLocalBuilder<'a> {
                 data as *mut _
             );
 
-            (listener, Box::from_raw(data))
+            let data = Box::from_raw(data);
+            res?;
+
+            (listener, data)
         };
 
-        Listener {
+        Ok(Listener {
             events: e,
             listener,
             data,
-        }
+            _tracked: TrackedListener::new("Core", self.core.as_raw_ptr().cast()),
+        })
     }
 }
 
@@ -403,6 +646,10 @@ impl Info {
         ChangeMask::from_bits_retain(mask)
     }
 
+    /// The properties of the remote core.
+    ///
+    /// They are only valid for the duration of the `info` callback,
+    /// use [`Properties::from_dict`] to keep an owned snapshot of them.
     pub fn props(&self) -> Option<&spa::utils::dict::DictRef> {
         let props_ptr: *mut spa::utils::dict::DictRef = unsafe { self.ptr.as_ref().props.cast() };
 
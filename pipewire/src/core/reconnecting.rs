@@ -0,0 +1,305 @@
+// Copyright The pipewire-rs Contributors.
+// SPDX-License-Identifier: MIT
+
+//! A [`Core`] wrapper automatically reconnecting to the remote when the connection is lost.
+
+use std::{
+    cell::{Cell, RefCell},
+    rc::{Rc, Weak},
+    time::Duration,
+};
+
+use super::{Core, Listener, PW_ID_CORE};
+use crate::{
+    context::Context,
+    loop_::{LoopRef, TimerSource},
+    properties::Properties,
+    Error,
+};
+
+const DEFAULT_MIN_BACKOFF: Duration = Duration::from_millis(100);
+const DEFAULT_MAX_BACKOFF: Duration = Duration::from_secs(10);
+
+/// A connection to a remote which reconnects automatically after getting disconnected.
+///
+/// A disconnection is detected when the core reports an `EPIPE` error on its own id (see [`PW_ID_CORE`]).
+/// Reconnection attempts are then made on the loop of the context, with an exponential backoff
+/// between failed attempts, until one succeeds or [`cancel`](Self::cancel) is called.
+///
+/// All proxies created from the previous [`Core`] are unusable after a disconnection, so they
+/// should be dropped in the [`on_disconnected`](Self::on_disconnected) callback, and recreated in
+/// the [`on_reconnected`](Self::on_reconnected) callback.
+pub struct ReconnectingCore {
+    inner: Rc<Inner>,
+}
+
+#[allow(clippy::type_complexity)]
+struct Inner {
+    // Declared before `_loop` so it is destroyed while its loop is still alive.
+    timer: TimerSource<'static>,
+    listener: RefCell<Option<Listener>>,
+    core: RefCell<Option<Core>>,
+    context: Context,
+    properties: Option<Properties>,
+    backoff: Cell<Duration>,
+    min_backoff: Cell<Duration>,
+    max_backoff: Cell<Duration>,
+    cancelled: Cell<bool>,
+    // The callbacks are cloned out of their cell before being called, so that they can be replaced
+    // from the callbacks themselves.
+    disconnected: RefCell<Option<Rc<dyn Fn()>>>,
+    reconnected: RefCell<Option<Rc<dyn Fn(&Core)>>>,
+    // The delays the timer was armed with, in order.
+    #[cfg(test)]
+    armed: RefCell<Vec<Duration>>,
+    // The loop `timer` borrows from.
+    _loop: Rc<dyn AsRef<LoopRef>>,
+}
+
+impl ReconnectingCore {
+    /// Connect to the remote, using the provided `properties` for this and every later connection.
+    ///
+    /// Fails if the initial connection cannot be established.
+    pub fn new(context: &Context, properties: Option<Properties>) -> Result<Self, Error> {
+        let core = context.connect(properties.clone())?;
+        let this = Self::unconnected(context, properties);
+        Inner::set_core(&this.inner, core);
+
+        Ok(this)
+    }
+
+    /// Create the wrapper without a connection, which is only made after a disconnection is reported.
+    fn unconnected(context: &Context, properties: Option<Properties>) -> Self {
+        let loop_handle = context.loop_handle();
+
+        // Safety: The loop is kept alive by `loop_handle`, which is owned by `Inner`
+        //         and dropped after the timer.
+        let loop_: &'static LoopRef = unsafe { &*((*loop_handle).as_ref() as *const LoopRef) };
+
+        let inner = Rc::new_cyclic(|weak: &Weak<Inner>| {
+            let weak = weak.clone();
+            let timer = loop_.add_timer(move |_| {
+                if let Some(inner) = weak.upgrade() {
+                    Inner::reconnect(&inner);
+                }
+            });
+
+            Inner {
+                timer,
+                listener: RefCell::new(None),
+                core: RefCell::new(None),
+                context: context.clone(),
+                properties,
+                backoff: Cell::new(DEFAULT_MIN_BACKOFF),
+                min_backoff: Cell::new(DEFAULT_MIN_BACKOFF),
+                max_backoff: Cell::new(DEFAULT_MAX_BACKOFF),
+                cancelled: Cell::new(false),
+                disconnected: RefCell::new(None),
+                reconnected: RefCell::new(None),
+                #[cfg(test)]
+                armed: RefCell::new(Vec::new()),
+                _loop: loop_handle,
+            }
+        });
+
+        Self { inner }
+    }
+
+    /// The currently connected [`Core`], or `None` if a reconnection attempt failed and another one is pending.
+    pub fn core(&self) -> Option<Core> {
+        self.inner.core.borrow().clone()
+    }
+
+    /// Set a callback called when the connection is lost.
+    pub fn on_disconnected<F>(&self, disconnected: F)
+    where
+        F: Fn() + 'static,
+    {
+        self.inner.disconnected.replace(Some(Rc::new(disconnected)));
+    }
+
+    /// Set a callback called with the new [`Core`] after successfully reconnecting.
+    pub fn on_reconnected<F>(&self, reconnected: F)
+    where
+        F: Fn(&Core) + 'static,
+    {
+        self.inner.reconnected.replace(Some(Rc::new(reconnected)));
+    }
+
+    /// Set the delay before the first reconnection attempt, and the maximum delay between attempts.
+    ///
+    /// The delay is doubled after each failed attempt, up to `max`.
+    ///
+    /// # Panics
+    /// Panics if `min` is zero or greater than `max`.
+    pub fn set_backoff(&self, min: Duration, max: Duration) {
+        assert!(!min.is_zero() && min <= max, "invalid backoff range");
+        self.inner.min_backoff.set(min);
+        self.inner.max_backoff.set(max);
+        self.inner.backoff.set(min);
+    }
+
+    /// Stop reconnecting, cancelling any pending reconnection attempt.
+    pub fn cancel(&self) {
+        self.inner.cancelled.set(true);
+        let _ = self.inner.timer.update_timer(None, None);
+    }
+}
+
+impl Inner {
+    fn set_core(this: &Rc<Self>, core: Core) {
+        let weak = Rc::downgrade(this);
+        let listener = core
+            .add_listener_local()
+            .error(move |id, _seq, res, _message| {
+                if id == PW_ID_CORE && res == -libc::EPIPE {
+                    if let Some(inner) = weak.upgrade() {
+                        inner.disconnected();
+                    }
+                }
+            })
+            .register();
+
+        this.listener.replace(Some(listener));
+        this.core.replace(Some(core));
+    }
+
+    fn disconnected(&self) {
+        let disconnected = self.disconnected.borrow().clone();
+        if let Some(disconnected) = disconnected {
+            disconnected();
+        }
+
+        self.backoff.set(self.min_backoff.get());
+        self.schedule();
+    }
+
+    fn schedule(&self) {
+        if !self.cancelled.get() {
+            #[cfg(test)]
+            self.armed.borrow_mut().push(self.backoff.get());
+            let _ = self.timer.update_timer(Some(self.backoff.get()), None);
+        }
+    }
+
+    fn reconnect(this: &Rc<Self>) {
+        if this.cancelled.get() {
+            return;
+        }
+
+        // Dropping the old core from the timer rather than from its own error callback.
+        this.listener.take();
+        this.core.take();
+
+        match this.context.connect(this.properties.clone()) {
+            Ok(core) => {
+                Self::set_core(this, core.clone());
+                let reconnected = this.reconnected.borrow().clone();
+                if let Some(reconnected) = reconnected {
+                    reconnected(&core);
+                }
+            }
+            Err(_) => {
+                this.backoff
+                    .set(next_backoff(this.backoff.get(), this.max_backoff.get()));
+                this.schedule();
+            }
+        }
+    }
+}
+
+/// The delay before the attempt following a failed one, which waited for `backoff`.
+fn next_backoff(backoff: Duration, max: Duration) -> Duration {
+    backoff.saturating_mul(2).min(max)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::main_loop::MainLoop;
+    use std::time::Instant;
+
+    /// A wrapper whose reconnection attempts all fail, as the remote does not exist.
+    fn unreachable(context: &Context) -> ReconnectingCore {
+        let properties = crate::properties::properties! {
+            *crate::keys::REMOTE_NAME => "pipewire-rs-test-no-such-remote"
+        };
+        ReconnectingCore::unconnected(context, Some(properties))
+    }
+
+    /// Iterate `mainloop` until `done` returns `true`, for at most a second.
+    fn iterate_until(mainloop: &MainLoop, done: impl Fn() -> bool) {
+        let deadline = Instant::now() + Duration::from_secs(1);
+        while !done() && Instant::now() < deadline {
+            mainloop.loop_().iterate(Duration::from_millis(10));
+        }
+    }
+
+    #[test]
+    fn next_backoff_doubles_up_to_max() {
+        let max = Duration::from_secs(10);
+        assert_eq!(
+            next_backoff(Duration::from_millis(100), max),
+            Duration::from_millis(200)
+        );
+        assert_eq!(next_backoff(Duration::from_secs(8), max), max);
+        assert_eq!(next_backoff(Duration::MAX, max), max);
+    }
+
+    #[test]
+    fn failed_attempts_back_off() {
+        let mainloop = MainLoop::new(None).unwrap();
+        let context = Context::new(&mainloop).unwrap();
+        let reconnecting = unreachable(&context);
+        reconnecting.set_backoff(Duration::from_millis(1), Duration::from_millis(4));
+
+        reconnecting.inner.disconnected();
+        iterate_until(&mainloop, || reconnecting.inner.armed.borrow().len() >= 4);
+        reconnecting.cancel();
+
+        // The first attempt waits for the minimum delay, which is then doubled after each failure.
+        assert_eq!(
+            reconnecting.inner.armed.borrow()[..4],
+            [1, 2, 4, 4].map(Duration::from_millis)
+        );
+        assert!(reconnecting.core().is_none());
+    }
+
+    #[test]
+    fn cancel_stops_attempts() {
+        let mainloop = MainLoop::new(None).unwrap();
+        let context = Context::new(&mainloop).unwrap();
+        let reconnecting = unreachable(&context);
+        reconnecting.set_backoff(Duration::from_millis(1), Duration::from_millis(4));
+
+        reconnecting.cancel();
+        reconnecting.inner.disconnected();
+        mainloop.loop_().iterate(Duration::from_millis(20));
+
+        assert_eq!(reconnecting.inner.backoff.get(), Duration::from_millis(1));
+        assert!(reconnecting.inner.armed.borrow().is_empty());
+    }
+
+    #[test]
+    fn callback_replaced_from_callback() {
+        let mainloop = MainLoop::new(None).unwrap();
+        let context = Context::new(&mainloop).unwrap();
+        let reconnecting = Rc::new(unreachable(&context));
+        reconnecting.cancel();
+
+        let calls = Rc::new(Cell::new(0));
+        let calls_clone = calls.clone();
+        let reconnecting_weak = Rc::downgrade(&reconnecting);
+        reconnecting.on_disconnected(move || {
+            calls_clone.set(calls_clone.get() + 1);
+            if let Some(reconnecting) = reconnecting_weak.upgrade() {
+                let calls = calls_clone.clone();
+                reconnecting.on_disconnected(move || calls.set(calls.get() + 10));
+            }
+        });
+
+        reconnecting.inner.disconnected();
+        reconnecting.inner.disconnected();
+        assert_eq!(calls.get(), 11);
+    }
+}
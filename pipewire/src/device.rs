@@ -3,15 +3,20 @@
 
 use bitflags::bitflags;
 use libc::c_void;
+use std::cell::RefCell;
 use std::ops::Deref;
 use std::{fmt, mem};
 use std::{pin::Pin, ptr};
 
 use crate::{
+    listener_tracking::TrackedListener,
+    properties::{Properties, PropertiesDiff},
     proxy::{Listener, Proxy, ProxyT},
     types::ObjectType,
 };
-use spa::{pod::Pod, spa_interface_call_method};
+use spa::{
+    param::ParamObject, pod::Pod, spa_interface_call_method, spa_interface_call_method_checked,
+};
 
 #[derive(Debug)]
 pub struct Device {
@@ -85,6 +90,66 @@ impl Device {
             );
         }
     }
+
+    /// Enumerate the profiles available on the device.
+    ///
+    /// For each profile, a `param` event with the [`EnumProfile`](spa::param::ParamType::EnumProfile) id will be emitted,
+    /// which can be parsed using [`Profile::parse`](spa::param::profile::Profile::parse).
+    pub fn enum_profiles(&self, seq: i32) {
+        self.enum_params(seq, Some(spa::param::ParamType::EnumProfile), 0, u32::MAX);
+    }
+
+    /// Enumerate the active profile of the device.
+    ///
+    /// A `param` event with the [`Profile`](spa::param::ParamType::Profile) id will be emitted
+    /// for the active profile.
+    pub fn enum_active_profile(&self, seq: i32) {
+        self.enum_params(seq, Some(spa::param::ParamType::Profile), 0, u32::MAX);
+    }
+
+    /// Switch the device to the profile with the provided `index`, as reported by
+    /// [`enum_profiles`](Self::enum_profiles).
+    ///
+    /// The profile is marked to be saved, so the session manager restores it, as done when
+    /// the user selects a profile.
+    pub fn set_profile(&self, index: i32) {
+        let mut profile = spa::param::profile::Profile::new(index);
+        profile.save = true;
+
+        let bytes = profile.to_pod_bytes();
+        let pod = bytes
+            .as_pod()
+            .expect("Profile serialized to an invalid pod");
+        self.set_param(spa::param::ParamType::Profile, 0, pod);
+    }
+
+    /// Enumerate the routes available on the device.
+    ///
+    /// For each route, a `param` event with the [`EnumRoute`](spa::param::ParamType::EnumRoute) id will be emitted,
+    /// which can be parsed using [`Route::parse`](spa::param::route::Route::parse).
+    pub fn enum_routes(&self, seq: i32) {
+        self.enum_params(seq, Some(spa::param::ParamType::EnumRoute), 0, u32::MAX);
+    }
+
+    /// Enumerate the currently active routes of the device.
+    ///
+    /// For each route, a `param` event with the [`Route`](spa::param::ParamType::Route) id will be emitted.
+    pub fn enum_active_routes(&self, seq: i32) {
+        self.enum_params(seq, Some(spa::param::ParamType::Route), 0, u32::MAX);
+    }
+
+    /// Configure a route of the device.
+    ///
+    /// This is used to change the volume, mute state or channel volumes of hardware devices,
+    /// by setting the `props` of the route.
+    /// The `index` of the route must match one of the routes reported by the device, and its
+    /// `device` must be set, for example to one of the [`devices`](spa::param::route::Route::devices)
+    /// of an `EnumRoute` param.
+    pub fn set_route(&self, route: &spa::param::route::Route) {
+        let bytes = route.to_pod_bytes();
+        let pod = bytes.as_pod().expect("Route serialized to an invalid pod");
+        self.set_param(spa::param::ParamType::Route, 0, pod);
+    }
 }
 
 impl ProxyT for Device {
@@ -113,6 +178,9 @@ struct ListenerLocalCallbacks {
     #[allow(clippy::type_complexity)]
     info: Option<Box<dyn Fn(&DeviceInfoRef)>>,
     #[allow(clippy::type_complexity)]
+    props_changed: Option<Box<dyn Fn(&DeviceInfoRef, &PropertiesDiff)>>,
+    previous_props: RefCell<Properties>,
+    #[allow(clippy::type_complexity)]
     param: Option<Box<dyn Fn(i32, spa::param::ParamType, u32, u32, Option<&Pod>)>>,
 }
 
@@ -235,6 +303,7 @@ pub struct DeviceListener {
     listener: Pin<Box<spa_sys::spa_hook>>,
     #[allow(dead_code)]
     data: Box<ListenerLocalCallbacks>,
+    _tracked: TrackedListener,
 }
 
 impl Listener for DeviceListener {}
@@ -255,6 +324,19 @@ impl<'a> DeviceListenerLocalBuilder<'a> {
         self
     }
 
+    /// Set a callback called with the keys that changed when the properties of the device are updated.
+    ///
+    /// The listener keeps a copy of the properties to compare them with the next update.
+    /// The first update reports all the properties as added.
+    #[must_use]
+    pub fn props_changed<F>(mut self, props_changed: F) -> Self
+    where
+        F: Fn(&DeviceInfoRef, &PropertiesDiff) + 'static,
+    {
+        self.cbs.props_changed = Some(Box::new(props_changed));
+        self
+    }
+
     #[must_use]
     pub fn param<F>(mut self, param: F) -> Self
     where
@@ -264,8 +346,19 @@ impl<'a> DeviceListenerLocalBuilder<'a> {
         self
     }
 
+    /// Register the listener.
+    ///
+    /// # Panics
+    /// If the device does not implement `add_listener`. Use [`try_register`](Self::try_register) to handle this error.
     #[must_use]
     pub fn register(self) -> DeviceListener {
+        self.try_register()
+            .expect("device does not implement add_listener")
+    }
+
+    /// Register the listener, returning an [`ENOTSUP`](nix::errno::Errno::ENOTSUP) error if the device
+    /// does not implement `add_listener`.
+    pub fn try_register(self) -> Result<DeviceListener, crate::Error> {
         unsafe extern "C" fn device_events_info(
             data: *mut c_void,
             info: *const pw_sys::pw_device_info,
@@ -274,7 +367,26 @@ impl<'a> DeviceListenerLocalBuilder<'a> {
             let info =
                 ptr::NonNull::new(info as *mut pw_sys::pw_device_info).expect("info is NULL");
             let info = info.cast::<DeviceInfoRef>().as_ref();
-            callbacks.info.as_ref().unwrap()(info);
+
+            if let Some(info_cb) = &callbacks.info {
+                info_cb(info);
+            }
+
+            if let Some(props_changed) = &callbacks.props_changed {
+                if let Some(props) = info
+                    .props()
+                    .filter(|_| info.change_mask().contains(DeviceChangeMask::PROPS))
+                {
+                    let diff = PropertiesDiff::new(callbacks.previous_props.borrow().dict(), props);
+                    callbacks
+                        .previous_props
+                        .replace(Properties::from_dict(props));
+
+                    if !diff.is_empty() {
+                        props_changed(info, &diff);
+                    }
+                }
+            }
         }
 
         unsafe extern "C" fn device_events_param(
@@ -301,7 +413,7 @@ impl<'a> DeviceListenerLocalBuilder<'a> {
             let mut e: Pin<Box<pw_sys::pw_device_events>> = Box::pin(mem::zeroed());
             e.version = pw_sys::PW_VERSION_DEVICE_EVENTS;
 
-            if self.cbs.info.is_some() {
+            if self.cbs.info.is_some() || self.cbs.props_changed.is_some() {
                 e.info = Some(device_events_info);
             }
             if self.cbs.param.is_some() {
@@ -318,7 +430,7 @@ impl<'a> DeviceListenerLocalBuilder<'a> {
             let mut listener: Pin<Box<spa_sys::spa_hook>> = Box::pin(mem::zeroed());
             let listener_ptr: *mut spa_sys::spa_hook = listener.as_mut().get_unchecked_mut();
 
-            spa_interface_call_method!(
+            let res = spa_interface_call_method_checked!(
                 device,
                 pw_sys::pw_device_methods,
                 add_listener,
@@ -327,13 +439,17 @@ impl<'a> DeviceListenerLocalBuilder<'a> {
                 data as *mut _
             );
 
-            (listener, Box::from_raw(data))
+            let data = Box::from_raw(data);
+            res?;
+
+            (listener, data)
         };
 
-        DeviceListener {
+        Ok(DeviceListener {
             events: e,
             listener,
             data,
-        }
+            _tracked: TrackedListener::new("Device", self.device.proxy.as_ptr().cast()),
+        })
     }
 }
@@ -10,6 +10,16 @@ pub enum Error {
     NoMemory,
     #[error("Wrong proxy type")]
     WrongProxyType,
+    #[error("Main loop is already running")]
+    AlreadyRunning,
+    #[error("Object not found")]
+    NotFound,
+    #[error("Timed out")]
+    Timeout,
+    #[error("Cancelled")]
+    Cancelled,
+    #[error("String contains an interior nul byte")]
+    NulByte(#[from] std::ffi::NulError),
     #[error(transparent)]
     SpaError(#[from] spa::utils::result::Error),
 }
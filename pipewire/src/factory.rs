@@ -9,10 +9,11 @@ use std::{ffi::CStr, ptr};
 use std::{fmt, mem};
 
 use crate::{
+    listener_tracking::TrackedListener,
     proxy::{Listener, Proxy, ProxyT},
     types::ObjectType,
 };
-use spa::spa_interface_call_method;
+use spa::spa_interface_call_method_checked;
 
 #[derive(Debug)]
 pub struct Factory {
@@ -174,6 +175,7 @@ pub struct FactoryListener {
     listener: Pin<Box<spa_sys::spa_hook>>,
     #[allow(dead_code)]
     data: Box<ListenerLocalCallbacks>,
+    _tracked: TrackedListener,
 }
 
 impl Listener for FactoryListener {}
@@ -194,8 +196,19 @@ impl<'a> FactoryListenerLocalBuilder<'a> {
         self
     }
 
+    /// Register the listener.
+    ///
+    /// # Panics
+    /// If the factory does not implement `add_listener`. Use [`try_register`](Self::try_register) to handle this error.
     #[must_use]
     pub fn register(self) -> FactoryListener {
+        self.try_register()
+            .expect("factory does not implement add_listener")
+    }
+
+    /// Register the listener, returning an [`ENOTSUP`](nix::errno::Errno::ENOTSUP) error if the factory
+    /// does not implement `add_listener`.
+    pub fn try_register(self) -> Result<FactoryListener, crate::Error> {
         unsafe extern "C" fn factory_events_info(
             data: *mut c_void,
             info: *const pw_sys::pw_factory_info,
@@ -225,7 +238,7 @@ impl<'a> FactoryListenerLocalBuilder<'a> {
             let mut listener: Pin<Box<spa_sys::spa_hook>> = Box::pin(mem::zeroed());
             let listener_ptr: *mut spa_sys::spa_hook = listener.as_mut().get_unchecked_mut();
 
-            spa_interface_call_method!(
+            let res = spa_interface_call_method_checked!(
                 factory,
                 pw_sys::pw_factory_methods,
                 add_listener,
@@ -234,13 +247,17 @@ impl<'a> FactoryListenerLocalBuilder<'a> {
                 data as *mut _
             );
 
-            (listener, Box::from_raw(data))
+            let data = Box::from_raw(data);
+            res?;
+
+            (listener, data)
         };
 
-        FactoryListener {
+        Ok(FactoryListener {
             events: e,
             listener,
             data,
-        }
+            _tracked: TrackedListener::new("Factory", self.factory.proxy.as_ptr().cast()),
+        })
     }
 }
@@ -118,6 +118,12 @@ key_constant!(CPU_MAX_ALIGN, PW_KEY_CPU_MAX_ALIGN,
 key_constant!(CPU_CORES, PW_KEY_CPU_CORES,
     /// number of cores
 );
+key_constant!(MEM_WARN_MLOCK, PW_KEY_MEM_WARN_MLOCK,
+    /// warn about failures to lock memory
+);
+key_constant!(MEM_ALLOW_MLOCK, PW_KEY_MEM_ALLOW_MLOCK,
+    /// allow mlock on memory
+);
 key_constant!(PRIORITY_SESSION, PW_KEY_PRIORITY_SESSION,
     /// priority in session manager
 );
@@ -305,7 +311,7 @@ key_constant!(PORT_CONTROL, PW_KEY_PORT_CONTROL,
     /// if this port is a control port
 );
 key_constant!(PORT_MONITOR, PW_KEY_PORT_MONITOR,
-    /// if this port is a monitor port
+    /// if this port is a monitor port, carrying a copy of the data played to a sink, `"true"` or `"false"`
 );
 key_constant!(PORT_CACHE_PARAMS, PW_KEY_PORT_CACHE_PARAMS,
     /// cache the node port params
@@ -529,6 +535,9 @@ key_constant!(VIDEO_FORMAT, PW_KEY_VIDEO_FORMAT,
 key_constant!(VIDEO_SIZE, PW_KEY_VIDEO_SIZE,
     /// a video size as "\<width\>x\<height\>"
 );
+key_constant!(METADATA_NAME, PW_KEY_METADATA_NAME,
+    /// the name of a metadata object
+);
 #[cfg(feature = "v0_3_44")]
 key_constant!(TARGET_OBJECT, PW_KEY_TARGET_OBJECT,
     /// a target object to link to. This can be and object name or object.serial PIPEWIRE_KEYS_H
@@ -32,6 +32,8 @@
 //! }
 //! ```
 //!
+//! For this common case, [`connect`] can also be used to create all of them at once.
+//!
 //! Now you can start hooking up different kinds of callbacks to the objects to react to events, and call methods
 //! on objects to change the state of the remote.
 //! ```no_run
@@ -70,6 +72,21 @@
 //! If we didn't run the loop, events and method invocations would not be processed, so the program would terminate
 //! without doing much.
 //!
+//! ## Object lifetimes
+//! The objects above keep the objects they depend on alive: a [`Context`](`context::Context`) holds a
+//! reference to its loop, and each [`Core`](`core::Core`) holds a reference to its context.
+//! They can therefore be dropped in any order, the loop is only destroyed once the last context using it is.
+//!
+//! Proxies, such as the [`Registry`](`registry::Registry`) or any object bound from it, belong to the
+//! connection of the core, which is closed when the context is destroyed: they hold a reference to the context
+//! of the core that created them, so that the connection stays open until the last of them is dropped.
+//! Listeners must in turn be dropped before the object they are registered on.
+//! Declaring them in that order, as in the examples above, is sufficient as locals are dropped in reverse order.
+//! The registry returned by [`Core::registry`](`core::Core::registry`) is owned by the core and borrowed from it,
+//! so it is destroyed with the last clone of the core.
+//! A registry created with [`Registry::from_raw`](`registry::Registry::from_raw`) does not hold such a
+//! reference, and must be dropped before the context.
+//!
 //! ## The main loop
 //! Sometimes, other stuff needs to be done even though we are waiting inside the main loop. \
 //! This can be done by adding sources to the loop.
@@ -111,6 +128,7 @@
 pub mod buffer;
 pub mod channel;
 pub mod client;
+pub mod coalesce;
 pub mod constants;
 pub mod context;
 pub mod core;
@@ -118,6 +136,7 @@ pub mod device;
 pub mod factory;
 pub mod keys;
 pub mod link;
+pub mod listener_tracking;
 pub mod loop_;
 pub mod main_loop;
 pub mod metadata;
@@ -132,6 +151,9 @@ pub mod stream;
 pub mod thread_loop;
 pub mod types;
 
+mod connection;
+pub use connection::{connect, Connection};
+
 mod error;
 pub use error::*;
 
@@ -7,9 +7,10 @@ use std::{
 };
 
 use bitflags::bitflags;
-use spa::spa_interface_call_method;
+use spa::spa_interface_call_method_checked;
 
 use crate::{
+    listener_tracking::TrackedListener,
     proxy::{Listener, Proxy, ProxyT},
     types::ObjectType,
 };
@@ -57,6 +58,7 @@ pub struct LinkListener {
     listener: Pin<Box<spa_sys::spa_hook>>,
     #[allow(dead_code)]
     data: Box<ListenerLocalCallbacks>,
+    _tracked: TrackedListener,
 }
 
 impl Listener for LinkListener {}
@@ -88,8 +90,19 @@ impl<'a> LinkListenerLocalBuilder<'a> {
         self
     }
 
+    /// Register the listener.
+    ///
+    /// # Panics
+    /// If the link does not implement `add_listener`. Use [`try_register`](Self::try_register) to handle this error.
     #[must_use]
     pub fn register(self) -> LinkListener {
+        self.try_register()
+            .expect("link does not implement add_listener")
+    }
+
+    /// Register the listener, returning an [`ENOTSUP`](nix::errno::Errno::ENOTSUP) error if the link
+    /// does not implement `add_listener`.
+    pub fn try_register(self) -> Result<LinkListener, crate::Error> {
         unsafe extern "C" fn link_events_info(
             data: *mut c_void,
             info: *const pw_sys::pw_link_info,
@@ -118,7 +131,7 @@ impl<'a> LinkListenerLocalBuilder<'a> {
             let mut listener: Pin<Box<spa_sys::spa_hook>> = Box::pin(mem::zeroed());
             let listener_ptr: *mut spa_sys::spa_hook = listener.as_mut().get_unchecked_mut();
 
-            spa_interface_call_method!(
+            let res = spa_interface_call_method_checked!(
                 link,
                 pw_sys::pw_link_methods,
                 add_listener,
@@ -127,14 +140,18 @@ impl<'a> LinkListenerLocalBuilder<'a> {
                 data as *mut _
             );
 
-            (listener, Box::from_raw(data))
+            let data = Box::from_raw(data);
+            res?;
+
+            (listener, data)
         };
 
-        LinkListener {
+        Ok(LinkListener {
             events: e,
             listener,
             data,
-        }
+            _tracked: TrackedListener::new("Link", self.link.proxy.as_ptr().cast()),
+        })
     }
 }
 
@@ -0,0 +1,155 @@
+// Copyright The pipewire-rs Contributors.
+// SPDX-License-Identifier: MIT
+
+//! Tracking of the registered listeners, to debug listener lifetime issues.
+//!
+//! With the `track-listeners` feature enabled, every listener returned by a listener builder is
+//! recorded until it is dropped, so that callbacks still firing, or never firing, can be traced
+//! back to a listener that is still alive, or was dropped too early.
+//!
+//! ```no_run
+//! # fn main() -> Result<(), pipewire::Error> {
+//! let mainloop = pipewire::main_loop::MainLoop::new(None)?;
+//! let context = pipewire::context::Context::new(&mainloop)?;
+//! let core = context.connect(None)?;
+//! let _listener = core.add_listener_local().done(|_, _| {}).register();
+//!
+//! #[cfg(feature = "track-listeners")]
+//! eprint!("{}", pipewire::listener_tracking::report());
+//! # Ok(())
+//! # }
+//! ```
+//!
+//! Without the feature, nothing is tracked and this module has no public items.
+
+use std::ffi::c_void;
+
+#[cfg(feature = "track-listeners")]
+use std::{collections::BTreeMap, fmt::Write, sync::Mutex};
+
+/// The number of live listeners, keyed by the kind of object and its address.
+#[cfg(feature = "track-listeners")]
+static LISTENERS: Mutex<BTreeMap<(&'static str, usize), usize>> = Mutex::new(BTreeMap::new());
+
+/// The listeners currently registered on one object.
+#[cfg(feature = "track-listeners")]
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct LiveListeners {
+    /// The kind of object, for example `"Node"` or `"Stream"`.
+    pub kind: &'static str,
+    /// The address of the raw object the listeners are registered on.
+    pub object: *const c_void,
+    /// The number of live listeners.
+    pub count: usize,
+}
+
+/// Get the listeners currently registered, grouped by object.
+#[cfg(feature = "track-listeners")]
+pub fn live_listeners() -> Vec<LiveListeners> {
+    LISTENERS
+        .lock()
+        .unwrap()
+        .iter()
+        .map(|(&(kind, object), &count)| LiveListeners {
+            kind,
+            object: object as *const c_void,
+            count,
+        })
+        .collect()
+}
+
+/// Get the number of listeners currently registered on the raw `object`,
+/// for example the pointer returned by [`StreamRef::as_raw_ptr`](crate::stream::StreamRef::as_raw_ptr).
+///
+/// For proxies such as nodes and links, use [`count_proxy`].
+#[cfg(feature = "track-listeners")]
+pub fn count(object: *const c_void) -> usize {
+    LISTENERS
+        .lock()
+        .unwrap()
+        .iter()
+        .filter(|((_, addr), _)| *addr == object as usize)
+        .map(|(_, count)| count)
+        .sum()
+}
+
+/// Get the number of listeners currently registered on `proxy`.
+#[cfg(feature = "track-listeners")]
+pub fn count_proxy<P: crate::proxy::ProxyT>(proxy: &P) -> usize {
+    count(proxy.upcast_ref().as_ptr().cast())
+}
+
+/// Render the listeners currently registered as a human readable report, one object per line.
+#[cfg(feature = "track-listeners")]
+pub fn report() -> String {
+    let mut out = String::new();
+    for live in live_listeners() {
+        writeln!(
+            out,
+            "{} {:p}: {} listener(s)",
+            live.kind, live.object, live.count
+        )
+        .expect("Writing to a String cannot fail");
+    }
+    out
+}
+
+/// Marker stored in each listener, recording it while it is alive.
+///
+/// Without the `track-listeners` feature this is a zero sized type doing nothing.
+pub(crate) struct TrackedListener {
+    #[cfg(feature = "track-listeners")]
+    key: (&'static str, usize),
+}
+
+impl TrackedListener {
+    #[inline]
+    pub(crate) fn new(kind: &'static str, object: *const c_void) -> Self {
+        #[cfg(feature = "track-listeners")]
+        {
+            let key = (kind, object as usize);
+            *LISTENERS.lock().unwrap().entry(key).or_insert(0) += 1;
+            Self { key }
+        }
+
+        #[cfg(not(feature = "track-listeners"))]
+        {
+            let _ = (kind, object);
+            Self {}
+        }
+    }
+}
+
+#[cfg(feature = "track-listeners")]
+impl Drop for TrackedListener {
+    fn drop(&mut self) {
+        let mut listeners = LISTENERS.lock().unwrap();
+        if let Some(count) = listeners.get_mut(&self.key) {
+            *count -= 1;
+            if *count == 0 {
+                listeners.remove(&self.key);
+            }
+        }
+    }
+}
+
+#[cfg(all(test, feature = "track-listeners"))]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn track() {
+        let object = 0x1234 as *const c_void;
+
+        let first = TrackedListener::new("Node", object);
+        let second = TrackedListener::new("Node", object);
+        assert_eq!(count(object), 2);
+        assert!(report().contains("Node 0x1234: 2 listener(s)"));
+
+        drop(first);
+        assert_eq!(count(object), 1);
+        drop(second);
+        assert_eq!(count(object), 0);
+        assert!(!live_listeners().iter().any(|live| live.object == object));
+    }
+}
@@ -7,6 +7,7 @@ use std::{
     os::unix::prelude::*,
     ptr::{self, NonNull},
     rc::{Rc, Weak},
+    sync::{Arc, Mutex, PoisonError},
     time::Duration,
 };
 
@@ -171,6 +172,7 @@ impl LoopRef {
         };
 
         let ptr = ptr::NonNull::new(source).expect("source is NULL");
+        self.source_added();
 
         IoSource {
             ptr,
@@ -214,6 +216,7 @@ impl LoopRef {
         };
 
         let ptr = ptr::NonNull::new(source).expect("source is NULL");
+        self.source_added();
 
         IdleSource {
             ptr,
@@ -259,6 +262,7 @@ impl LoopRef {
         };
 
         let ptr = ptr::NonNull::new(source).expect("source is NULL");
+        self.source_added();
 
         SignalSource {
             ptr,
@@ -267,6 +271,32 @@ impl LoopRef {
         }
     }
 
+    /// Register a single callback to react to any of the provided signals.
+    ///
+    /// The callback is given the signal which was received.
+    /// All the signal handlers are removed when the returned [`SignalSources`] is dropped.
+    ///
+    /// # Panics
+    /// This function will panic if any thread but the main thread tries to register a signal.
+    #[must_use]
+    pub fn add_signals_local<F>(&self, signals: &[Signal], callback: F) -> SignalSources
+    where
+        F: Fn(Signal) + 'static,
+        Self: Sized,
+    {
+        let callback = Rc::new(callback);
+
+        let sources = signals
+            .iter()
+            .map(|&signal| {
+                let callback = callback.clone();
+                self.add_signal_local(signal, move || callback(signal))
+            })
+            .collect();
+
+        SignalSources { _sources: sources }
+    }
+
     /// Register a new event with a callback that is called when the event happens.
     ///
     /// The returned [`EventSource`] can be used to trigger the event.
@@ -300,10 +330,16 @@ impl LoopRef {
         };
 
         let ptr = ptr::NonNull::new(source).expect("source is NULL");
+        self.source_added();
+        let target = Arc::new(Mutex::new(Some(EventTarget {
+            utils: self.as_raw().utils,
+            source: ptr.as_ptr(),
+        })));
 
         EventSource {
             ptr,
             loop_: self,
+            target,
             _data: data,
         }
     }
@@ -343,6 +379,7 @@ impl LoopRef {
         };
 
         let ptr = ptr::NonNull::new(source).expect("source is NULL");
+        self.source_added();
 
         TimerSource {
             ptr,
@@ -351,6 +388,113 @@ impl LoopRef {
         }
     }
 
+    /// Call `callback` from the thread of the loop.
+    ///
+    /// If called from the thread of the loop, for example from another callback of the loop,
+    /// `callback` is called immediately.
+    /// Otherwise, it is queued and called at the next iteration of the loop, and if `block` is `true`,
+    /// this waits until `callback` has returned. This is the way to safely change state owned
+    /// by the loop from another thread, for example from the `process` callback of a stream,
+    /// which is called from the data loop.
+    ///
+    /// The loop thread is the one that entered the loop, for example by calling
+    /// [`MainLoop::run`](crate::main_loop::MainLoop::run). If the loop is not entered,
+    /// `callback` is called immediately from the calling thread instead, whatever the thread is:
+    /// state owned by the loop must therefore only be changed this way while the loop is running.
+    ///
+    /// A blocking invoke must only be used if the loop is running in another thread, or it
+    /// waits forever. In particular, it deadlocks if the calling thread is the one that is
+    /// supposed to iterate the loop, or if the loop thread is itself blocked waiting on the caller.
+    ///
+    /// If the loop is destroyed before a queued `callback` is called, the callback is leaked.
+    pub fn invoke<F>(&self, block: bool, callback: F) -> Result<(), Error>
+    where
+        F: FnOnce() + Send + 'static,
+    {
+        unsafe extern "C" fn call_closure<F>(
+            _loop: *mut spa_sys::spa_loop,
+            _async: bool,
+            _seq: u32,
+            data: *const c_void,
+            _size: usize,
+            _user_data: *mut c_void,
+        ) -> c_int
+        where
+            F: FnOnce(),
+        {
+            // `data` points to a copy of the pointer to the boxed callback.
+            let callback = Box::from_raw(*(data as *const *mut F));
+            callback();
+            0
+        }
+
+        let data = Box::into_raw(Box::new(callback));
+
+        let res = unsafe {
+            let mut iface = self.as_raw().loop_.as_ref().unwrap().iface;
+
+            spa_interface_call_method!(
+                &mut iface as *mut spa_sys::spa_interface,
+                spa_sys::spa_loop_methods,
+                invoke,
+                Some(call_closure::<F>),
+                crate::constants::ID_ANY,
+                (&data as *const *mut F).cast(),
+                std::mem::size_of::<*mut F>(),
+                block,
+                ptr::null_mut()
+            )
+        };
+
+        if let Err(e) = spa::spa_result!(res) {
+            // The loop only fails before queuing the call, so the callback is still owned here.
+            drop(unsafe { Box::from_raw(data) });
+            return Err(e.into());
+        }
+
+        Ok(())
+    }
+
+    /// Get the number of sources added to this loop through [`LoopRef`] that have not been dropped yet.
+    ///
+    /// This is meant for diagnostics, for example to find sources that are leaked or kept alive for too long.
+    /// The sources internally added by PipeWire, such as the one of the connection of a
+    /// [`Core`](crate::core::Core), are not counted.
+    pub fn source_count(&self) -> usize {
+        SOURCES
+            .lock()
+            .unwrap_or_else(PoisonError::into_inner)
+            .iter()
+            .find(|(ptr, _)| *ptr == self.as_raw_ptr() as usize)
+            .map_or(0, |(_, count)| *count)
+    }
+
+    fn source_added(&self) {
+        let mut sources = SOURCES.lock().unwrap_or_else(PoisonError::into_inner);
+        match sources
+            .iter_mut()
+            .find(|(ptr, _)| *ptr == self.as_raw_ptr() as usize)
+        {
+            Some((_, count)) => *count += 1,
+            None => sources.push((self.as_raw_ptr() as usize, 1)),
+        }
+    }
+
+    fn source_removed(&self) {
+        let mut sources = SOURCES.lock().unwrap_or_else(PoisonError::into_inner);
+        if let Some(index) = sources
+            .iter()
+            .position(|(ptr, _)| *ptr == self.as_raw_ptr() as usize)
+        {
+            sources[index].1 -= 1;
+            // Remove the loop once it has no source, so that a new loop allocated at the same
+            // address starts from zero.
+            if sources[index].1 == 0 {
+                sources.swap_remove(index);
+            }
+        }
+    }
+
     /// Destroy a source that belongs to this loop.
     ///
     /// # Safety
@@ -360,6 +504,8 @@ impl LoopRef {
         S: IsSource,
         Self: Sized,
     {
+        self.source_removed();
+
         let mut iface = self.as_raw().utils.as_ref().unwrap().iface;
 
         spa_interface_call_method!(
@@ -371,6 +517,12 @@ impl LoopRef {
     }
 }
 
+/// The number of sources of each loop, by loop pointer, see [`LoopRef::source_count`].
+///
+/// A [`LoopRef`] has no state of its own, so the counts of its loop are looked up here.
+/// Loops without any source are not listed.
+static SOURCES: Mutex<Vec<(usize, usize)>> = Mutex::new(Vec::new());
+
 /// Trait implemented by objects that implement a `pw_loop` and are reference counted in some way.
 ///
 /// # Safety
@@ -563,6 +715,14 @@ impl<'l> Drop for SignalSource<'l> {
     }
 }
 
+/// A group of [`SignalSource`]s sharing the same callback.
+///
+/// This can be obtained by calling [`add_signals_local`](`LoopRef::add_signals_local`) on a loop.
+/// All the signal handlers are removed when this is dropped.
+pub struct SignalSources<'l> {
+    _sources: Vec<SignalSource<'l>>,
+}
+
 /// A source that can be used to signal to a loop that an event has occurred.
 ///
 /// This source can be obtained by calling [`add_event`](`LoopRef::add_event`) on a loop, registering a callback to it.
@@ -572,6 +732,8 @@ impl<'l> Drop for SignalSource<'l> {
 pub struct EventSource<'l> {
     ptr: ptr::NonNull<spa_sys::spa_source>,
     loop_: &'l LoopRef,
+    // Shared with the signalers, and cleared when the source is destroyed.
+    target: Arc<Mutex<Option<EventTarget>>>,
     // Store data wrapper to prevent leak
     _data: Box<dyn Fn() + 'static>,
 }
@@ -599,14 +761,77 @@ impl<'l> EventSource<'l> {
 
         SpaResult::from_c(res)
     }
+
+    /// Get a handle to signal the event from another thread.
+    ///
+    /// The source itself is bound to the loop thread, the returned [`EventSignaler`] is the only
+    /// part of it that can be sent to other threads, for example to hand work over to the loop thread.
+    pub fn signaler(&self) -> EventSignaler {
+        EventSignaler {
+            target: self.target.clone(),
+        }
+    }
 }
 
 impl<'l> Drop for EventSource<'l> {
     fn drop(&mut self) {
+        // Hold the lock while destroying the source, so no signaler can use it concurrently.
+        let mut target = self.target.lock().unwrap_or_else(|e| e.into_inner());
+        target.take();
         unsafe { self.loop_.destroy_source(self) }
     }
 }
 
+struct EventTarget {
+    utils: *mut spa_sys::spa_loop_utils,
+    source: *mut spa_sys::spa_source,
+}
+
+// Safety: Signaling an event is thread safe, and the pointers are only used while the
+//         source is alive, which is ensured by the mutex wrapping the target.
+unsafe impl Send for EventTarget {}
+
+/// A handle to signal an [`EventSource`] from any thread, obtained with [`EventSource::signaler`].
+///
+/// Signaling makes the loop call the callback of the event from the loop thread.
+/// Once the source is dropped, signaling does nothing.
+#[derive(Clone)]
+pub struct EventSignaler {
+    target: Arc<Mutex<Option<EventTarget>>>,
+}
+
+impl EventSignaler {
+    /// Signal the event, to make the loop call its callback at the next possible occasion.
+    ///
+    /// Returns `false` if the source was dropped.
+    pub fn signal(&self) -> bool {
+        let target = self.target.lock().unwrap_or_else(|e| e.into_inner());
+
+        match &*target {
+            Some(target) => {
+                unsafe {
+                    let mut iface = target.utils.as_ref().unwrap().iface;
+
+                    spa_interface_call_method!(
+                        &mut iface as *mut spa_sys::spa_interface,
+                        spa_sys::spa_loop_utils_methods,
+                        signal_event,
+                        target.source
+                    );
+                }
+                true
+            }
+            None => false,
+        }
+    }
+}
+
+impl std::fmt::Debug for EventSignaler {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("EventSignaler").finish_non_exhaustive()
+    }
+}
+
 /// A source that can be used to have a callback called on a timer.
 ///
 /// This source can be obtained by calling [`add_timer`](`LoopRef::add_timer`) on a loop, registering a callback to it.
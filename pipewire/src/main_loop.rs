@@ -1,14 +1,20 @@
 // Copyright The pipewire-rs Contributors.
 // SPDX-License-Identifier: MIT
 
+use std::cell::Cell;
 use std::ptr::{self, NonNull};
 use std::rc::{Rc, Weak};
 
 use crate::{
     error::Error,
-    loop_::{IsLoopRc, LoopRef},
+    loop_::{EventSource, IsLoopRc, LoopRef, Signal, SignalSources},
 };
 
+/// A loop running on the thread calling [`run`](Self::run).
+///
+/// Clones share the same loop, which is destroyed once the last clone, and the last
+/// [`Context`](crate::context::Context) created on it, are dropped.
+/// See the [crate level documentation](crate#object-lifetimes) for the lifetimes of the other objects.
 #[derive(Debug, Clone)]
 pub struct MainLoop {
     inner: Rc<MainLoopInner>,
@@ -51,21 +57,100 @@ impl MainLoop {
         WeakMainLoop { weak }
     }
 
+    /// Get the [`LoopRef`] of this loop.
+    ///
+    /// Sources such as timers, idles, events, signals or IOs are registered on the returned loop,
+    /// which is shared by all loop types.
+    /// The returned [`LoopRef`] borrows from `self` and is valid for as long as it is alive.
     pub fn loop_(&self) -> &LoopRef {
         unsafe {
             let pw_loop = pw_sys::pw_main_loop_get_loop(self.as_raw_ptr());
-            // FIXME: Make sure pw_loop is not null
-            &*(pw_loop.cast::<LoopRef>())
+            ptr::NonNull::new(pw_loop)
+                .expect("main loop has no loop")
+                .cast::<LoopRef>()
+                .as_ref()
         }
     }
 
-    pub fn run(&self) {
+    /// Register a single callback to react to any of the provided signals on the loop.
+    ///
+    /// This is a shortcut for [`LoopRef::add_signals_local`] on the [`loop_()`](Self::loop_) of this main loop,
+    /// see its documentation for details.
+    #[must_use]
+    pub fn add_signals_local<F>(&self, signals: &[Signal], callback: F) -> SignalSources
+    where
+        F: Fn(Signal) + 'static,
+    {
+        self.loop_().add_signals_local(signals, callback)
+    }
+
+    /// Register an event with a callback called on the loop thread each time the event is signaled.
+    ///
+    /// This is a shortcut for [`LoopRef::add_event`] on the [`loop_()`](Self::loop_) of this main loop.
+    /// The returned [`EventSource`] must stay on the loop thread, but its
+    /// [`signaler`](EventSource::signaler) can be sent to worker threads to wake the loop and hand
+    /// work over to it, which is the only part of the source that is [`Send`].
+    ///
+    /// Several signals received before the loop dispatches the event result in a single call.
+    #[must_use]
+    pub fn add_event<F>(&self, callback: F) -> EventSource
+    where
+        F: Fn() + 'static,
+    {
+        self.loop_().add_event(callback)
+    }
+
+    /// Run the loop, dispatching events until [`quit`](Self::quit) is called.
+    ///
+    /// Returns the exit code passed to the last call to [`quit_with_code`](Self::quit_with_code),
+    /// or `0` if the loop was stopped using [`quit`](Self::quit).
+    ///
+    /// # Panics
+    /// Panics if the loop is already running, see [`try_run`](Self::try_run).
+    pub fn run(&self) -> i32 {
+        self.try_run().expect("main loop is already running")
+    }
+
+    /// Run the loop, dispatching events until [`quit`](Self::quit) is called.
+    ///
+    /// Returns the exit code like [`run`](Self::run) does, or [`Error::AlreadyRunning`] without running the loop
+    /// if it is already running, for example when called from one of the callbacks dispatched by the loop.
+    pub fn try_run(&self) -> Result<i32, Error> {
+        if self.inner.running.replace(true) {
+            return Err(Error::AlreadyRunning);
+        }
+
+        self.inner.exit_code.set(0);
         unsafe {
             pw_sys::pw_main_loop_run(self.as_raw_ptr());
         }
+        self.inner.running.set(false);
+
+        Ok(self.inner.exit_code.get())
+    }
+
+    /// Returns `true` if the loop is currently running, i.e. if [`run`](Self::run) has not returned yet.
+    pub fn is_running(&self) -> bool {
+        self.inner.running.get()
+    }
+
+    /// Get the number of sources registered on the loop that have not been dropped yet.
+    ///
+    /// This is a shortcut for [`LoopRef::source_count`] on the [`loop_()`](Self::loop_) of this main loop.
+    pub fn source_count(&self) -> usize {
+        self.loop_().source_count()
     }
 
+    /// Stop the loop, making [`run`](Self::run) return `0`.
     pub fn quit(&self) {
+        self.quit_with_code(0);
+    }
+
+    /// Stop the loop, making [`run`](Self::run) return `code`.
+    ///
+    /// If this is called multiple times before the loop stops, the last code is returned.
+    pub fn quit_with_code(&self, code: i32) {
+        self.inner.exit_code.set(code);
         unsafe {
             pw_sys::pw_main_loop_quit(self.as_raw_ptr());
         }
@@ -95,11 +180,17 @@ impl WeakMainLoop {
 #[derive(Debug)]
 struct MainLoopInner {
     ptr: ptr::NonNull<pw_sys::pw_main_loop>,
+    running: Cell<bool>,
+    exit_code: Cell<i32>,
 }
 
 impl MainLoopInner {
     pub unsafe fn from_raw(ptr: NonNull<pw_sys::pw_main_loop>) -> Self {
-        Self { ptr }
+        Self {
+            ptr,
+            running: Cell::new(false),
+            exit_code: Cell::new(0),
+        }
     }
 }
 
@@ -108,3 +199,136 @@ impl Drop for MainLoopInner {
         unsafe { pw_sys::pw_main_loop_destroy(self.ptr.as_ptr()) }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn reentrant_run() {
+        let mainloop = MainLoop::new(None).unwrap();
+        assert!(!mainloop.is_running());
+
+        let mainloop_clone = mainloop.clone();
+        let _idle = mainloop.loop_().add_idle(true, move || {
+            assert!(mainloop_clone.is_running());
+            assert!(matches!(
+                mainloop_clone.try_run(),
+                Err(Error::AlreadyRunning)
+            ));
+            mainloop_clone.quit();
+        });
+
+        mainloop.try_run().unwrap();
+        assert!(!mainloop.is_running());
+    }
+
+    #[test]
+    fn source_count() {
+        let mainloop = MainLoop::new(None).unwrap();
+        assert_eq!(mainloop.source_count(), 0);
+
+        let idle = mainloop.loop_().add_idle(false, || {});
+        let timer = mainloop.loop_().add_timer(|_| {});
+        let event = mainloop.add_event(|| {});
+        assert_eq!(mainloop.source_count(), 3);
+
+        // Sources are only counted on their own loop.
+        let other = MainLoop::new(None).unwrap();
+        assert_eq!(other.source_count(), 0);
+
+        drop(timer);
+        assert_eq!(mainloop.source_count(), 2);
+        drop(idle);
+        drop(event);
+        assert_eq!(mainloop.source_count(), 0);
+    }
+
+    #[test]
+    fn quit_with_code() {
+        let mainloop = MainLoop::new(None).unwrap();
+
+        let mainloop_clone = mainloop.clone();
+        let _idle = mainloop.loop_().add_idle(true, move || {
+            mainloop_clone.quit_with_code(1);
+            mainloop_clone.quit_with_code(42);
+        });
+
+        assert_eq!(mainloop.run(), 42);
+    }
+
+    #[test]
+    fn event_from_thread() {
+        let mainloop = MainLoop::new(None).unwrap();
+
+        let mainloop_clone = mainloop.clone();
+        let event = mainloop.add_event(move || mainloop_clone.quit());
+        let signaler = event.signaler();
+
+        let thread = std::thread::spawn(move || assert!(signaler.signal()));
+        mainloop.run();
+        thread.join().unwrap();
+
+        let signaler = event.signaler();
+        drop(event);
+        assert!(!signaler.signal());
+    }
+
+    #[test]
+    fn invoke() {
+        let mainloop = MainLoop::new(None).unwrap();
+
+        let (sender, receiver) = std::sync::mpsc::channel();
+        mainloop
+            .loop_()
+            .invoke(false, move || sender.send(42).unwrap())
+            .unwrap();
+
+        while receiver.try_recv().is_err() {
+            mainloop
+                .loop_()
+                .iterate(std::time::Duration::from_millis(100));
+        }
+    }
+
+    #[test]
+    fn blocking_invoke_from_thread() {
+        let mainloop = MainLoop::new(None).unwrap();
+        let loop_thread = std::thread::current().id();
+
+        let mainloop_clone = mainloop.clone();
+        let quit = mainloop.add_event(move || mainloop_clone.quit());
+        let quit = quit.signaler();
+
+        // The loop is only entered by `run`, so wait for it to be running before invoking.
+        let (started, wait_started) = std::sync::mpsc::channel();
+        let started = std::cell::RefCell::new(Some(started));
+        let _idle = mainloop.loop_().add_idle(true, move || {
+            if let Some(started) = started.borrow_mut().take() {
+                started.send(()).unwrap();
+            }
+        });
+
+        // Only used while the loop is alive, the thread is joined before the loop is dropped.
+        let loop_ptr = mainloop.loop_().as_raw_ptr() as usize;
+        let thread = std::thread::spawn(move || {
+            wait_started.recv().unwrap();
+            let loop_ = unsafe { &*(loop_ptr as *const LoopRef) };
+
+            let (sender, receiver) = std::sync::mpsc::channel();
+            loop_
+                .invoke(true, move || {
+                    sender.send(std::thread::current().id()).unwrap()
+                })
+                .unwrap();
+            // The invoke blocks until the callback has returned.
+            let called_from = receiver.try_recv().unwrap();
+
+            assert!(quit.signal());
+            called_from
+        });
+
+        mainloop.run();
+        assert_eq!(thread.join().unwrap(), loop_thread);
+    }
+}
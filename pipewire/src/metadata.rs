@@ -1,7 +1,6 @@
 // Copyright The pipewire-rs Contributors.
 // SPDX-License-Identifier: MIT
 
-use std::ffi::CString;
 use std::os::raw::c_char;
 use std::{
     ffi::{c_void, CStr},
@@ -11,10 +10,11 @@ use std::{
 };
 
 use crate::{
+    listener_tracking::TrackedListener,
     proxy::{Listener, Proxy, ProxyT},
     types::ObjectType,
 };
-use spa::spa_interface_call_method;
+use spa::spa_interface_call_method_checked;
 
 #[derive(Debug)]
 pub struct Metadata {
@@ -50,14 +50,25 @@ impl Metadata {
         }
     }
 
-    pub fn set_property(&self, subject: u32, key: &str, type_: Option<&str>, value: Option<&str>) {
+    /// Set a metadata property.
+    ///
+    /// # Errors
+    /// Returns [`Error::NulByte`](crate::Error::NulByte) if `key`, `type_` or `value` contains a 0 byte.
+    pub fn set_property(
+        &self,
+        subject: u32,
+        key: &str,
+        type_: Option<&str>,
+        value: Option<&str>,
+    ) -> Result<(), crate::Error> {
         // Keep CStrings allocated here in order for pointers to remain valid.
-        let key = CString::new(key).expect("Invalid byte in metadata key");
-        let type_ = type_.map(|t| CString::new(t).expect("Invalid byte in metadata type"));
-        let value = value.map(|v| CString::new(v).expect("Invalid byte in metadata value"));
+        let key = crate::utils::null_terminated(key)?;
+        let type_ = type_.map(crate::utils::null_terminated).transpose()?;
+        let value = value.map(crate::utils::null_terminated).transpose()?;
         let key_cstr = key.as_c_str();
 
-        Metadata::set_property_cstr(self, subject, key_cstr, type_.as_deref(), value.as_deref())
+        Metadata::set_property_cstr(self, subject, key_cstr, type_.as_deref(), value.as_deref());
+        Ok(())
     }
 
     pub fn set_property_cstr(
@@ -98,6 +109,7 @@ pub struct MetadataListener {
     listener: Pin<Box<spa_sys::spa_hook>>,
     #[allow(dead_code)]
     data: Box<ListenerLocalCallbacks>,
+    _tracked: TrackedListener,
 }
 
 impl Listener for MetadataListener {}
@@ -112,6 +124,23 @@ impl Drop for MetadataListener {
 struct ListenerLocalCallbacks {
     #[allow(clippy::type_complexity)]
     property: Option<Box<dyn Fn(u32, Option<&str>, Option<&str>, Option<&str>) -> i32>>,
+    subject: Option<u32>,
+    key_prefix: Option<String>,
+}
+
+impl ListenerLocalCallbacks {
+    /// Returns `true` if a property event for `subject` and `key` passes the filters.
+    fn matches(&self, subject: u32, key: Option<&str>) -> bool {
+        if self.subject.is_some_and(|s| s != subject) {
+            return false;
+        }
+
+        match (&self.key_prefix, key) {
+            (Some(prefix), Some(key)) => key.starts_with(prefix.as_str()),
+            // A `None` key clears all properties, including the filtered ones.
+            _ => true,
+        }
+    }
 }
 
 #[must_use]
@@ -135,8 +164,38 @@ impl<'meta> MetadataListenerLocalBuilder<'meta> {
         self
     }
 
+    /// Only call the [`property`](Self::property) callback for properties of `subject`.
+    ///
+    /// The filtering is done client-side: all the events are still sent by the server.
+    pub fn subject(mut self, subject: u32) -> Self {
+        self.cbs.subject = Some(subject);
+        self
+    }
+
+    /// Only call the [`property`](Self::property) callback for keys starting with `prefix`,
+    /// such as `default.audio.` to only watch the default audio devices.
+    ///
+    /// Events clearing all the properties, with a `None` key, are always passed to the callback.
+    ///
+    /// The filtering is done client-side: all the events are still sent by the server.
+    pub fn key_prefix(mut self, prefix: &str) -> Self {
+        self.cbs.key_prefix = Some(prefix.to_string());
+        self
+    }
+
+    /// Register the listener.
+    ///
+    /// # Panics
+    /// If the metadata does not implement `add_listener`. Use [`try_register`](Self::try_register) to handle this error.
     #[must_use]
     pub fn register(self) -> MetadataListener {
+        self.try_register()
+            .expect("metadata does not implement add_listener")
+    }
+
+    /// Register the listener, returning an [`ENOTSUP`](nix::errno::Errno::ENOTSUP) error if the metadata
+    /// does not implement `add_listener`.
+    pub fn try_register(self) -> Result<MetadataListener, crate::Error> {
         unsafe extern "C" fn metadata_events_property(
             data: *mut c_void,
             subject: u32,
@@ -150,6 +209,9 @@ impl<'meta> MetadataListenerLocalBuilder<'meta> {
             } else {
                 None
             };
+            if !callbacks.matches(subject, key.as_deref()) {
+                return 0;
+            }
             let type_ = if !type_.is_null() {
                 Some(CStr::from_ptr(type_).to_string_lossy())
             } else {
@@ -186,7 +248,7 @@ impl<'meta> MetadataListenerLocalBuilder<'meta> {
             let mut listener: Pin<Box<spa_sys::spa_hook>> = Box::pin(mem::zeroed());
             let listener_ptr: *mut spa_sys::spa_hook = listener.as_mut().get_unchecked_mut();
 
-            spa_interface_call_method!(
+            let res = spa_interface_call_method_checked!(
                 metadata,
                 pw_sys::pw_metadata_methods,
                 add_listener,
@@ -195,13 +257,37 @@ impl<'meta> MetadataListenerLocalBuilder<'meta> {
                 data as *mut _
             );
 
-            (listener, Box::from_raw(data))
+            let data = Box::from_raw(data);
+            res?;
+
+            (listener, data)
         };
 
-        MetadataListener {
+        Ok(MetadataListener {
             events: e,
             listener,
             data,
-        }
+            _tracked: TrackedListener::new("Metadata", self.metadata.proxy.as_ptr().cast()),
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn property_filters() {
+        let cbs = ListenerLocalCallbacks {
+            subject: Some(0),
+            key_prefix: Some("default.audio.".to_string()),
+            ..Default::default()
+        };
+
+        assert!(cbs.matches(0, Some("default.audio.sink")));
+        assert!(cbs.matches(0, None));
+        assert!(!cbs.matches(0, Some("default.video.source")));
+        assert!(!cbs.matches(1, Some("default.audio.sink")));
+        assert!(ListenerLocalCallbacks::default().matches(1, Some("target.object")));
     }
 }
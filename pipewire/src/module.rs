@@ -9,10 +9,11 @@ use std::{ffi::CStr, ptr};
 use std::{fmt, mem};
 
 use crate::{
+    listener_tracking::TrackedListener,
     proxy::{Listener, Proxy, ProxyT},
     types::ObjectType,
 };
-use spa::spa_interface_call_method;
+use spa::spa_interface_call_method_checked;
 
 #[derive(Debug)]
 pub struct Module {
@@ -183,6 +184,7 @@ pub struct ModuleListener {
     listener: Pin<Box<spa_sys::spa_hook>>,
     #[allow(dead_code)]
     data: Box<ListenerLocalCallbacks>,
+    _tracked: TrackedListener,
 }
 
 impl Listener for ModuleListener {}
@@ -203,8 +205,19 @@ impl<'a> ModuleListenerLocalBuilder<'a> {
         self
     }
 
+    /// Register the listener.
+    ///
+    /// # Panics
+    /// If the module does not implement `add_listener`. Use [`try_register`](Self::try_register) to handle this error.
     #[must_use]
     pub fn register(self) -> ModuleListener {
+        self.try_register()
+            .expect("module does not implement add_listener")
+    }
+
+    /// Register the listener, returning an [`ENOTSUP`](nix::errno::Errno::ENOTSUP) error if the module
+    /// does not implement `add_listener`.
+    pub fn try_register(self) -> Result<ModuleListener, crate::Error> {
         unsafe extern "C" fn module_events_info(
             data: *mut c_void,
             info: *const pw_sys::pw_module_info,
@@ -234,7 +247,7 @@ impl<'a> ModuleListenerLocalBuilder<'a> {
             let mut listener: Pin<Box<spa_sys::spa_hook>> = Box::pin(mem::zeroed());
             let listener_ptr: *mut spa_sys::spa_hook = listener.as_mut().get_unchecked_mut();
 
-            spa_interface_call_method!(
+            let res = spa_interface_call_method_checked!(
                 module,
                 pw_sys::pw_module_methods,
                 add_listener,
@@ -243,13 +256,17 @@ impl<'a> ModuleListenerLocalBuilder<'a> {
                 data as *mut _
             );
 
-            (listener, Box::from_raw(data))
+            let data = Box::from_raw(data);
+            res?;
+
+            (listener, data)
         };
 
-        ModuleListener {
+        Ok(ModuleListener {
             events: e,
             listener,
             data,
-        }
+            _tracked: TrackedListener::new("Module", self.module.proxy.as_ptr().cast()),
+        })
     }
 }
@@ -3,16 +3,30 @@
 
 use bitflags::bitflags;
 use libc::c_void;
+use std::cell::RefCell;
 use std::ops::Deref;
 use std::pin::Pin;
 use std::{ffi::CStr, ptr};
 use std::{fmt, mem};
 
 use crate::{
+    listener_tracking::TrackedListener,
+    properties::{Properties, PropertiesDiff},
     proxy::{Listener, Proxy, ProxyT},
     types::ObjectType,
+    Error,
 };
-use spa::{pod::Pod, spa_interface_call_method};
+use spa::{
+    param::{
+        audio::{Props, PropsBuilder},
+        ParamObject,
+    },
+    pod::Pod,
+    spa_interface_call_method, spa_interface_call_method_checked,
+};
+
+#[cfg(feature = "futures")]
+pub mod param_stream;
 
 #[derive(Debug)]
 pub struct Node {
@@ -74,6 +88,20 @@ impl Node {
         }
     }
 
+    /// Enumerate the params with the id `id`, or all the params if `id` is [`None`],
+    /// as an asynchronous stream.
+    ///
+    /// This is the asynchronous counterpart to calling [`enum_params`](Self::enum_params) and
+    /// collecting the params received by the `param` callback until the node is synced.
+    /// See [`ParamStream`](param_stream::ParamStream) for details.
+    #[cfg(feature = "futures")]
+    pub fn enum_params_stream(
+        &self,
+        id: Option<spa::param::ParamType>,
+    ) -> Result<param_stream::ParamStream, Error> {
+        param_stream::ParamStream::new(self, id)
+    }
+
     pub fn set_param(&self, id: spa::param::ParamType, flags: u32, param: &Pod) {
         unsafe {
             spa_interface_call_method!(
@@ -86,6 +114,81 @@ impl Node {
             );
         }
     }
+
+    /// Request the current audio properties of the node, such as its volume and mute state.
+    ///
+    /// They are received by the [`props`](NodeListenerLocalBuilder::props) callback of the listeners
+    /// of the node. To also receive them each time they change, use
+    /// [`subscribe_params`](Self::subscribe_params) with [`ParamType::Props`](spa::param::ParamType::Props) instead.
+    pub fn enum_props(&self, seq: i32) {
+        self.enum_params(seq, Some(spa::param::ParamType::Props), 0, u32::MAX);
+    }
+
+    /// Get the current audio properties of the node, such as its volume and mute state.
+    ///
+    /// Returns [`Error::NotFound`] if the node has no `Props` param.
+    /// See [`enum_params_stream`](Self::enum_params_stream) for how the future makes progress.
+    #[cfg(feature = "futures")]
+    pub async fn props(&self) -> Result<Props, Error> {
+        use futures_core::Stream;
+
+        let mut params = self.enum_params_stream(Some(spa::param::ParamType::Props))?;
+        let param = std::future::poll_fn(|cx| Pin::new(&mut params).poll_next(cx))
+            .await
+            .ok_or(Error::NotFound)?;
+
+        Props::parse(param.pod()).map_err(|e| spa::utils::result::Error::from(e).into())
+    }
+
+    /// Set the volume of all the channels of the node, using its `Props` param.
+    ///
+    /// PipeWire volumes are linear amplitude factors, while volume sliders usually use a cubic scale,
+    /// see [`linear_to_cubic`](spa::param::audio::linear_to_cubic) to convert between them.
+    ///
+    /// The current volume is read from [`Props::volume`] or, per channel, from [`Props::channel_volumes`],
+    /// see [`enum_props`](Self::enum_props).
+    pub fn set_volume(&self, volume: f32) -> Result<(), Error> {
+        self.set_props(&PropsBuilder::new().volume(volume))
+    }
+
+    /// Set the volume of each channel of the node, using its `Props` param.
+    ///
+    /// See [`set_volume`](Self::set_volume) for details on the volume scale.
+    pub fn set_channel_volumes(&self, volumes: &[f32]) -> Result<(), Error> {
+        self.set_props(&PropsBuilder::new().channel_volumes(volumes))
+    }
+
+    /// Mute or unmute the node, using its `Props` param.
+    ///
+    /// The current mute state is read from [`Props::mute`], see [`enum_props`](Self::enum_props).
+    pub fn set_mute(&self, mute: bool) -> Result<(), Error> {
+        self.set_props(&PropsBuilder::new().mute(mute))
+    }
+
+    /// Set the audio properties of the node built with `props`, using its `Props` param.
+    ///
+    /// Fails if the properties are not [valid](PropsBuilder::validate).
+    pub fn set_props(&self, props: &PropsBuilder) -> Result<(), Error> {
+        let bytes = props
+            .to_pod_bytes()
+            .map_err(spa::utils::result::Error::from)?;
+        let pod = bytes.as_pod().expect("Props serialized to an invalid pod");
+        self.set_param(spa::param::ParamType::Props, 0, pod);
+
+        Ok(())
+    }
+
+    /// Configure the ports of the node, using the [`PortConfig`](spa::param::ParamType::PortConfig) param.
+    ///
+    /// This can for example be used to switch an audio adapter node to [`Dsp`](spa::param::port_config::ParamPortConfigMode::Dsp)
+    /// mode with a given channel layout, splitting its channels into separate ports.
+    pub fn set_port_config(&self, config: &spa::param::port_config::PortConfig) {
+        let bytes = config.to_pod_bytes();
+        let pod = bytes
+            .as_pod()
+            .expect("PortConfig serialized to an invalid pod");
+        self.set_param(spa::param::ParamType::PortConfig, 0, pod);
+    }
 }
 
 impl ProxyT for Node {
@@ -114,7 +217,12 @@ struct ListenerLocalCallbacks {
     #[allow(clippy::type_complexity)]
     info: Option<Box<dyn Fn(&NodeInfoRef)>>,
     #[allow(clippy::type_complexity)]
+    props_changed: Option<Box<dyn Fn(&NodeInfoRef, &PropertiesDiff)>>,
+    previous_props: RefCell<Properties>,
+    #[allow(clippy::type_complexity)]
     param: Option<Box<dyn Fn(i32, spa::param::ParamType, u32, u32, Option<&Pod>)>>,
+    #[allow(clippy::type_complexity)]
+    props: Option<Box<dyn Fn(&Props)>>,
 }
 
 pub struct NodeListenerLocalBuilder<'a> {
@@ -138,10 +246,12 @@ impl NodeInfoRef {
         self.0.id
     }
 
+    /// The maximum number of input ports the node can have.
     pub fn max_input_ports(&self) -> u32 {
         self.0.max_input_ports
     }
 
+    /// The maximum number of output ports the node can have.
     pub fn max_output_ports(&self) -> u32 {
         self.0.max_output_ports
     }
@@ -150,10 +260,18 @@ impl NodeInfoRef {
         NodeChangeMask::from_bits_retain(self.0.change_mask)
     }
 
+    /// The number of input ports the node currently has.
+    ///
+    /// This is known before the globals of the ports are announced by the registry,
+    /// and is updated when [`NodeChangeMask::INPUT_PORTS`] is set in the [`change_mask`](Self::change_mask).
     pub fn n_input_ports(&self) -> u32 {
         self.0.n_input_ports
     }
 
+    /// The number of output ports the node currently has.
+    ///
+    /// This is known before the globals of the ports are announced by the registry,
+    /// and is updated when [`NodeChangeMask::OUTPUT_PORTS`] is set in the [`change_mask`](Self::change_mask).
     pub fn n_output_ports(&self) -> u32 {
         self.0.n_output_ports
     }
@@ -162,11 +280,7 @@ impl NodeInfoRef {
         let raw_state = self.0.state;
         match raw_state {
             pw_sys::pw_node_state_PW_NODE_STATE_ERROR => {
-                let error = unsafe {
-                    let error = self.0.error;
-                    CStr::from_ptr(error).to_str().unwrap()
-                };
-                NodeState::Error(error)
+                NodeState::Error(self.error().unwrap_or_default())
             }
             pw_sys::pw_node_state_PW_NODE_STATE_CREATING => NodeState::Creating,
             pw_sys::pw_node_state_PW_NODE_STATE_SUSPENDED => NodeState::Suspended,
@@ -176,12 +290,25 @@ impl NodeInfoRef {
         }
     }
 
+    /// The message describing why the node is in the [`NodeState::Error`] state, such as a busy device.
+    ///
+    /// Returns `None` if the node did not provide a message, or if the message is not valid UTF-8.
+    pub fn error(&self) -> Option<&str> {
+        if self.0.error.is_null() {
+            return None;
+        }
+
+        unsafe { CStr::from_ptr(self.0.error).to_str().ok() }
+    }
+
     pub fn props(&self) -> Option<&spa::utils::dict::DictRef> {
         let props_ptr: *mut spa::utils::dict::DictRef = self.0.props.cast();
         ptr::NonNull::new(props_ptr).map(|ptr| unsafe { ptr.as_ref() })
     }
 
     /// Get the param infos for the node.
+    ///
+    /// This lists the params supported by the node, and whether they can be enumerated and set.
     pub fn params(&self) -> &[spa::param::ParamInfo] {
         unsafe {
             let params_ptr = self.0.params;
@@ -196,6 +323,14 @@ impl NodeInfoRef {
             }
         }
     }
+
+    /// Get the param info of the param `id`, or `None` if the node does not support it.
+    ///
+    /// This can be used to check if a param such as [`Props`](spa::param::ParamType::Props)
+    /// is writable before calling [`Node::set_param`].
+    pub fn param_info(&self, id: spa::param::ParamType) -> Option<&spa::param::ParamInfo> {
+        self.params().iter().find(|info| info.id() == id)
+    }
 }
 
 impl fmt::Debug for NodeInfoRef {
@@ -271,6 +406,9 @@ bitflags! {
 
 #[derive(Debug)]
 pub enum NodeState<'a> {
+    /// The node is in error, with a message describing the error.
+    ///
+    /// The message is empty if the node did not provide one, see [`NodeInfoRef::error`].
     Error(&'a str),
     Creating,
     Suspended,
@@ -301,6 +439,7 @@ pub struct NodeListener {
     listener: Pin<Box<spa_sys::spa_hook>>,
     #[allow(dead_code)]
     data: Box<ListenerLocalCallbacks>,
+    _tracked: TrackedListener,
 }
 
 impl Listener for NodeListener {}
@@ -321,6 +460,19 @@ impl<'a> NodeListenerLocalBuilder<'a> {
         self
     }
 
+    /// Set a callback called with the keys that changed when the properties of the node are updated.
+    ///
+    /// The listener keeps a copy of the properties to compare them with the next update.
+    /// The first update reports all the properties as added.
+    #[must_use]
+    pub fn props_changed<F>(mut self, props_changed: F) -> Self
+    where
+        F: Fn(&NodeInfoRef, &PropertiesDiff) + 'static,
+    {
+        self.cbs.props_changed = Some(Box::new(props_changed));
+        self
+    }
+
     #[must_use]
     pub fn param<F>(mut self, param: F) -> Self
     where
@@ -330,8 +482,34 @@ impl<'a> NodeListenerLocalBuilder<'a> {
         self
     }
 
+    /// Set a callback called with the audio properties of the node, such as its volume,
+    /// each time its `Props` param is received.
+    ///
+    /// The param is received after calling [`Node::enum_props`], or on each change
+    /// after subscribing to it with [`Node::subscribe_params`].
+    /// `Props` params which can not be parsed are ignored.
+    #[must_use]
+    pub fn props<F>(mut self, props: F) -> Self
+    where
+        F: Fn(&Props) + 'static,
+    {
+        self.cbs.props = Some(Box::new(props));
+        self
+    }
+
+    /// Register the listener.
+    ///
+    /// # Panics
+    /// If the node does not implement `add_listener`. Use [`try_register`](Self::try_register) to handle this error.
     #[must_use]
     pub fn register(self) -> NodeListener {
+        self.try_register()
+            .expect("node does not implement add_listener")
+    }
+
+    /// Register the listener, returning an [`ENOTSUP`](nix::errno::Errno::ENOTSUP) error if the node
+    /// does not implement `add_listener`.
+    pub fn try_register(self) -> Result<NodeListener, crate::Error> {
         unsafe extern "C" fn node_events_info(
             data: *mut c_void,
             info: *const pw_sys::pw_node_info,
@@ -339,7 +517,26 @@ impl<'a> NodeListenerLocalBuilder<'a> {
             let callbacks = (data as *mut ListenerLocalCallbacks).as_ref().unwrap();
             let info = ptr::NonNull::new(info as *mut pw_sys::pw_node_info).expect("info is NULL");
             let info = info.cast::<NodeInfoRef>().as_ref();
-            callbacks.info.as_ref().unwrap()(info);
+
+            if let Some(info_cb) = &callbacks.info {
+                info_cb(info);
+            }
+
+            if let Some(props_changed) = &callbacks.props_changed {
+                if let Some(props) = info
+                    .props()
+                    .filter(|_| info.change_mask().contains(NodeChangeMask::PROPS))
+                {
+                    let diff = PropertiesDiff::new(callbacks.previous_props.borrow().dict(), props);
+                    callbacks
+                        .previous_props
+                        .replace(Properties::from_dict(props));
+
+                    if !diff.is_empty() {
+                        props_changed(info, &diff);
+                    }
+                }
+            }
         }
 
         unsafe extern "C" fn node_events_param(
@@ -359,17 +556,28 @@ impl<'a> NodeListenerLocalBuilder<'a> {
                 None
             };
 
-            callbacks.param.as_ref().unwrap()(seq, id, index, next, param);
+            if let Some(param_cb) = &callbacks.param {
+                param_cb(seq, id, index, next, param);
+            }
+
+            if let Some(props_cb) = &callbacks.props {
+                if let Some(props) = param
+                    .filter(|_| id == spa::param::ParamType::Props)
+                    .and_then(|param| Props::parse(param).ok())
+                {
+                    props_cb(&props);
+                }
+            }
         }
 
         let e = unsafe {
             let mut e: Pin<Box<pw_sys::pw_node_events>> = Box::pin(mem::zeroed());
             e.version = pw_sys::PW_VERSION_NODE_EVENTS;
 
-            if self.cbs.info.is_some() {
+            if self.cbs.info.is_some() || self.cbs.props_changed.is_some() {
                 e.info = Some(node_events_info);
             }
-            if self.cbs.param.is_some() {
+            if self.cbs.param.is_some() || self.cbs.props.is_some() {
                 e.param = Some(node_events_param);
             }
 
@@ -383,7 +591,7 @@ impl<'a> NodeListenerLocalBuilder<'a> {
             let mut listener: Pin<Box<spa_sys::spa_hook>> = Box::pin(mem::zeroed());
             let listener_ptr: *mut spa_sys::spa_hook = listener.as_mut().get_unchecked_mut();
 
-            spa_interface_call_method!(
+            let res = spa_interface_call_method_checked!(
                 node,
                 pw_sys::pw_node_methods,
                 add_listener,
@@ -392,13 +600,17 @@ impl<'a> NodeListenerLocalBuilder<'a> {
                 data as *mut _
             );
 
-            (listener, Box::from_raw(data))
+            let data = Box::from_raw(data);
+            res?;
+
+            (listener, data)
         };
 
-        NodeListener {
+        Ok(NodeListener {
             events: e,
             listener,
             data,
-        }
+            _tracked: TrackedListener::new("Node", self.node.proxy.as_ptr().cast()),
+        })
     }
 }
@@ -0,0 +1,169 @@
+// Copyright The pipewire-rs Contributors.
+// SPDX-License-Identifier: MIT
+
+//! Enumerating the params of a node as a [`futures_core::Stream`].
+
+use std::{
+    cell::RefCell,
+    collections::VecDeque,
+    pin::Pin,
+    rc::Rc,
+    sync::atomic::{AtomicI32, Ordering},
+    task::{Context, Poll, Waker},
+};
+
+use spa::{
+    param::ParamType,
+    pod::{Pod, PodBuf},
+    utils::result::AsyncSeq,
+};
+
+use super::{Node, NodeListener};
+use crate::{proxy::ProxyListener, Error};
+
+/// The sequence numbers passed to `enum_params`, to tell the params of each enumeration apart.
+static NEXT_SEQ: AtomicI32 = AtomicI32::new(1);
+
+/// A param received while enumerating the params of a node.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct EnumeratedParam {
+    /// The id of the param.
+    pub id: ParamType,
+    /// The index of the param, in the list of params with this id.
+    pub index: u32,
+    bytes: PodBuf,
+}
+
+impl EnumeratedParam {
+    /// Get the param as a pod.
+    pub fn pod(&self) -> &Pod {
+        self.bytes.as_pod().expect("Received param is a valid pod")
+    }
+
+    /// Get the raw bytes of the param pod.
+    pub fn as_bytes(&self) -> &[u8] {
+        &self.bytes
+    }
+}
+
+#[derive(Default)]
+struct Shared {
+    params: VecDeque<EnumeratedParam>,
+    waker: Option<Waker>,
+    done_seq: Option<AsyncSeq>,
+    done: bool,
+}
+
+impl Shared {
+    fn wake(&mut self) {
+        if let Some(waker) = self.waker.take() {
+            waker.wake();
+        }
+    }
+}
+
+/// The params of a node, enumerated as an asynchronous [`futures_core::Stream`],
+/// see [`Node::enum_params_stream`].
+///
+/// The stream yields each param sent by the node for the enumeration, and ends once the node
+/// has sent all of them.
+/// If the node does not support the requested param, the node emits an error
+/// and the stream ends without yielding anything.
+///
+/// ```no_run
+/// # async fn formats(node: &pipewire::node::Node) -> Result<(), pipewire::Error> {
+/// use futures::StreamExt;
+/// use pipewire::spa::param::ParamType;
+///
+/// let mut params = node.enum_params_stream(Some(ParamType::EnumFormat))?;
+/// while let Some(param) = params.next().await {
+///     println!("{}", pipewire::spa::pod::to_debug_string(param.as_bytes()));
+/// }
+/// # Ok(())
+/// # }
+/// ```
+///
+/// # Cancellation
+/// Dropping the stream removes its listeners, so the params still sent by the node for
+/// the enumeration are ignored. The enumeration itself cannot be canceled on the server.
+///
+/// The loop of the node needs to be iterated for the stream to make progress, and as the listeners
+/// are local, the stream has to be polled from the thread of the loop.
+pub struct ParamStream {
+    shared: Rc<RefCell<Shared>>,
+    // Keep the listeners alive while the stream is.
+    _node_listener: NodeListener,
+    _proxy_listener: ProxyListener,
+}
+
+impl ParamStream {
+    pub(super) fn new(node: &Node, id: Option<ParamType>) -> Result<Self, Error> {
+        let shared: Rc<RefCell<Shared>> = Rc::default();
+        let seq = NEXT_SEQ.fetch_add(1, Ordering::Relaxed);
+
+        let node_listener = node
+            .add_listener_local()
+            .param({
+                let shared = shared.clone();
+                move |param_seq, id, index, _next, param| {
+                    let mut shared = shared.borrow_mut();
+                    if param_seq != seq || shared.done {
+                        return;
+                    }
+
+                    if let Some(param) = param {
+                        shared.params.push_back(EnumeratedParam {
+                            id,
+                            index,
+                            bytes: PodBuf::from_bytes(param.as_bytes()),
+                        });
+                        shared.wake();
+                    }
+                }
+            })
+            .register();
+
+        let proxy_listener = node
+            .proxy
+            .add_listener_local()
+            .done({
+                let shared = shared.clone();
+                move |done_seq| {
+                    let mut shared = shared.borrow_mut();
+                    if shared.done_seq == Some(done_seq) {
+                        shared.done = true;
+                        shared.wake();
+                    }
+                }
+            })
+            .register();
+
+        node.enum_params(seq, id, 0, u32::MAX);
+        // The done event is emitted once all the params of the enumeration have been sent.
+        let done_seq = node.proxy.sync(0)?;
+        shared.borrow_mut().done_seq = Some(done_seq);
+
+        Ok(Self {
+            shared,
+            _node_listener: node_listener,
+            _proxy_listener: proxy_listener,
+        })
+    }
+}
+
+impl futures_core::Stream for ParamStream {
+    type Item = EnumeratedParam;
+
+    fn poll_next(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Option<Self::Item>> {
+        let mut shared = self.shared.borrow_mut();
+
+        if let Some(param) = shared.params.pop_front() {
+            Poll::Ready(Some(param))
+        } else if shared.done {
+            Poll::Ready(None)
+        } else {
+            shared.waker = Some(cx.waker().clone());
+            Poll::Pending
+        }
+    }
+}
@@ -5,6 +5,10 @@ use bitflags::bitflags;
 use std::fmt;
 
 bitflags! {
+    /// The permissions of a client on an object.
+    ///
+    /// Permissions can be combined using the usual bitflags operations, such as
+    /// [`union`](Self::union) (`|`), [`intersection`](Self::intersection) (`&`) and [`difference`](Self::difference) (`-`).
     #[derive(Debug, PartialEq, Eq, Clone, Copy)]
     pub struct PermissionFlags: u32 {
         const R = pw_sys::PW_PERM_R;
@@ -43,6 +47,105 @@ impl Permission {
     pub fn set_permission_flags(&mut self, flags: PermissionFlags) {
         self.0.permissions = flags.bits();
     }
+
+    /// Obtain a [`Permission`] from a raw [`pw_permission`](pw_sys::pw_permission).
+    pub fn from_raw(raw: pw_sys::pw_permission) -> Self {
+        Self(raw)
+    }
+
+    /// Get the raw [`pw_permission`](pw_sys::pw_permission) representing this `Permission`.
+    pub fn as_raw(&self) -> pw_sys::pw_permission {
+        self.0
+    }
+
+    /// View a slice of permissions as the raw array expected by the C API.
+    pub fn slice_as_raw(permissions: &[Self]) -> &[pw_sys::pw_permission] {
+        // Safety: `Permission` is a transparent wrapper around `pw_permission`.
+        unsafe { std::slice::from_raw_parts(permissions.as_ptr().cast(), permissions.len()) }
+    }
+
+    /// View a raw array of permissions received from the C API as a slice of [`Permission`].
+    pub fn slice_from_raw(permissions: &[pw_sys::pw_permission]) -> &[Self] {
+        // Safety: `Permission` is a transparent wrapper around `pw_permission`.
+        unsafe { std::slice::from_raw_parts(permissions.as_ptr().cast(), permissions.len()) }
+    }
+}
+
+impl From<(u32, PermissionFlags)> for Permission {
+    fn from((id, flags): (u32, PermissionFlags)) -> Self {
+        Self::new(id, flags)
+    }
+}
+
+impl From<Permission> for (u32, PermissionFlags) {
+    fn from(permission: Permission) -> Self {
+        (permission.id(), permission.permission_flags())
+    }
+}
+
+impl From<pw_sys::pw_permission> for Permission {
+    fn from(raw: pw_sys::pw_permission) -> Self {
+        Self::from_raw(raw)
+    }
+}
+
+impl From<Permission> for pw_sys::pw_permission {
+    fn from(permission: Permission) -> Self {
+        permission.as_raw()
+    }
+}
+
+impl PermissionFlags {
+    /// Compute the effective permissions, starting from `default`, adding the `granted` permissions
+    /// and then removing the `revoked` ones.
+    ///
+    /// Revoked permissions take precedence over granted ones.
+    #[must_use]
+    pub fn effective(default: Self, granted: Self, revoked: Self) -> Self {
+        default.union(granted).difference(revoked)
+    }
+
+    /// Format the permissions as an octal string such as `0700`, as accepted by PipeWire
+    /// wherever permissions are parsed from a number.
+    ///
+    /// This is different from the `rwxm` form used when displaying permissions.
+    pub fn to_octal_string(&self) -> String {
+        format!("{:04o}", self.bits())
+    }
+
+    /// Parse permissions from an octal string such as `0700`, as produced by [`to_octal_string`](Self::to_octal_string).
+    ///
+    /// An optional `0o` prefix is accepted. Bits not corresponding to known permissions are retained.
+    ///
+    /// Returns `None` if `s` is not a valid octal number.
+    pub fn from_octal_str(s: &str) -> Option<Self> {
+        let digits = s.strip_prefix("0o").unwrap_or(s);
+        u32::from_str_radix(digits, 8)
+            .ok()
+            .map(Self::from_bits_retain)
+    }
+}
+
+impl Permission {
+    /// Get the permissions which apply to the object with id `id` in `permissions`,
+    /// the same way they are applied by [`Client::update_permissions`](crate::client::Client::update_permissions).
+    ///
+    /// A permission specific to the object replaces the default permission,
+    /// which uses [`ID_ANY`](crate::constants::ID_ANY) as its id.
+    /// When several permissions apply to the same id, the last one wins.
+    ///
+    /// Returns `None` if neither a specific nor a default permission is found.
+    pub fn effective_for(permissions: &[Permission], id: u32) -> Option<PermissionFlags> {
+        let find = |id| {
+            permissions
+                .iter()
+                .rev()
+                .find(|permission| permission.id() == id)
+                .map(Permission::permission_flags)
+        };
+
+        find(id).or_else(|| find(crate::constants::ID_ANY))
+    }
 }
 
 impl fmt::Debug for Permission {
@@ -53,3 +156,107 @@ impl fmt::Debug for Permission {
             .finish()
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::constants::ID_ANY;
+
+    #[test]
+    fn effective_flags() {
+        let rx = PermissionFlags::R | PermissionFlags::X;
+
+        assert_eq!(
+            PermissionFlags::effective(rx, PermissionFlags::W, PermissionFlags::empty()),
+            PermissionFlags::R | PermissionFlags::W | PermissionFlags::X
+        );
+        assert_eq!(
+            PermissionFlags::effective(rx, PermissionFlags::empty(), PermissionFlags::X),
+            PermissionFlags::R
+        );
+        // Revoking wins over granting.
+        assert_eq!(
+            PermissionFlags::effective(rx, PermissionFlags::W, PermissionFlags::W),
+            rx
+        );
+    }
+
+    #[test]
+    fn octal_string() {
+        // Values of the PW_PERM_* defines.
+        assert_eq!(PermissionFlags::R.bits(), 0o400);
+        assert_eq!(PermissionFlags::W.bits(), 0o200);
+        assert_eq!(PermissionFlags::X.bits(), 0o100);
+        assert_eq!(PermissionFlags::M.bits(), 0o010);
+
+        let rwx = PermissionFlags::R | PermissionFlags::W | PermissionFlags::X;
+        assert_eq!(rwx.to_octal_string(), "0700");
+        assert_eq!(PermissionFlags::M.to_octal_string(), "0010");
+        assert_eq!(PermissionFlags::empty().to_octal_string(), "0000");
+
+        for flags in [
+            rwx,
+            rwx | PermissionFlags::M,
+            PermissionFlags::R,
+            PermissionFlags::empty(),
+        ] {
+            assert_eq!(
+                PermissionFlags::from_octal_str(&flags.to_octal_string()),
+                Some(flags)
+            );
+        }
+
+        assert_eq!(
+            PermissionFlags::from_octal_str("0o500"),
+            Some(PermissionFlags::R | PermissionFlags::X)
+        );
+        assert_eq!(PermissionFlags::from_octal_str("rwx"), None);
+        assert_eq!(PermissionFlags::from_octal_str("0800"), None);
+    }
+
+    #[test]
+    fn raw_conversions() {
+        let rw = PermissionFlags::R | PermissionFlags::W;
+
+        let permission = Permission::from((42, rw));
+        let raw = pw_sys::pw_permission::from(permission);
+        assert_eq!(raw.id, 42);
+        assert_eq!(raw.permissions, pw_sys::PW_PERM_R | pw_sys::PW_PERM_W);
+        assert_eq!(
+            <(u32, PermissionFlags)>::from(Permission::from(raw)),
+            (42, rw)
+        );
+
+        let permissions = [
+            Permission::new(ID_ANY, PermissionFlags::R),
+            Permission::new(42, rw),
+        ];
+        let raw = Permission::slice_as_raw(&permissions);
+        assert_eq!(raw.len(), 2);
+        assert_eq!(raw[0].id, ID_ANY);
+        assert_eq!(raw[1].permissions, rw.bits());
+
+        let back = Permission::slice_from_raw(raw);
+        assert_eq!(back[1].id(), 42);
+        assert_eq!(back[1].permission_flags(), rw);
+    }
+
+    #[test]
+    fn effective_for_object() {
+        let permissions = [
+            Permission::new(ID_ANY, PermissionFlags::R),
+            Permission::new(42, PermissionFlags::R | PermissionFlags::W),
+            Permission::new(42, PermissionFlags::empty()),
+        ];
+
+        assert_eq!(
+            Permission::effective_for(&permissions, 42),
+            Some(PermissionFlags::empty())
+        );
+        assert_eq!(
+            Permission::effective_for(&permissions, 1),
+            Some(PermissionFlags::R)
+        );
+        assert_eq!(Permission::effective_for(&permissions[1..], 1), None);
+    }
+}
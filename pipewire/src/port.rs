@@ -8,11 +8,12 @@ use std::{fmt, mem};
 use std::{pin::Pin, ptr};
 
 use crate::{
+    listener_tracking::TrackedListener,
     proxy::{Listener, Proxy, ProxyT},
     spa::utils::Direction,
     types::ObjectType,
 };
-use spa::{pod::Pod, spa_interface_call_method};
+use spa::{pod::Pod, spa_interface_call_method, spa_interface_call_method_checked};
 
 #[derive(Debug)]
 pub struct Port {
@@ -230,6 +231,7 @@ pub struct PortListener {
     listener: Pin<Box<spa_sys::spa_hook>>,
     #[allow(dead_code)]
     data: Box<ListenerLocalCallbacks>,
+    _tracked: TrackedListener,
 }
 
 impl Listener for PortListener {}
@@ -259,8 +261,19 @@ impl<'a> PortListenerLocalBuilder<'a> {
         self
     }
 
+    /// Register the listener.
+    ///
+    /// # Panics
+    /// If the port does not implement `add_listener`. Use [`try_register`](Self::try_register) to handle this error.
     #[must_use]
     pub fn register(self) -> PortListener {
+        self.try_register()
+            .expect("port does not implement add_listener")
+    }
+
+    /// Register the listener, returning an [`ENOTSUP`](nix::errno::Errno::ENOTSUP) error if the port
+    /// does not implement `add_listener`.
+    pub fn try_register(self) -> Result<PortListener, crate::Error> {
         unsafe extern "C" fn port_events_info(
             data: *mut c_void,
             info: *const pw_sys::pw_port_info,
@@ -312,7 +325,7 @@ impl<'a> PortListenerLocalBuilder<'a> {
             let mut listener: Pin<Box<spa_sys::spa_hook>> = Box::pin(mem::zeroed());
             let listener_ptr: *mut spa_sys::spa_hook = listener.as_mut().get_unchecked_mut();
 
-            spa_interface_call_method!(
+            let res = spa_interface_call_method_checked!(
                 port,
                 pw_sys::pw_port_methods,
                 add_listener,
@@ -321,13 +334,17 @@ impl<'a> PortListenerLocalBuilder<'a> {
                 data as *mut _
             );
 
-            (listener, Box::from_raw(data))
+            let data = Box::from_raw(data);
+            res?;
+
+            (listener, data)
         };
 
-        PortListener {
+        Ok(PortListener {
             events: e,
             listener,
             data,
-        }
+            _tracked: TrackedListener::new("Port", self.port.proxy.as_ptr().cast()),
+        })
     }
 }
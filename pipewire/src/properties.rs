@@ -109,6 +109,31 @@ impl Properties {
             Self::from_ptr(ptr::NonNull::new(copy).expect("pw_properties_new_dict() returned NULL"))
         }
     }
+
+    /// Request a latency of `frames` samples at `rate` for the node created with these properties,
+    /// by setting the [`NODE_LATENCY`](crate::keys::NODE_LATENCY) property.
+    ///
+    /// This is the way to request a latency when creating a stream with
+    /// [`Stream::new`](crate::stream::Stream::new) or [`Stream::new_with_defaults`](crate::stream::Stream::new_with_defaults),
+    /// [`StreamRef::set_latency`](crate::stream::StreamRef::set_latency) changes it afterwards.
+    /// Like with the latter, this is only a request, which the server may not honor.
+    #[must_use]
+    pub fn with_latency(mut self, frames: u32, rate: u32) -> Self {
+        self.insert(*crate::keys::NODE_LATENCY, format!("{}/{}", frames, rate));
+        self
+    }
+
+    /// Request the graph to run at `rate` while the node created with these properties is active,
+    /// by setting the [`NODE_RATE`](crate::keys::NODE_RATE) property.
+    ///
+    /// This is the creation-time equivalent of [`StreamRef::set_rate`](crate::stream::StreamRef::set_rate),
+    /// and the request may likewise not be honored by the server.
+    #[cfg(feature = "v0_3_33")]
+    #[must_use]
+    pub fn with_rate(mut self, rate: u32) -> Self {
+        self.insert(*crate::keys::NODE_RATE, format!("1/{}", rate));
+        self
+    }
 }
 
 impl AsRef<PropertiesRef> for Properties {
@@ -123,6 +148,20 @@ impl AsRef<spa::utils::dict::DictRef> for Properties {
     }
 }
 
+impl spa::utils::dict::ReadableDict for Properties {
+    fn get(&self, key: &str) -> Option<&str> {
+        self.dict().get(key)
+    }
+
+    fn iter(&self) -> spa::utils::dict::Iter<'_> {
+        self.dict().iter()
+    }
+
+    fn len(&self) -> usize {
+        self.dict().len()
+    }
+}
+
 impl std::ops::Deref for Properties {
     type Target = PropertiesRef;
 
@@ -175,9 +214,7 @@ impl Drop for Properties {
 
 impl fmt::Debug for Properties {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
-        let dict: &spa::utils::dict::DictRef = self.as_ref();
-        // FIXME: Debug-print dict keys and values directly
-        f.debug_tuple("Properties").field(dict).finish()
+        fmt::Debug::fmt(self.deref(), f)
     }
 }
 
@@ -203,6 +240,11 @@ impl PropertiesRef {
         unsafe { &*(&self.0.dict as *const spa_sys::spa_dict as *const spa::utils::dict::DictRef) }
     }
 
+    /// An iterator over all key-value pairs that are valid utf-8, without allocating.
+    pub fn iter(&self) -> spa::utils::dict::Iter<'_> {
+        self.dict().iter()
+    }
+
     // TODO: Impl as trait?
     pub fn to_owned(&self) -> Properties {
         unsafe {
@@ -242,6 +284,30 @@ impl PropertiesRef {
         unsafe { pw_sys::pw_properties_set(self.as_raw_ptr(), k.as_ptr(), v.as_ptr()) };
     }
 
+    /// Insert a key/value pair and set the provided [`Flags`](spa::utils::dict::Flags) on the properties.
+    ///
+    /// In the underlying `spa_dict`, flags apply to the whole dictionary rather than to a single item,
+    /// so the flags of previously inserted pairs are replaced as well.
+    /// [`insert`](Self::insert) leaves the flags untouched.
+    ///
+    /// Note that pipewire does not keep the items sorted when inserting, so setting
+    /// [`SORTED`](spa::utils::dict::Flags::SORTED) is only correct if the caller guarantees the items are sorted.
+    pub fn insert_with_flags<K, V>(&mut self, key: K, value: V, flags: spa::utils::dict::Flags)
+    where
+        K: Into<Vec<u8>>,
+        V: Into<Vec<u8>>,
+    {
+        self.insert(key, value);
+        self.set_flags(flags);
+    }
+
+    /// Set the [`Flags`](spa::utils::dict::Flags) of the underlying dictionary.
+    ///
+    /// The current flags can be retrieved using [`DictRef::flags`](spa::utils::dict::DictRef::flags) on the [`dict`](Self::dict).
+    pub fn set_flags(&mut self, flags: spa::utils::dict::Flags) {
+        self.0.dict.flags = flags.bits();
+    }
+
     pub fn remove<T>(&mut self, key: T)
     where
         T: Into<Vec<u8>>,
@@ -261,10 +327,39 @@ impl AsRef<spa::utils::dict::DictRef> for PropertiesRef {
     }
 }
 
+impl spa::utils::dict::ReadableDict for PropertiesRef {
+    fn get(&self, key: &str) -> Option<&str> {
+        self.dict().get(key)
+    }
+
+    fn iter(&self) -> spa::utils::dict::Iter<'_> {
+        self.dict().iter()
+    }
+
+    fn len(&self) -> usize {
+        self.dict().len()
+    }
+}
+
 impl fmt::Debug for PropertiesRef {
+    /// Print the properties as a map, such as `{"node.name": "foo"}`.
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
-        // FIXME: Debug-print dict key and values directly
-        f.debug_tuple("PropertiesRef").field(self.as_ref()).finish()
+        f.debug_map()
+            .entries(
+                self.dict()
+                    .iter_cstr()
+                    .map(|(key, value)| (key.to_string_lossy(), value.to_string_lossy())),
+            )
+            .finish()
+    }
+}
+
+impl<'a> IntoIterator for &'a PropertiesRef {
+    type Item = (&'a str, &'a str);
+    type IntoIter = spa::utils::dict::Iter<'a>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.iter()
     }
 }
 
@@ -280,10 +375,73 @@ where
     }
 }
 
+/// The keys that changed between two sets of properties.
+///
+/// This is what the `props_changed` callbacks of the [`Node`](crate::node::NodeListenerLocalBuilder::props_changed)
+/// and [`Device`](crate::device::DeviceListenerLocalBuilder::props_changed) listeners receive.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct PropertiesDiff {
+    /// The keys which were added, with their value.
+    pub added: Vec<(String, String)>,
+    /// The keys whose value changed, with their new value.
+    pub changed: Vec<(String, String)>,
+    /// The keys which were removed.
+    pub removed: Vec<String>,
+}
+
+impl PropertiesDiff {
+    /// Compute the changes from the `old` properties to the `new` ones.
+    pub fn new(old: &spa::utils::dict::DictRef, new: &spa::utils::dict::DictRef) -> Self {
+        let mut diff = Self::default();
+
+        for (key, value) in new.iter() {
+            match old.get(key) {
+                None => diff.added.push((key.to_string(), value.to_string())),
+                Some(old_value) if old_value != value => {
+                    diff.changed.push((key.to_string(), value.to_string()))
+                }
+                Some(_) => {}
+            }
+        }
+
+        diff.removed = old
+            .keys()
+            .filter(|key| new.get(key).is_none())
+            .map(str::to_string)
+            .collect();
+
+        diff
+    }
+
+    /// Returns `true` if no key was added, changed or removed.
+    pub fn is_empty(&self) -> bool {
+        self.added.is_empty() && self.changed.is_empty() && self.removed.is_empty()
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
 
+    #[test]
+    fn diff() {
+        let old = properties! {
+            "K0" => "V0",
+            "K1" => "V1"
+        };
+        let new = properties! {
+            "K1" => "V2",
+            "K2" => "V3"
+        };
+
+        let diff = PropertiesDiff::new(old.dict(), new.dict());
+        assert_eq!(diff.added, [("K2".to_string(), "V3".to_string())]);
+        assert_eq!(diff.changed, [("K1".to_string(), "V2".to_string())]);
+        assert_eq!(diff.removed, ["K0".to_string()]);
+
+        assert!(PropertiesDiff::new(new.dict(), new.dict()).is_empty());
+    }
+
     #[test]
     fn new() {
         let props = properties! {
@@ -317,6 +475,54 @@ mod tests {
         assert_eq!(Some("V1"), props.dict().get("K1"));
     }
 
+    #[test]
+    fn insert_with_flags() {
+        let mut props = properties! {
+            "K0" => "V0"
+        };
+
+        assert_eq!(spa::utils::dict::Flags::empty(), props.dict().flags());
+        props.insert_with_flags("K1", "V1", spa::utils::dict::Flags::SORTED);
+        assert_eq!(Some("V1"), props.dict().get("K1"));
+        assert_eq!(spa::utils::dict::Flags::SORTED, props.dict().flags());
+    }
+
+    #[test]
+    fn with_latency() {
+        let props = properties! {
+            "K0" => "V0"
+        }
+        .with_latency(256, 48000);
+
+        assert_eq!(Some("V0"), props.dict().get("K0"));
+        assert_eq!(Some("256/48000"), props.get(*crate::keys::NODE_LATENCY));
+    }
+
+    #[cfg(feature = "v0_3_33")]
+    #[test]
+    fn with_rate() {
+        let props = Properties::new().with_rate(44100);
+
+        assert_eq!(Some("1/44100"), props.get(*crate::keys::NODE_RATE));
+    }
+
+    #[test]
+    fn readable_dict() {
+        use spa::utils::dict::ReadableDict;
+
+        fn read(dict: &impl ReadableDict) -> (Option<&str>, usize) {
+            (dict.get("K0"), dict.len())
+        }
+
+        let props = properties! {
+            "K0" => "V0"
+        };
+
+        assert_eq!((Some("V0"), 1), read(&props));
+        assert_eq!((Some("V0"), 1), read(&*props));
+        assert_eq!(read(&props), read(props.dict()));
+    }
+
     #[test]
     fn clone() {
         let props1 = properties! {
@@ -366,4 +572,16 @@ mod tests {
         assert_eq!(props_copy.dict().len(), 1);
         assert_eq!(props_copy.dict().get("K0"), Some("V0"));
     }
+
+    #[test]
+    fn iter_and_debug() {
+        let props = properties! {
+            "K0" => "V0",
+            "K1" => "V1"
+        };
+
+        let entries: Vec<_> = props.iter().collect();
+        assert_eq!(entries, [("K0", "V0"), ("K1", "V1")]);
+        assert_eq!(format!("{:?}", props), r#"{"K0": "V0", "K1": "V1"}"#);
+    }
 }
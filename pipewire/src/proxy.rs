@@ -7,16 +7,24 @@ use std::mem;
 use std::pin::Pin;
 use std::{ffi::CStr, ptr};
 
-use crate::{types::ObjectType, Error};
+use spa::utils::result::AsyncSeq;
+
+use crate::{context::Context, listener_tracking::TrackedListener, types::ObjectType, Error};
 
 pub struct Proxy {
     ptr: ptr::NonNull<pw_sys::pw_proxy>,
+    // The context owns the connection of the proxy and destroys it with the connection,
+    // so it is kept alive until the proxy has been destroyed.
+    _context: Option<Context>,
 }
 
 // Wrapper around a proxy pointer
 impl Proxy {
-    pub(crate) fn new(ptr: ptr::NonNull<pw_sys::pw_proxy>) -> Self {
-        Proxy { ptr }
+    pub(crate) fn new(ptr: ptr::NonNull<pw_sys::pw_proxy>, context: Option<Context>) -> Self {
+        Proxy {
+            ptr,
+            _context: context,
+        }
     }
 
     pub(crate) fn as_ptr(&self) -> *mut pw_sys::pw_proxy {
@@ -34,6 +42,18 @@ impl Proxy {
         unsafe { pw_sys::pw_proxy_get_id(self.as_ptr()) }
     }
 
+    /// Ask the server to emit the [`done`](ProxyListenerLocalBuilder::done) event of this proxy
+    /// with the returned sequence number, after processing all the requests made before on the proxy.
+    ///
+    /// Unlike [`Core::sync`](crate::core::Core::sync), this only waits for the requests on this
+    /// proxy, for example to know when a [`set_param`](crate::node::Node::set_param) on a node was handled.
+    pub fn sync(&self, seq: i32) -> Result<AsyncSeq, Error> {
+        let res = unsafe { pw_sys::pw_proxy_sync(self.as_ptr(), seq) };
+
+        let res = spa::spa_result!(async res)?;
+        Ok(res)
+    }
+
     /// Get the type of the proxy as well as it's version.
     pub fn get_type(&self) -> (ObjectType, u32) {
         unsafe {
@@ -116,6 +136,7 @@ pub struct ProxyListener {
     listener: Pin<Box<spa_sys::spa_hook>>,
     #[allow(dead_code)]
     data: Box<ListenerLocalCallbacks>,
+    _tracked: TrackedListener,
 }
 
 impl Listener for ProxyListener {}
@@ -130,7 +151,7 @@ struct ListenerLocalCallbacks {
     destroy: Option<Box<dyn Fn()>>,
     bound: Option<Box<dyn Fn(u32)>>,
     removed: Option<Box<dyn Fn()>>,
-    done: Option<Box<dyn Fn(i32)>>,
+    done: Option<Box<dyn Fn(AsyncSeq)>>,
     #[allow(clippy::type_complexity)]
     error: Option<Box<dyn Fn(i32, i32, &str)>>, // TODO: return a proper Error enum?
 }
@@ -168,10 +189,12 @@ impl<'a> ProxyListenerLocalBuilder<'a> {
         self
     }
 
+    /// Set a callback called with the sequence number returned by [`Proxy::sync`],
+    /// once the requests made before the sync have been processed.
     #[must_use]
     pub fn done<F>(mut self, done: F) -> Self
     where
-        F: Fn(i32) + 'static,
+        F: Fn(AsyncSeq) + 'static,
     {
         self.cbs.done = Some(Box::new(done));
         self
@@ -205,7 +228,7 @@ impl<'a> ProxyListenerLocalBuilder<'a> {
 
         unsafe extern "C" fn proxy_done(data: *mut c_void, seq: i32) {
             let callbacks = (data as *mut ListenerLocalCallbacks).as_ref().unwrap();
-            callbacks.done.as_ref().unwrap()(seq);
+            callbacks.done.as_ref().unwrap()(AsyncSeq::from_raw(seq));
         }
 
         unsafe extern "C" fn proxy_error(
@@ -268,6 +291,7 @@ impl<'a> ProxyListenerLocalBuilder<'a> {
             events: e,
             listener,
             data,
+            _tracked: TrackedListener::new("Proxy", self.proxy.as_ptr().cast()),
         }
     }
 }
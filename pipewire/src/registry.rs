@@ -1,16 +1,23 @@
 // Copyright The pipewire-rs Contributors.
 // SPDX-License-Identifier: MIT
 
-use libc::{c_char, c_void};
+use libc::{c_char, c_int, c_void};
 
 use std::{
-    ffi::{CStr, CString},
-    mem,
+    cell::{Cell, RefCell},
+    ffi::CStr,
+    fmt, mem,
     pin::Pin,
     ptr,
+    rc::Rc,
+    sync::atomic::{AtomicBool, Ordering},
+    time::{Duration, Instant},
 };
 
 use crate::{
+    context::Context,
+    listener_tracking::TrackedListener,
+    loop_::LoopRef,
     permissions::PermissionFlags,
     properties::Properties,
     proxy::{Proxy, ProxyT},
@@ -18,17 +25,39 @@ use crate::{
     Error,
 };
 
+pub mod cache;
+
 #[derive(Debug)]
 pub struct Registry {
     ptr: ptr::NonNull<pw_sys::pw_registry>,
+    // Kept alive until the registry has been destroyed, like for a [`Proxy`].
+    context: Option<Context>,
 }
 
 impl Registry {
-    pub(crate) fn new(ptr: ptr::NonNull<pw_sys::pw_registry>) -> Self {
-        Registry { ptr }
+    pub(crate) fn new(ptr: ptr::NonNull<pw_sys::pw_registry>, context: Option<Context>) -> Self {
+        Registry { ptr, context }
+    }
+
+    /// Create a new registry from a raw [`pw_registry`](`pw_sys::pw_registry`), taking ownership of it.
+    ///
+    /// # Safety
+    /// The provided pointer must point to a valid, well aligned [`pw_registry`](`pw_sys::pw_registry`).
+    ///
+    /// The raw registry should not be manually destroyed, as the new [`Registry`] takes ownership of it
+    /// and destroys it when dropped.
+    ///
+    /// Unlike a registry returned by [`Core::get_registry`](crate::core::Core::get_registry), it does not
+    /// keep its context alive, so it must be dropped before the context.
+    pub unsafe fn from_raw(ptr: ptr::NonNull<pw_sys::pw_registry>) -> Self {
+        Self::new(ptr, None)
     }
 
-    fn as_ptr(&self) -> *mut pw_sys::pw_registry {
+    /// Get the raw [`pw_registry`](`pw_sys::pw_registry`) pointer of this registry.
+    ///
+    /// The pointer is only valid for as long as the [`Registry`] is alive, and remains owned
+    /// by it: it must not be destroyed manually.
+    pub fn as_raw_ptr(&self) -> *mut pw_sys::pw_registry {
         self.ptr.as_ptr()
     }
 
@@ -41,37 +70,317 @@ impl Registry {
         }
     }
 
+    /// Register a listener implementing the [`RegistryListener`] trait.
+    ///
+    /// This is an alternative to the closure based [`add_listener_local`](Self::add_listener_local),
+    /// requiring a single allocation for all the events and allowing the listener to keep its state,
+    /// which can then be accessed using [`TraitListener::get`].
+    ///
+    /// # Panics
+    /// If the registry does not implement `add_listener`.
+    /// Use [`try_add_listener_local_with`](Self::try_add_listener_local_with) to handle this error.
+    #[must_use]
+    pub fn add_listener_local_with<L: RegistryListener + 'static>(
+        &self,
+        listener: L,
+    ) -> TraitListener<L> {
+        self.try_add_listener_local_with(listener)
+            .expect("registry does not implement add_listener")
+    }
+
+    /// Register a listener implementing the [`RegistryListener`] trait, returning an
+    /// [`ENOTSUP`](nix::errno::Errno::ENOTSUP) error if the registry does not implement `add_listener`.
+    pub fn try_add_listener_local_with<L: RegistryListener + 'static>(
+        &self,
+        listener: L,
+    ) -> Result<TraitListener<L>, Error> {
+        unsafe extern "C" fn registry_events_global<L: RegistryListener>(
+            data: *mut c_void,
+            id: u32,
+            permissions: u32,
+            type_: *const c_char,
+            version: u32,
+            props: *const spa_sys::spa_dict,
+        ) {
+            let type_ = ObjectType::from_str(CStr::from_ptr(type_).to_str().unwrap());
+            let obj = GlobalObject::new(id, permissions, type_, version, props);
+            let listener = (data as *mut L).as_ref().unwrap();
+            listener.global(&obj);
+        }
+
+        unsafe extern "C" fn registry_events_global_remove<L: RegistryListener>(
+            data: *mut c_void,
+            id: u32,
+        ) {
+            let listener = (data as *mut L).as_ref().unwrap();
+            listener.global_remove(id);
+        }
+
+        let e = unsafe {
+            let mut e: Pin<Box<pw_sys::pw_registry_events>> = Box::pin(mem::zeroed());
+            e.version = pw_sys::PW_VERSION_REGISTRY_EVENTS;
+            e.global = Some(registry_events_global::<L>);
+            e.global_remove = Some(registry_events_global_remove::<L>);
+
+            e
+        };
+
+        let (hook, data) = unsafe {
+            let data = Box::into_raw(Box::new(listener));
+            let mut hook: Pin<Box<spa_sys::spa_hook>> = Box::pin(mem::zeroed());
+            let hook_ptr: *mut spa_sys::spa_hook = hook.as_mut().get_unchecked_mut();
+
+            let res = spa::spa_interface_call_method_checked!(
+                self.as_raw_ptr(),
+                pw_sys::pw_registry_methods,
+                add_listener,
+                hook_ptr.cast(),
+                e.as_ref().get_ref(),
+                data as *mut _
+            );
+
+            let data = Box::from_raw(data);
+            res?;
+
+            (hook, data)
+        };
+
+        Ok(TraitListener {
+            events: e,
+            listener: hook,
+            data,
+            _tracked: TrackedListener::new("Registry", self.as_raw_ptr().cast()),
+        })
+    }
+
+    /// Add a listener whose callbacks share the mutable state `state`.
+    ///
+    /// Each callback receives the state as `&mut S` along with the registry, so that state such as
+    /// the proxies bound for the globals can be updated without wrapping it in `Rc<RefCell<_>>`,
+    /// and globals can be bound without keeping a `Weak<Registry>` in the closures.
+    ///
+    /// All the callbacks are called on the thread of the loop, one at a time, so no locking is needed.
+    /// The state can be accessed between events using [`StatefulListener::state`].
+    ///
+    /// ```no_run
+    /// # fn main() -> Result<(), pipewire::Error> {
+    /// use std::collections::HashMap;
+    /// use pipewire::{node::Node, types::ObjectType};
+    ///
+    /// let mainloop = pipewire::main_loop::MainLoop::new(None)?;
+    /// let context = pipewire::context::Context::new(&mainloop)?;
+    /// let core = context.connect(None)?;
+    /// let registry = core.get_registry()?;
+    ///
+    /// let _listener = registry
+    ///     .add_listener_local_with_user_data(HashMap::<u32, Node>::new())
+    ///     .global(|nodes, registry, global| {
+    ///         if global.type_ == ObjectType::Node {
+    ///             nodes.insert(global.id, registry.bind_node(global).unwrap());
+    ///         }
+    ///     })
+    ///     .global_remove(|nodes, _registry, id| {
+    ///         nodes.remove(&id);
+    ///     })
+    ///     .register();
+    ///
+    /// mainloop.run();
+    /// # Ok(())
+    /// # }
+    /// ```
+    #[must_use]
+    pub fn add_listener_local_with_user_data<S: 'static>(
+        &self,
+        state: S,
+    ) -> StatefulListenerBuilder<S> {
+        StatefulListenerBuilder {
+            registry: self,
+            state,
+            global: None,
+            global_remove: None,
+        }
+    }
+
+    /// Bind the global `object`, creating a proxy of type `T` for it.
+    ///
+    /// Returns [`Error::WrongProxyType`] without binding the global if its type is not the type of `T`.
+    /// The typed variants, such as [`bind_node`](Self::bind_node), avoid having to specify `T`.
     pub fn bind<T: ProxyT, P: AsRef<spa::utils::dict::DictRef>>(
         &self,
         object: &GlobalObject<P>,
     ) -> Result<T, Error> {
+        if object.type_ != T::type_() {
+            return Err(Error::WrongProxyType);
+        }
+
+        let type_ = object.type_.as_type_info().ok_or(Error::WrongProxyType)?;
         let proxy = unsafe {
-            let type_ = CString::new(object.type_.to_str()).unwrap();
-            let version = object.type_.client_version();
+            let version = object.type_.client_version().min(object.version);
 
-            let proxy = spa::spa_interface_call_method!(
-                self.as_ptr(),
+            spa::spa_interface_call_method_checked!(
+                self.as_raw_ptr(),
                 pw_sys::pw_registry_methods,
                 bind,
                 object.id,
                 type_.as_ptr(),
                 version,
                 0
-            );
-
-            proxy
-        };
+            )
+        }?;
 
         let proxy = ptr::NonNull::new(proxy.cast()).ok_or(Error::NoMemory)?;
 
-        Proxy::new(proxy).downcast().map_err(|(_, e)| e)
+        Proxy::new(proxy, self.context.clone())
+            .downcast()
+            .map_err(|(_, e)| e)
+    }
+
+    /// Bind the client global `object`.
+    ///
+    /// Returns [`Error::WrongProxyType`] if the global is not a client.
+    pub fn bind_client<P: AsRef<spa::utils::dict::DictRef>>(
+        &self,
+        object: &GlobalObject<P>,
+    ) -> Result<crate::client::Client, Error> {
+        self.bind(object)
+    }
+
+    /// Bind the device global `object`.
+    ///
+    /// Returns [`Error::WrongProxyType`] if the global is not a device.
+    pub fn bind_device<P: AsRef<spa::utils::dict::DictRef>>(
+        &self,
+        object: &GlobalObject<P>,
+    ) -> Result<crate::device::Device, Error> {
+        self.bind(object)
+    }
+
+    /// Bind the factory global `object`.
+    ///
+    /// Returns [`Error::WrongProxyType`] if the global is not a factory.
+    pub fn bind_factory<P: AsRef<spa::utils::dict::DictRef>>(
+        &self,
+        object: &GlobalObject<P>,
+    ) -> Result<crate::factory::Factory, Error> {
+        self.bind(object)
+    }
+
+    /// Bind the link global `object`.
+    ///
+    /// Returns [`Error::WrongProxyType`] if the global is not a link.
+    pub fn bind_link<P: AsRef<spa::utils::dict::DictRef>>(
+        &self,
+        object: &GlobalObject<P>,
+    ) -> Result<crate::link::Link, Error> {
+        self.bind(object)
+    }
+
+    /// Bind the metadata global `object`.
+    ///
+    /// Returns [`Error::WrongProxyType`] if the global is not a metadata.
+    pub fn bind_metadata<P: AsRef<spa::utils::dict::DictRef>>(
+        &self,
+        object: &GlobalObject<P>,
+    ) -> Result<crate::metadata::Metadata, Error> {
+        self.bind(object)
+    }
+
+    /// Bind the module global `object`.
+    ///
+    /// Returns [`Error::WrongProxyType`] if the global is not a module.
+    pub fn bind_module<P: AsRef<spa::utils::dict::DictRef>>(
+        &self,
+        object: &GlobalObject<P>,
+    ) -> Result<crate::module::Module, Error> {
+        self.bind(object)
+    }
+
+    /// Bind the node global `object`.
+    ///
+    /// Returns [`Error::WrongProxyType`] if the global is not a node.
+    pub fn bind_node<P: AsRef<spa::utils::dict::DictRef>>(
+        &self,
+        object: &GlobalObject<P>,
+    ) -> Result<crate::node::Node, Error> {
+        self.bind(object)
+    }
+
+    /// Bind the port global `object`.
+    ///
+    /// Returns [`Error::WrongProxyType`] if the global is not a port.
+    pub fn bind_port<P: AsRef<spa::utils::dict::DictRef>>(
+        &self,
+        object: &GlobalObject<P>,
+    ) -> Result<crate::port::Port, Error> {
+        self.bind(object)
+    }
+
+    /// Iterate `loop_` until a global matching `predicate` is announced, or `timeout` elapses.
+    ///
+    /// Only the globals announced during the call are matched: the registry announces each global once,
+    /// when it is added or right after the registry is created, and not again to listeners registered later.
+    /// Use [`RegistryCache::wait_for`](cache::RegistryCache::wait_for) to also match the globals already announced.
+    /// `loop_` must be the loop of the context the registry was created from, and must not be running.
+    ///
+    /// If `cancel` is set to `true`, for example from a signal handler or another thread,
+    /// waiting stops and [`Error::Cancelled`] is returned. The flag is checked at least every 50 milliseconds.
+    ///
+    /// Returns [`Error::Timeout`] if no matching global was announced in time.
+    pub fn wait_for<F>(
+        &self,
+        loop_: &LoopRef,
+        predicate: F,
+        timeout: Duration,
+        cancel: Option<&AtomicBool>,
+    ) -> Result<OwnedGlobalObject, Error>
+    where
+        F: Fn(&GlobalObject<&spa::utils::dict::DictRef>) -> bool + 'static,
+    {
+        let found: Rc<RefCell<Option<OwnedGlobalObject>>> = Rc::default();
+
+        let found_clone = found.clone();
+        let _listener = self
+            .add_listener_local()
+            .global(move |global| {
+                let mut found = found_clone.borrow_mut();
+                if found.is_none() && predicate(global) {
+                    *found = Some(global.to_owned());
+                }
+            })
+            .register();
+
+        const CANCEL_POLL: Duration = Duration::from_millis(50);
+        const MAX_ITERATION: Duration = Duration::from_secs(60);
+
+        // A timeout too large to be represented, such as `Duration::MAX`, waits forever.
+        let deadline = Instant::now().checked_add(timeout);
+        loop {
+            if let Some(global) = found.take() {
+                return Ok(global);
+            }
+
+            let remaining = match deadline {
+                Some(deadline) => deadline.saturating_duration_since(Instant::now()),
+                None => Duration::MAX,
+            };
+            if remaining.is_zero() {
+                return Err(Error::Timeout);
+            }
+
+            match cancel {
+                Some(cancel) if cancel.load(Ordering::Relaxed) => return Err(Error::Cancelled),
+                Some(_) => loop_.iterate(remaining.min(CANCEL_POLL)),
+                // `iterate` panics if the timeout does not fit in a `c_int` of milliseconds.
+                None => loop_.iterate(remaining.min(MAX_ITERATION)),
+            };
+        }
     }
 
     /// Attempt to destroy the global object with the specified id on the remote.
     pub fn destroy_global(&self, global_id: u32) -> spa::utils::result::SpaResult {
         let result = unsafe {
             spa::spa_interface_call_method!(
-                self.as_ptr(),
+                self.as_raw_ptr(),
                 pw_sys::pw_registry_methods,
                 destroy,
                 global_id
@@ -85,18 +394,23 @@ impl Registry {
 impl Drop for Registry {
     fn drop(&mut self) {
         unsafe {
-            pw_sys::pw_proxy_destroy(self.as_ptr().cast());
+            pw_sys::pw_proxy_destroy(self.as_raw_ptr().cast());
         }
     }
 }
 
 type GlobalCallback = dyn Fn(&GlobalObject<&spa::utils::dict::DictRef>);
 type GlobalRemoveCallback = dyn Fn(u32);
+type GlobalFilter = dyn Fn(u32, &ObjectType) -> bool;
 
 #[derive(Default)]
 struct ListenerLocalCallbacks {
     global: Option<Box<GlobalCallback>>,
     global_remove: Option<Box<GlobalRemoveCallback>>,
+    filter: Option<Box<GlobalFilter>>,
+    initial_sync_done: Option<Box<dyn Fn()>>,
+    initial_sync_seq: Cell<Option<i32>>,
+    skip_unbindable: bool,
 }
 
 pub struct ListenerLocalBuilder<'a> {
@@ -104,21 +418,203 @@ pub struct ListenerLocalBuilder<'a> {
     cbs: ListenerLocalCallbacks,
 }
 
+/// The `global_remove` event of the listeners registered with [`ListenerLocalBuilder`].
+///
+/// It is called for each removed global so it must not allocate, which is checked by the
+/// `registry_alloc` integration test, using its own global allocator.
+unsafe extern "C" fn registry_events_global_remove(data: *mut c_void, id: u32) {
+    let callbacks = (data as *mut ListenerLocalCallbacks).as_ref().unwrap();
+    callbacks.global_remove.as_ref().unwrap()(id);
+}
+
 pub struct Listener {
     // Need to stay allocated while the listener is registered
     #[allow(dead_code)]
     events: Pin<Box<pw_sys::pw_registry_events>>,
     listener: Pin<Box<spa_sys::spa_hook>>,
+    // Proxy listener used to track the initial sync, if requested.
     #[allow(dead_code)]
+    proxy_events: Option<Pin<Box<pw_sys::pw_proxy_events>>>,
+    proxy_listener: Option<Pin<Box<spa_sys::spa_hook>>>,
     data: Box<ListenerLocalCallbacks>,
+    _tracked: TrackedListener,
 }
 
 impl Drop for Listener {
+    fn drop(&mut self) {
+        spa::utils::hook::remove(*self.listener);
+        if let Some(proxy_listener) = &self.proxy_listener {
+            spa::utils::hook::remove(**proxy_listener);
+        }
+    }
+}
+
+/// A listener for the events of a [`Registry`], registered using [`Registry::add_listener_local_with`].
+///
+/// All methods have an empty default implementation, so only the events of interest need to be implemented.
+///
+/// The methods take `&self`, as the listener may be accessed using [`TraitListener::get`] between events,
+/// so any state updated by the listener should use interior mutability, such as a [`Cell`].
+/// Unlike state shared with the closures of [`ListenerLocalBuilder`], it does not need to be wrapped in an `Rc`.
+pub trait RegistryListener {
+    /// Called when a global is added to the registry.
+    fn global(&self, global: &GlobalObject<&spa::utils::dict::DictRef>) {
+        let _ = global;
+    }
+
+    /// Called when the global with id `id` is removed from the registry.
+    fn global_remove(&self, id: u32) {
+        let _ = id;
+    }
+}
+
+/// A registered [`RegistryListener`], which stops receiving events when dropped.
+pub struct TraitListener<L: RegistryListener> {
+    // Need to stay allocated while the listener is registered
+    #[allow(dead_code)]
+    events: Pin<Box<pw_sys::pw_registry_events>>,
+    listener: Pin<Box<spa_sys::spa_hook>>,
+    data: Box<L>,
+    _tracked: TrackedListener,
+}
+
+impl<L: RegistryListener> TraitListener<L> {
+    /// Get a reference to the registered listener.
+    pub fn get(&self) -> &L {
+        &self.data
+    }
+}
+
+impl<L: RegistryListener> Drop for TraitListener<L> {
     fn drop(&mut self) {
         spa::utils::hook::remove(*self.listener);
     }
 }
 
+type StatefulGlobalCB<S> = dyn Fn(&mut S, &Registry, &GlobalObject<&spa::utils::dict::DictRef>);
+
+/// A builder for a registry listener sharing a mutable state between its callbacks,
+/// see [`Registry::add_listener_local_with_user_data`].
+pub struct StatefulListenerBuilder<'a, S> {
+    registry: &'a Registry,
+    state: S,
+    global: Option<Box<StatefulGlobalCB<S>>>,
+    global_remove: Option<Box<dyn Fn(&mut S, &Registry, u32)>>,
+}
+
+impl<'a, S: 'static> StatefulListenerBuilder<'a, S> {
+    /// Set the callback for the `global` event.
+    #[must_use]
+    pub fn global<F>(mut self, global: F) -> Self
+    where
+        F: Fn(&mut S, &Registry, &GlobalObject<&spa::utils::dict::DictRef>) + 'static,
+    {
+        self.global = Some(Box::new(global));
+        self
+    }
+
+    /// Set the callback for the `global_remove` event.
+    #[must_use]
+    pub fn global_remove<F>(mut self, global_remove: F) -> Self
+    where
+        F: Fn(&mut S, &Registry, u32) + 'static,
+    {
+        self.global_remove = Some(Box::new(global_remove));
+        self
+    }
+
+    /// Register the listener.
+    ///
+    /// # Panics
+    /// If the registry does not implement `add_listener`. Use [`try_register`](Self::try_register) to handle this error.
+    #[must_use]
+    pub fn register(self) -> StatefulListener<S> {
+        self.try_register()
+            .expect("registry does not implement add_listener")
+    }
+
+    /// Register the listener, returning an [`ENOTSUP`](nix::errno::Errno::ENOTSUP) error if the registry
+    /// does not implement `add_listener`.
+    pub fn try_register(self) -> Result<StatefulListener<S>, Error> {
+        let listener = self.registry.try_add_listener_local_with(Stateful {
+            registry: self.registry.ptr,
+            context: self.registry.context.clone(),
+            state: RefCell::new(self.state),
+            global: self.global,
+            global_remove: self.global_remove,
+        })?;
+
+        Ok(StatefulListener { listener })
+    }
+}
+
+/// The [`RegistryListener`] behind a [`StatefulListener`].
+struct Stateful<S> {
+    registry: ptr::NonNull<pw_sys::pw_registry>,
+    // The context of the registry, for the proxies bound from the events.
+    context: Option<Context>,
+    state: RefCell<S>,
+    global: Option<Box<StatefulGlobalCB<S>>>,
+    global_remove: Option<Box<dyn Fn(&mut S, &Registry, u32)>>,
+}
+
+impl<S> Stateful<S> {
+    fn call(&self, f: impl FnOnce(&mut S, &Registry)) {
+        // Events are only emitted while the registry is alive, and the borrowed registry
+        // must not destroy the proxy when dropped.
+        let mut registry =
+            mem::ManuallyDrop::new(Registry::new(self.registry, self.context.clone()));
+        let mut state = self
+            .state
+            .try_borrow_mut()
+            .expect("Registry listener state borrowed during an event");
+        f(&mut state, &registry);
+        drop(registry.context.take());
+    }
+}
+
+impl<S> RegistryListener for Stateful<S> {
+    fn global(&self, global: &GlobalObject<&spa::utils::dict::DictRef>) {
+        if let Some(cb) = &self.global {
+            self.call(|state, registry| cb(state, registry, global));
+        }
+    }
+
+    fn global_remove(&self, id: u32) {
+        if let Some(cb) = &self.global_remove {
+            self.call(|state, registry| cb(state, registry, id));
+        }
+    }
+}
+
+/// A registered listener sharing a mutable state between its callbacks,
+/// see [`Registry::add_listener_local_with_user_data`].
+///
+/// The listener stops receiving events when dropped.
+pub struct StatefulListener<S> {
+    listener: TraitListener<Stateful<S>>,
+}
+
+impl<S> StatefulListener<S> {
+    /// Borrow the state of the listener.
+    ///
+    /// # Panics
+    /// If the state is borrowed mutably, which is only the case while a callback is running.
+    /// Likewise, a callback panics if it is called while the returned borrow is still held,
+    /// for example if it is kept while iterating the loop.
+    pub fn state(&self) -> std::cell::Ref<'_, S> {
+        self.listener.get().state.borrow()
+    }
+
+    /// Mutably borrow the state of the listener.
+    ///
+    /// # Panics
+    /// If the state is already borrowed, see [`state`](Self::state).
+    pub fn state_mut(&self) -> std::cell::RefMut<'_, S> {
+        self.listener.get().state.borrow_mut()
+    }
+}
+
 impl<'a> ListenerLocalBuilder<'a> {
     #[must_use]
     pub fn global<F>(mut self, global: F) -> Self
@@ -138,8 +634,61 @@ impl<'a> ListenerLocalBuilder<'a> {
         self
     }
 
+    /// Only call the [`global`](Self::global) callback for globals for which `filter` returns `true`,
+    /// given their id and type.
+    ///
+    /// The filter is called before the [`GlobalObject`] is created, so skipping most globals,
+    /// for example to only keep the nodes, is cheaper than filtering them in the `global` callback.
+    ///
+    /// The [`global_remove`](Self::global_remove) callback is still called for the ids of
+    /// filtered out globals.
+    #[must_use]
+    pub fn filter<F>(mut self, filter: F) -> Self
+    where
+        F: Fn(u32, &ObjectType) -> bool + 'static,
+    {
+        self.cbs.filter = Some(Box::new(filter));
+        self
+    }
+
+    /// Do not call the [`global`](Self::global) callback for globals which are not
+    /// [bindable](GlobalObject::is_bindable), as binding them would fail.
+    #[must_use]
+    pub fn skip_unbindable(mut self) -> Self {
+        self.cbs.skip_unbindable = true;
+        self
+    }
+
+    /// Set a callback called once all the globals existing when the listener is registered
+    /// have been reported to the [`global`](Self::global) callback.
+    ///
+    /// This is done by issuing a sync on the registry right after registering the listener,
+    /// and the callback is only called once.
+    #[must_use]
+    pub fn initial_sync_done<F>(mut self, initial_sync_done: F) -> Self
+    where
+        F: Fn() + 'static,
+    {
+        self.cbs.initial_sync_done = Some(Box::new(initial_sync_done));
+        self
+    }
+
+    /// Register the listener.
+    ///
+    /// # Panics
+    /// If the registry does not implement `add_listener`, or if an [`initial_sync_done`](Self::initial_sync_done)
+    /// callback is set and the sync can not be issued, for example because the connection was lost.
+    /// Use [`try_register`](Self::try_register) to handle these errors.
     #[must_use]
     pub fn register(self) -> Listener {
+        self.try_register()
+            .expect("Failed to register the registry listener")
+    }
+
+    /// Register the listener, returning an error if the registry does not implement `add_listener`
+    /// ([`ENOTSUP`](nix::errno::Errno::ENOTSUP)), or if an [`initial_sync_done`](Self::initial_sync_done)
+    /// callback is set and the sync can not be issued.
+    pub fn try_register(self) -> Result<Listener, Error> {
         unsafe extern "C" fn registry_events_global(
             data: *mut c_void,
             id: u32,
@@ -148,15 +697,27 @@ impl<'a> ListenerLocalBuilder<'a> {
             version: u32,
             props: *const spa_sys::spa_dict,
         ) {
-            let type_ = CStr::from_ptr(type_).to_str().unwrap();
-            let obj = GlobalObject::new(id, permissions, type_, version, props);
+            let type_ = ObjectType::from_str(CStr::from_ptr(type_).to_str().unwrap());
             let callbacks = (data as *mut ListenerLocalCallbacks).as_ref().unwrap();
+            if let Some(filter) = &callbacks.filter {
+                if !filter(id, &type_) {
+                    return;
+                }
+            }
+
+            let obj = GlobalObject::new(id, permissions, type_, version, props);
+            if callbacks.skip_unbindable && !obj.is_bindable() {
+                return;
+            }
             callbacks.global.as_ref().unwrap()(&obj);
         }
 
-        unsafe extern "C" fn registry_events_global_remove(data: *mut c_void, id: u32) {
+        unsafe extern "C" fn registry_proxy_done(data: *mut c_void, seq: c_int) {
             let callbacks = (data as *mut ListenerLocalCallbacks).as_ref().unwrap();
-            callbacks.global_remove.as_ref().unwrap()(id);
+            if callbacks.initial_sync_seq.get() == Some(seq) {
+                callbacks.initial_sync_seq.set(None);
+                callbacks.initial_sync_done.as_ref().unwrap()();
+            }
         }
 
         let e = unsafe {
@@ -173,13 +734,20 @@ impl<'a> ListenerLocalBuilder<'a> {
             e
         };
 
-        let (listener, data) = unsafe {
-            let ptr = self.registry.as_ptr();
+        let proxy_events = self.cbs.initial_sync_done.as_ref().map(|_| unsafe {
+            let mut e: Pin<Box<pw_sys::pw_proxy_events>> = Box::pin(mem::zeroed());
+            e.version = pw_sys::PW_VERSION_PROXY_EVENTS;
+            e.done = Some(registry_proxy_done);
+            e
+        });
+
+        let (listener, proxy_listener, data) = unsafe {
+            let ptr = self.registry.as_raw_ptr();
             let data = Box::into_raw(Box::new(self.cbs));
             let mut listener: Pin<Box<spa_sys::spa_hook>> = Box::pin(mem::zeroed());
             let listener_ptr: *mut spa_sys::spa_hook = listener.as_mut().get_unchecked_mut();
 
-            spa::spa_interface_call_method!(
+            let res = spa::spa_interface_call_method_checked!(
                 ptr,
                 pw_sys::pw_registry_methods,
                 add_listener,
@@ -187,18 +755,75 @@ impl<'a> ListenerLocalBuilder<'a> {
                 e.as_ref().get_ref(),
                 data as *mut _
             );
+            if let Err(err) = res {
+                drop(Box::from_raw(data));
+                return Err(err.into());
+            }
+
+            let proxy_listener = proxy_events.as_ref().map(|proxy_events| {
+                let mut proxy_listener: Pin<Box<spa_sys::spa_hook>> = Box::pin(mem::zeroed());
+                let proxy_listener_ptr: *mut spa_sys::spa_hook =
+                    proxy_listener.as_mut().get_unchecked_mut();
+
+                pw_sys::pw_proxy_add_listener(
+                    ptr.cast(),
+                    proxy_listener_ptr,
+                    proxy_events.as_ref().get_ref(),
+                    data as *mut _,
+                );
 
-            (listener, Box::from_raw(data))
+                proxy_listener
+            });
+
+            (listener, proxy_listener, Box::from_raw(data))
         };
 
-        Listener {
+        let listener = Listener {
             events: e,
             listener,
+            proxy_events,
+            proxy_listener,
             data,
+            _tracked: TrackedListener::new("Registry", self.registry.as_raw_ptr().cast()),
+        };
+
+        if listener.proxy_listener.is_some() {
+            // The listener is dropped, removing its hooks, if the sync can not be issued.
+            let seq = unsafe { pw_sys::pw_proxy_sync(self.registry.as_raw_ptr().cast(), 0) };
+            spa::spa_result!(seq)?;
+            listener.data.initial_sync_seq.set(Some(seq));
         }
+
+        Ok(listener)
     }
 }
 
+/// A global object announced by the registry.
+///
+/// The globals given to the [`global`](ListenerLocalBuilder::global) callback borrow their
+/// properties from memory only valid for the duration of the callback, as a `GlobalObject<&DictRef>`.
+/// Use [`into_owned`](Self::into_owned) or [`to_owned`](Self::to_owned) to get an [`OwnedGlobalObject`]
+/// copying everything, which can be stored or moved out of the callback.
+///
+/// # Permissions
+/// The [`permissions`](Self::permissions) are the ones our client has on the global.
+/// The server only announces globals with the [`R`](PermissionFlags::R) permission, and removes
+/// them from the registry if that permission is revoked later, so restricted clients such as
+/// sandboxed applications only see part of the graph.
+/// Binding a global without the `R` permission fails, see [`is_bindable`](Self::is_bindable).
+/// Calling methods on the bound proxy additionally requires the [`X`](PermissionFlags::X) permission,
+/// and changing its params the [`W`](PermissionFlags::W) permission.
+///
+/// # Versions
+/// The [`version`](Self::version) is the version of the interface implemented by the server for
+/// the global. [`Registry::bind`] binds the global with the lowest of that version and the one
+/// this crate was built against, so binding also works with servers older or newer than
+/// the installed headers.
+/// Methods and events added in a later version of an interface are only available if the bound
+/// version is recent enough, which can be checked with [`supports_version`](Self::supports_version).
+/// The methods wrapped by the typed proxies of this crate are all part of the versions of their
+/// interfaces shipped since PipeWire 0.3; events of a newer version than the bound one are
+/// simply never emitted.
 #[derive(Debug)]
 pub struct GlobalObject<P: AsRef<spa::utils::dict::DictRef>> {
     pub id: u32,
@@ -212,11 +837,10 @@ impl GlobalObject<&spa::utils::dict::DictRef> {
     unsafe fn new(
         id: u32,
         permissions: u32,
-        type_: &str,
+        type_: ObjectType,
         version: u32,
         props: *const spa_sys::spa_dict,
     ) -> Self {
-        let type_ = ObjectType::from_str(type_);
         let permissions = PermissionFlags::from_bits_retain(permissions);
         let props = ptr::NonNull::new(props.cast_mut())
             .map(|ptr| ptr.cast::<spa::utils::dict::DictRef>().as_ref());
@@ -232,7 +856,92 @@ impl GlobalObject<&spa::utils::dict::DictRef> {
 }
 
 impl<P: AsRef<spa::utils::dict::DictRef>> GlobalObject<P> {
-    pub fn to_owned(&self) -> GlobalObject<Properties> {
+    /// Returns `true` if we have the [`R`](PermissionFlags::R) permission on the global,
+    /// which is needed to [bind](Registry::bind) it.
+    pub fn is_bindable(&self) -> bool {
+        self.permissions.contains(PermissionFlags::R)
+    }
+
+    /// Returns `true` if the server implements at least version `min` of the interface of the global.
+    pub fn supports_version(&self, min: u32) -> bool {
+        self.version >= min
+    }
+
+    /// Returns `true` if the global is a monitor port, as set in its [`PORT_MONITOR`](crate::keys::PORT_MONITOR)
+    /// property.
+    ///
+    /// Monitor ports are the output ports of sinks, carrying a copy of the data played to the sink.
+    pub fn is_monitor_port(&self) -> bool {
+        self.type_ == ObjectType::Port
+            && self
+                .props
+                .as_ref()
+                .and_then(|props| props.as_ref().get(*crate::keys::PORT_MONITOR))
+                .map_or(false, |value| value == "true" || value == "1")
+    }
+
+    /// Get the property best describing the global, depending on its type.
+    fn name(&self) -> Option<&str> {
+        let key = match self.type_ {
+            ObjectType::Node => *crate::keys::NODE_NAME,
+            ObjectType::Port => *crate::keys::PORT_NAME,
+            ObjectType::Device => *crate::keys::DEVICE_NAME,
+            ObjectType::Client => *crate::keys::APP_NAME,
+            ObjectType::Module => *crate::keys::MODULE_NAME,
+            ObjectType::Factory => *crate::keys::FACTORY_NAME,
+            ObjectType::Core => *crate::keys::CORE_NAME,
+            ObjectType::Metadata => *crate::keys::METADATA_NAME,
+            _ => *crate::keys::OBJECT_PATH,
+        };
+
+        self.props.as_ref()?.as_ref().get(key)
+    }
+}
+
+impl<P: AsRef<spa::utils::dict::DictRef>> fmt::Display for GlobalObject<P> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let type_ = self.type_.to_str();
+        let type_ = type_.strip_prefix("PipeWire:Interface:").unwrap_or(type_);
+
+        let perm = |flag, c| {
+            if self.permissions.contains(flag) {
+                c
+            } else {
+                '-'
+            }
+        };
+
+        write!(
+            f,
+            "id: {}, type: {} (v{}), perms: {}{}{}{}",
+            self.id,
+            type_,
+            self.version,
+            perm(PermissionFlags::R, 'r'),
+            perm(PermissionFlags::W, 'w'),
+            perm(PermissionFlags::X, 'x'),
+            perm(PermissionFlags::M, 'm'),
+        )?;
+
+        if let Some(name) = self.name() {
+            write!(f, ", name: {}", name)?;
+        }
+
+        Ok(())
+    }
+}
+
+/// A [`GlobalObject`] owning its properties, not borrowing any memory.
+pub type OwnedGlobalObject = GlobalObject<Properties>;
+
+impl<P: AsRef<spa::utils::dict::DictRef>> GlobalObject<P> {
+    /// Convert the global into an [`OwnedGlobalObject`], deep-copying its properties.
+    pub fn into_owned(self) -> OwnedGlobalObject {
+        self.to_owned()
+    }
+
+    /// Get an [`OwnedGlobalObject`] copy of the global, deep-copying its properties.
+    pub fn to_owned(&self) -> OwnedGlobalObject {
         GlobalObject {
             id: self.id,
             permissions: self.permissions,
@@ -249,6 +958,7 @@ impl<P: AsRef<spa::utils::dict::DictRef>> GlobalObject<P> {
 #[cfg(test)]
 mod tests {
     use super::*;
+
     #[test]
     fn set_object_type() {
         assert_eq!(
@@ -263,6 +973,85 @@ mod tests {
         assert_eq!(o.to_str(), "PipeWire:Interface:Badger");
     }
 
+    #[test]
+    fn display_global_object() {
+        let global = GlobalObject {
+            id: 42,
+            permissions: PermissionFlags::R | PermissionFlags::X,
+            type_: ObjectType::Node,
+            version: 3,
+            props: Some(crate::properties::properties! {
+                *crate::keys::NODE_NAME => "alsa_output"
+            }),
+        };
+
+        assert_eq!(
+            global.to_string(),
+            "id: 42, type: Node (v3), perms: r-x-, name: alsa_output"
+        );
+    }
+
+    #[test]
+    fn bindable_global_object() {
+        let mut global = GlobalObject {
+            id: 42,
+            permissions: PermissionFlags::R,
+            type_: ObjectType::Node,
+            version: 3,
+            props: None::<crate::properties::Properties>,
+        };
+        assert!(global.is_bindable());
+
+        global.permissions = PermissionFlags::W | PermissionFlags::X;
+        assert!(!global.is_bindable());
+    }
+
+    #[test]
+    fn monitor_port() {
+        let port = |props| GlobalObject {
+            id: 42,
+            permissions: PermissionFlags::R,
+            type_: ObjectType::Port,
+            version: 3,
+            props,
+        };
+
+        assert!(port(Some(crate::properties::properties! {
+            *crate::keys::PORT_MONITOR => "true"
+        }))
+        .is_monitor_port());
+        assert!(!port(Some(crate::properties::properties! {
+            *crate::keys::PORT_MONITOR => "false"
+        }))
+        .is_monitor_port());
+        assert!(!port(None).is_monitor_port());
+    }
+
+    #[test]
+    fn supports_version() {
+        let global = GlobalObject {
+            id: 42,
+            permissions: PermissionFlags::R,
+            type_: ObjectType::Metadata,
+            version: 3,
+            props: None::<crate::properties::Properties>,
+        };
+
+        assert!(global.supports_version(0));
+        assert!(global.supports_version(3));
+        assert!(!global.supports_version(4));
+    }
+
+    #[test]
+    fn known_object_types() {
+        assert!(ObjectType::Node.is_known());
+        assert!(!ObjectType::Other("PipeWire:Interface:Badger".to_string()).is_known());
+
+        assert!(ObjectType::all_known().all(|t| t.is_known()));
+        assert!(ObjectType::all_known().any(|t| t == ObjectType::Metadata));
+        assert!(ObjectType::all_known().all(|t| ObjectType::from_str(t.to_str()) == t));
+    }
+
     #[test]
     #[should_panic(expected = "Invalid object type")]
     fn client_version_panic() {
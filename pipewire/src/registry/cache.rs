@@ -0,0 +1,253 @@
+// Copyright The pipewire-rs Contributors.
+// SPDX-License-Identifier: MIT
+
+//! A local cache of the globals announced by a [`Registry`].
+
+use std::{
+    cell::{Cell, Ref, RefCell},
+    collections::BTreeMap,
+    rc::Rc,
+    sync::atomic::AtomicBool,
+    time::Duration,
+};
+
+use super::{GlobalObject, Listener, OwnedGlobalObject, Registry};
+use crate::{loop_::LoopRef, permissions::PermissionFlags, types::ObjectType, Error};
+
+/// Keeps track of all the globals announced by a [`Registry`], until they are removed.
+///
+/// The cache is filled in while the loop is running, as the registry emits its events.
+/// Use [`is_synced`](Self::is_synced) to know if all the globals existing when the cache was
+/// created have been received.
+///
+/// The cache must not outlive the [`Registry`] it was created from.
+pub struct RegistryCache {
+    globals: Rc<RefCell<BTreeMap<u32, OwnedGlobalObject>>>,
+    synced: Rc<Cell<bool>>,
+    _listener: Listener,
+}
+
+impl RegistryCache {
+    /// Create a new cache, tracking the globals of `registry`.
+    pub fn new(registry: &Registry) -> Self {
+        Self::new_internal(registry, None)
+    }
+
+    /// Create a new cache, tracking the globals of `registry`, and calling `removed` with
+    /// each global removed from the registry.
+    ///
+    /// Unlike the [`global_remove`](super::ListenerLocalBuilder::global_remove) event of the registry,
+    /// the callback receives the cached global, so that its type and properties are still available,
+    /// for example to remove it from a list displayed by a UI.
+    /// Globals removed before they were received by the cache are reported by their id only.
+    ///
+    /// The callback is called after the global was removed from the cache.
+    pub fn with_removed<F>(registry: &Registry, removed: F) -> Self
+    where
+        F: Fn(RemovedGlobal) + 'static,
+    {
+        Self::new_internal(registry, Some(Box::new(removed)))
+    }
+
+    fn new_internal(registry: &Registry, removed: Option<Box<dyn Fn(RemovedGlobal)>>) -> Self {
+        let globals: Rc<RefCell<BTreeMap<u32, OwnedGlobalObject>>> = Rc::default();
+        let synced = Rc::new(Cell::new(false));
+
+        let globals_add = globals.clone();
+        let globals_remove = globals.clone();
+        let synced_clone = synced.clone();
+        let listener = registry
+            .add_listener_local()
+            .global(move |global| {
+                globals_add
+                    .borrow_mut()
+                    .insert(global.id, global.to_owned());
+            })
+            .global_remove(move |id| {
+                let global = globals_remove.borrow_mut().remove(&id);
+                if let Some(removed) = &removed {
+                    removed(global.map_or(RemovedGlobal::Unknown(id), RemovedGlobal::Known));
+                }
+            })
+            .initial_sync_done(move || synced_clone.set(true))
+            .register();
+
+        Self {
+            globals,
+            synced,
+            _listener: listener,
+        }
+    }
+
+    /// Returns `true` once all the globals existing when the cache was created have been received.
+    pub fn is_synced(&self) -> bool {
+        self.synced.get()
+    }
+
+    /// All the globals currently known, sorted by id.
+    ///
+    /// The returned [`Ref`] must be dropped before the loop dispatches registry events again,
+    /// otherwise updating the cache will panic.
+    pub fn globals(&self) -> Ref<'_, BTreeMap<u32, OwnedGlobalObject>> {
+        self.globals.borrow()
+    }
+
+    /// Get the global with the provided `id`, if known.
+    pub fn get(&self, id: u32) -> Option<Ref<'_, OwnedGlobalObject>> {
+        Ref::filter_map(self.globals.borrow(), |globals| globals.get(&id)).ok()
+    }
+
+    /// Get a copy of the first known global matching `predicate`, or wait for one to be announced.
+    ///
+    /// Unlike [`Registry::wait_for`], this also matches the globals announced before the call,
+    /// as long as they are in the cache. See [`Registry::wait_for`] for the other arguments,
+    /// `registry` must be the registry the cache was created from.
+    pub fn wait_for<F>(
+        &self,
+        registry: &Registry,
+        loop_: &LoopRef,
+        predicate: F,
+        timeout: Duration,
+        cancel: Option<&AtomicBool>,
+    ) -> Result<OwnedGlobalObject, Error>
+    where
+        F: Fn(&GlobalObject<&spa::utils::dict::DictRef>) -> bool + 'static,
+    {
+        let cached = self.globals.borrow().values().find_map(|global| {
+            let borrowed = GlobalObject {
+                id: global.id,
+                permissions: global.permissions,
+                type_: global.type_.clone(),
+                version: global.version,
+                props: global
+                    .props
+                    .as_ref()
+                    .map(AsRef::<spa::utils::dict::DictRef>::as_ref),
+            };
+            predicate(&borrowed).then(|| global.to_owned())
+        });
+
+        match cached {
+            Some(global) => Ok(global),
+            None => registry.wait_for(loop_, predicate, timeout, cancel),
+        }
+    }
+
+    /// Get a copy of all the known globals of type `type_`, sorted by id.
+    pub fn of_type(&self, type_: &ObjectType) -> Vec<OwnedGlobalObject> {
+        self.globals
+            .borrow()
+            .values()
+            .filter(|global| &global.type_ == type_)
+            .map(|global| global.to_owned())
+            .collect()
+    }
+
+    /// Get a copy of all the port globals belonging to the node with id `node_id`.
+    ///
+    /// Ports are matched using their [`NODE_ID`](crate::keys::NODE_ID) property,
+    /// and sorted by their [`PORT_ID`](crate::keys::PORT_ID) property.
+    pub fn ports_of_node(&self, node_id: u32) -> Vec<OwnedGlobalObject> {
+        let prop_u32 = |global: &OwnedGlobalObject, key: &str| {
+            prop(global, key).and_then(|value| value.parse::<u32>().ok())
+        };
+
+        let mut ports: Vec<_> = self
+            .of_type(&ObjectType::Port)
+            .into_iter()
+            .filter(|port| prop_u32(port, *crate::keys::NODE_ID) == Some(node_id))
+            .collect();
+        ports.sort_by_key(|port| prop_u32(port, *crate::keys::PORT_ID));
+
+        ports
+    }
+
+    /// Get all the loaded modules, sorted by id.
+    ///
+    /// The arguments of a module are not part of the properties of its global. To get them,
+    /// bind the global to a [`Module`](crate::module::Module) and use the `args` of its info.
+    pub fn modules(&self) -> Vec<LoadedModule> {
+        self.of_type(&ObjectType::Module)
+            .iter()
+            .map(|module| LoadedModule {
+                id: module.id,
+                name: prop_string(module, *crate::keys::MODULE_NAME),
+                description: prop_string(module, *crate::keys::MODULE_DESCRIPTION),
+                version: prop_string(module, *crate::keys::MODULE_VERSION),
+            })
+            .collect()
+    }
+
+    /// Get all the connected clients with information about their process, sorted by id.
+    ///
+    /// The process information is provided by the clients themselves when connecting,
+    /// except for sandboxed clients, for which it is set by the server.
+    pub fn clients(&self) -> Vec<ConnectedClient> {
+        self.of_type(&ObjectType::Client)
+            .iter()
+            .map(|client| ConnectedClient {
+                id: client.id,
+                app_name: prop_string(client, *crate::keys::APP_NAME),
+                process_id: prop(client, *crate::keys::APP_PROCESS_ID)
+                    .and_then(|pid| pid.parse().ok()),
+                process_binary: prop_string(client, *crate::keys::APP_PROCESS_BINARY),
+                permissions: client.permissions,
+            })
+            .collect()
+    }
+}
+
+/// A global removed from the registry, as passed to the callback of [`RegistryCache::with_removed`].
+#[derive(Debug)]
+pub enum RemovedGlobal {
+    /// The global was known by the cache.
+    Known(OwnedGlobalObject),
+    /// The global was removed before the cache received it, only its id is known.
+    Unknown(u32),
+}
+
+impl RemovedGlobal {
+    /// The id of the removed global.
+    pub fn id(&self) -> u32 {
+        match self {
+            Self::Known(global) => global.id,
+            Self::Unknown(id) => *id,
+        }
+    }
+}
+
+/// A client connected to the server, as returned by [`RegistryCache::clients`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ConnectedClient {
+    /// The id of the client global, as used by [`Client::update_permissions`](crate::client::Client::update_permissions).
+    pub id: u32,
+    /// The [`APP_NAME`](crate::keys::APP_NAME) property of the client.
+    pub app_name: Option<String>,
+    /// The [`APP_PROCESS_ID`](crate::keys::APP_PROCESS_ID) property of the client.
+    pub process_id: Option<u32>,
+    /// The [`APP_PROCESS_BINARY`](crate::keys::APP_PROCESS_BINARY) property of the client.
+    pub process_binary: Option<String>,
+    /// The permissions we have on the client global.
+    pub permissions: PermissionFlags,
+}
+
+/// A module loaded by the server, as returned by [`RegistryCache::modules`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct LoadedModule {
+    /// The id of the module global.
+    pub id: u32,
+    /// The [`MODULE_NAME`](crate::keys::MODULE_NAME) property of the module.
+    pub name: Option<String>,
+    /// The [`MODULE_DESCRIPTION`](crate::keys::MODULE_DESCRIPTION) property of the module.
+    pub description: Option<String>,
+    /// The [`MODULE_VERSION`](crate::keys::MODULE_VERSION) property of the module.
+    pub version: Option<String>,
+}
+
+fn prop<'a>(global: &'a OwnedGlobalObject, key: &str) -> Option<&'a str> {
+    global.props.as_ref().and_then(|props| props.get(key))
+}
+
+fn prop_string(global: &OwnedGlobalObject, key: &str) -> Option<String> {
+    prop(global, key).map(str::to_string)
+}
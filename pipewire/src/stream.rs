@@ -7,20 +7,29 @@ use crate::buffer::Buffer;
 use crate::{
     core::Core,
     error::Error,
+    listener_tracking::TrackedListener,
     properties::{Properties, PropertiesRef},
 };
 use bitflags::bitflags;
-use spa::utils::result::SpaResult;
+use spa::param::ParamObject;
 use std::{
-    ffi::{self, CStr, CString},
+    cell::OnceCell,
+    ffi::{self, CStr},
     fmt::Debug,
     mem, os,
     pin::Pin,
     ptr,
 };
 
+#[cfg(feature = "futures")]
+pub mod buffer_stream;
+mod stats;
+
+pub use stats::StreamStats;
+
 #[derive(Debug, PartialEq)]
 pub enum StreamState {
+    /// The stream is in error, with a message describing the error.
     Error(String),
     Unconnected,
     Connecting,
@@ -48,12 +57,55 @@ impl StreamState {
     }
 }
 
+/// The node a stream is connected to by [`StreamRef::connect_to`].
+#[derive(Debug, Clone, PartialEq, Eq, Default)]
+pub enum StreamTarget {
+    /// Let the session manager connect the stream to the default device.
+    #[default]
+    Default,
+    /// The node with the provided id.
+    ///
+    /// Ids may be reused after an object is destroyed, prefer [`Serial`](Self::Serial) where available.
+    NodeId(u32),
+    /// The object with the provided [`OBJECT_SERIAL`](crate::keys::OBJECT_SERIAL).
+    #[cfg(feature = "v0_3_44")]
+    Serial(u64),
+    /// The object with the provided name, such as the [`NODE_NAME`](crate::keys::NODE_NAME) of a device node.
+    #[cfg(feature = "v0_3_44")]
+    Name(String),
+}
+
+/// Get the default properties of an audio stream in `direction`, as used by
+/// [`Stream::new_with_defaults`]:
+///
+/// - [`MEDIA_TYPE`](crate::keys::MEDIA_TYPE): `Audio`
+/// - [`MEDIA_CATEGORY`](crate::keys::MEDIA_CATEGORY): `Playback` for [`Direction::Output`](spa::utils::Direction::Output),
+///   `Capture` for [`Direction::Input`](spa::utils::Direction::Input)
+/// - [`MEDIA_ROLE`](crate::keys::MEDIA_ROLE): `Music`
+///
+/// These are the defaults of the `pw-cat` tool, and are used by the session manager
+/// to pick the device and the policy for the stream.
+pub fn default_properties(direction: spa::utils::Direction) -> Properties {
+    let category = if direction == spa::utils::Direction::Input {
+        "Capture"
+    } else {
+        "Playback"
+    };
+
+    crate::properties::properties! {
+        *crate::keys::MEDIA_TYPE => "Audio",
+        *crate::keys::MEDIA_CATEGORY => category,
+        *crate::keys::MEDIA_ROLE => "Music",
+    }
+}
+
 /// A wrapper around the pipewire stream interface. Streams are a higher
 /// level abstraction around nodes in the graph. A stream can be used to send or
 /// receive frames of audio or video data by connecting it to another node.
 /// `D` is the user data, to allow passing extra context to the callbacks.
 pub struct Stream {
     ptr: ptr::NonNull<pw_sys::pw_stream>,
+    stats: OnceCell<stats::StatsListener>,
     // objects that need to stay alive while the Stream is
     _core: Core,
 }
@@ -62,8 +114,10 @@ impl Stream {
     /// Create a [`Stream`]
     ///
     /// Initialises a new stream with the given `name` and `properties`.
+    ///
+    /// A latency or a rate can be requested with [`Properties::with_latency`] and [`Properties::with_rate`].
     pub fn new(core: &Core, name: &str, properties: Properties) -> Result<Self, Error> {
-        let name = CString::new(name).expect("Invalid byte in stream name");
+        let name = crate::utils::null_terminated(name)?;
 
         let c_str = name.as_c_str();
         Stream::new_cstr(core, c_str, properties)
@@ -78,12 +132,53 @@ impl Stream {
 
         Ok(Stream {
             ptr: stream,
+            stats: OnceCell::new(),
             _core: core.clone(),
         })
     }
 
+    /// Create an audio [`Stream`] in `direction`, with `properties` merged over
+    /// the [`default_properties`] of the direction.
+    ///
+    /// This allows only setting the properties that differ from the defaults,
+    /// for example just [`MEDIA_ROLE`](crate::keys::MEDIA_ROLE).
+    /// To start from an empty set of properties instead, use [`new`](Self::new).
+    ///
+    /// ```no_run
+    /// # fn main() -> Result<(), pipewire::Error> {
+    /// use pipewire::{properties::properties, spa::utils::Direction, stream::Stream};
+    ///
+    /// let mainloop = pipewire::main_loop::MainLoop::new(None)?;
+    /// let context = pipewire::context::Context::new(&mainloop)?;
+    /// let core = context.connect(None)?;
+    ///
+    /// let stream = Stream::new_with_defaults(
+    ///     &core,
+    ///     "player",
+    ///     Direction::Output,
+    ///     properties! { *pipewire::keys::MEDIA_ROLE => "Game" },
+    /// )?;
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn new_with_defaults(
+        core: &Core,
+        name: &str,
+        direction: spa::utils::Direction,
+        properties: Properties,
+    ) -> Result<Self, Error> {
+        let mut merged = default_properties(direction);
+        for (key, value) in properties.iter() {
+            merged.insert(key, value);
+        }
+
+        Stream::new(core, name, merged)
+    }
+
     pub fn into_raw(self) -> *mut pw_sys::pw_stream {
         let mut this = std::mem::ManuallyDrop::new(self);
+        // The statistics listener is created by the wrapper, so it is removed with it.
+        this.stats.take();
 
         // FIXME: self needs to be wrapped in ManuallyDrop so the raw stream
         //        isn't destroyed. However, the core should still be dropped.
@@ -94,6 +189,27 @@ impl Stream {
 
         this.ptr.as_ptr()
     }
+
+    /// Get the statistics of the buffers of the stream, to detect that the application
+    /// does not keep up with the graph.
+    ///
+    /// Statistics are only collected once requested: the first call starts listening to the
+    /// buffer events of the stream and returns zeros. Call it once before [connecting](StreamRef::connect)
+    /// the stream to cover all of its buffers.
+    ///
+    /// # Thread safety
+    ///
+    /// The counters are updated where the buffer events and calls happen, which is the realtime
+    /// thread for a stream connected with [`StreamFlags::RT_PROCESS`], and are read here from the
+    /// thread of the stream's loop. Each counter is read atomically, but they are not read together,
+    /// so counters of a snapshot taken while the stream is processing may be one cycle apart.
+    /// Updating them never blocks the realtime thread: events happening while the statistics of a
+    /// stream are being enabled or disabled are not counted.
+    pub fn stats(&self) -> StreamStats {
+        self.stats
+            .get_or_init(|| stats::StatsListener::register(self))
+            .stats()
+    }
 }
 
 impl std::ops::Deref for Stream {
@@ -117,6 +233,8 @@ impl std::fmt::Debug for Stream {
 
 impl std::ops::Drop for Stream {
     fn drop(&mut self) {
+        // The statistics listener must be removed before the stream it is registered on is destroyed.
+        self.stats.take();
         unsafe { pw_sys::pw_stream_destroy(self.as_raw_ptr()) }
     }
 }
@@ -134,6 +252,13 @@ impl StreamRef {
     }
 
     /// Add a local listener builder
+    ///
+    /// All the callbacks of a listener are set at once when it is [registered](ListenerLocalBuilder::register),
+    /// and stay active until the returned [`StreamListener`] is dropped.
+    /// A stream can have several listeners, each called in the order they were registered, so
+    /// callbacks that need to be enabled or disabled at runtime, such as a `process` callback
+    /// only used while the stream is active, can be registered as a separate listener and
+    /// removed by dropping it, without affecting the other callbacks.
     #[must_use = "Fluent builder API"]
     pub fn add_local_listener_with_user_data<D>(
         &self,
@@ -158,6 +283,11 @@ impl StreamRef {
     ///
     /// Tries to connect to the node `id` in the given `direction`. If no node
     /// is provided then any suitable node will be used.
+    ///
+    /// The stream moves from [`StreamState::Unconnected`] to [`StreamState::Connecting`],
+    /// and then to [`StreamState::Paused`] once the format is negotiated.
+    /// A stream that was [disconnected](Self::disconnect) can be connected again, for example
+    /// to a different target node.
     // FIXME: high-level API for params
     pub fn connect(
         &self,
@@ -179,7 +309,127 @@ impl StreamRef {
             )
         };
 
-        SpaResult::from_c(r).into_sync_result()?;
+        spa::spa_result!(sync r)?;
+        Ok(())
+    }
+
+    /// Connect the stream to `target` in the given `direction`.
+    ///
+    /// This is a shortcut for [`connect`](Self::connect), setting the
+    /// [`TARGET_OBJECT`](crate::keys::TARGET_OBJECT) property of the stream when needed.
+    /// Connecting to [`StreamTarget::Default`] removes any target set by a previous connection,
+    /// letting the session manager pick the default device.
+    // FIXME: high-level API for params
+    pub fn connect_to(
+        &self,
+        direction: spa::utils::Direction,
+        target: &StreamTarget,
+        flags: StreamFlags,
+        params: &mut [&spa::pod::Pod],
+    ) -> Result<(), Error> {
+        let id = match target {
+            StreamTarget::NodeId(id) => Some(*id),
+            _ => None,
+        };
+
+        #[cfg(feature = "v0_3_44")]
+        {
+            let value = match target {
+                StreamTarget::Serial(serial) => Some(serial.to_string()),
+                StreamTarget::Name(name) => Some(name.clone()),
+                _ => None,
+            };
+            self.set_target_object(value.as_deref())?;
+        }
+
+        self.connect(direction, id, flags, params)
+    }
+
+    /// Set or remove the [`TARGET_OBJECT`](crate::keys::TARGET_OBJECT) property of the stream.
+    #[cfg(feature = "v0_3_44")]
+    fn set_target_object(&self, value: Option<&str>) -> Result<(), Error> {
+        self.update_property(*crate::keys::TARGET_OBJECT, value)
+    }
+
+    /// Request a latency of `frames` samples at `rate`, by setting the
+    /// [`NODE_LATENCY`](crate::keys::NODE_LATENCY) property of the stream.
+    ///
+    /// For example, `set_latency(256, 48000)` requests a quantum of 256 samples at 48kHz, about 5ms.
+    ///
+    /// This is only a hint: the graph runs with a single quantum, chosen by the server from the
+    /// latencies requested by all the active nodes and its configured limits, so the actual
+    /// quantum may differ. Use [`time`](Self::time) to check the actual latency.
+    ///
+    /// To request a latency from the creation of the stream, use [`Properties::with_latency`].
+    pub fn set_latency(&self, frames: u32, rate: u32) -> Result<(), Error> {
+        self.update_property(
+            *crate::keys::NODE_LATENCY,
+            Some(&format!("{}/{}", frames, rate)),
+        )
+    }
+
+    /// Request the graph to run at `rate`, by setting the [`NODE_RATE`](crate::keys::NODE_RATE)
+    /// property of the stream.
+    ///
+    /// This is only a hint: the server only switches to rates allowed by its configuration,
+    /// and only when no other active node prevents it.
+    ///
+    /// To request a rate from the creation of the stream, use [`Properties::with_rate`].
+    #[cfg(feature = "v0_3_33")]
+    pub fn set_rate(&self, rate: u32) -> Result<(), Error> {
+        self.update_property(*crate::keys::NODE_RATE, Some(&format!("1/{}", rate)))
+    }
+
+    /// Report the latency of the stream, by updating its [`Latency`](spa::param::ParamType::Latency) params.
+    ///
+    /// This is how a stream processing the data declares the latency it adds, so that
+    /// the graph can compensate for it. Unlike [`set_latency`](Self::set_latency), this does not
+    /// change the quantum of the graph.
+    ///
+    /// As updating the params replaces all the latency params of the stream, the latency of both
+    /// directions is reported at once: `input` for [`Direction::Input`](spa::utils::Direction::Input)
+    /// and `output` for [`Direction::Output`](spa::utils::Direction::Output).
+    /// Returns an `EINVAL` error if either of them has another direction.
+    pub fn report_latency(
+        &self,
+        input: &spa::param::latency::Latency,
+        output: &spa::param::latency::Latency,
+    ) -> Result<(), Error> {
+        if input.direction != spa::utils::Direction::Input
+            || output.direction != spa::utils::Direction::Output
+        {
+            return Err(Error::SpaError(nix::errno::Errno::EINVAL.into()));
+        }
+
+        let input = input.to_pod_bytes();
+        let output = output.to_pod_bytes();
+
+        self.update_params(&mut [
+            input.as_pod().expect("Serialized latency is a valid pod"),
+            output.as_pod().expect("Serialized latency is a valid pod"),
+        ])
+    }
+
+    /// Set or remove, if `value` is `None`, the property `key` of the stream.
+    fn update_property(&self, key: &str, value: Option<&str>) -> Result<(), Error> {
+        let key = crate::utils::null_terminated(key)?;
+        let value = value.map(crate::utils::null_terminated).transpose()?;
+
+        // Built by hand, as a NULL value removes the property.
+        let items = [spa::utils::dict::spa_dict_item {
+            key: key.as_ptr(),
+            value: value.as_deref().map_or(ptr::null(), CStr::as_ptr),
+        }];
+        let dict = spa_sys::spa_dict {
+            flags: 0,
+            n_items: items.len() as u32,
+            items: items.as_ptr(),
+        };
+
+        unsafe {
+            pw_sys::pw_stream_update_properties(self.as_raw_ptr(), &dict);
+        }
+
         Ok(())
     }
 
@@ -197,7 +447,7 @@ impl StreamRef {
             )
         };
 
-        SpaResult::from_c(r).into_sync_result()?;
+        spa::spa_result!(sync r)?;
         Ok(())
     }
 
@@ -205,7 +455,7 @@ impl StreamRef {
     pub fn set_active(&self, active: bool) -> Result<(), Error> {
         let r = unsafe { pw_sys::pw_stream_set_active(self.as_raw_ptr(), active) };
 
-        SpaResult::from_c(r).into_sync_result()?;
+        spa::spa_result!(sync r)?;
         Ok(())
     }
 
@@ -220,7 +470,9 @@ impl StreamRef {
     /// The pointer returned could be NULL if no buffer is available. The buffer
     /// should be returned to the stream once processing is complete.
     pub unsafe fn dequeue_raw_buffer(&self) -> *mut pw_sys::pw_buffer {
-        pw_sys::pw_stream_dequeue_buffer(self.as_raw_ptr())
+        let buffer = pw_sys::pw_stream_dequeue_buffer(self.as_raw_ptr());
+        stats::dequeued(self.as_raw_ptr(), buffer);
+        buffer
     }
 
     pub fn dequeue_buffer(&self) -> Option<Buffer> {
@@ -239,25 +491,32 @@ impl StreamRef {
     /// a call to [StreamRef::dequeue_raw_buffer()].
     pub unsafe fn queue_raw_buffer(&self, buffer: *mut pw_sys::pw_buffer) {
         pw_sys::pw_stream_queue_buffer(self.as_raw_ptr(), buffer);
+        stats::queued(self.as_raw_ptr());
     }
 
     /// Disconnect the stream
+    ///
+    /// The stream moves back to [`StreamState::Unconnected`], and its buffers are removed,
+    /// which is reported to the `remove_buffer` callback.
+    ///
+    /// The stream itself is not destroyed: its listeners remain registered, and it can be
+    /// connected again using [`connect`](Self::connect), to switch to another target node at runtime.
     pub fn disconnect(&self) -> Result<(), Error> {
         let r = unsafe { pw_sys::pw_stream_disconnect(self.as_raw_ptr()) };
 
-        SpaResult::from_c(r).into_sync_result()?;
+        spa::spa_result!(sync r)?;
         Ok(())
     }
 
     /// Set the stream in error state
     ///
-    /// # Panics
-    /// Will panic if `error` contains a 0 byte.
-    ///
-    pub fn set_error(&mut self, res: i32, error: &str) {
-        let error = CString::new(error).expect("failed to convert error to CString");
+    /// # Errors
+    /// Returns [`Error::NulByte`] if `error` contains a 0 byte.
+    pub fn set_error(&mut self, res: i32, error: &str) -> Result<(), Error> {
+        let error = crate::utils::null_terminated(error)?;
         let error_cstr = error.as_c_str();
-        StreamRef::set_error_cstr(self, res, error_cstr)
+        StreamRef::set_error_cstr(self, res, error_cstr);
+        Ok(())
     }
 
     /// Set the stream in error state with CStr
@@ -276,10 +535,18 @@ impl StreamRef {
     pub fn flush(&self, drain: bool) -> Result<(), Error> {
         let r = unsafe { pw_sys::pw_stream_flush(self.as_raw_ptr(), drain) };
 
-        SpaResult::from_c(r).into_sync_result()?;
+        spa::spa_result!(sync r)?;
         Ok(())
     }
 
+    /// Set the value of a control of the stream.
+    ///
+    /// Control `id`s are the ids of the properties of the `Props` param of the stream,
+    /// such as `SPA_PROP_volume` or `SPA_PROP_channelVolumes`, and the available controls
+    /// are reported with the `control_info` callback once the stream is connected.
+    /// Controls holding a single value expect `values` to contain one element.
+    ///
+    /// Use [`control`](Self::control) to read back the current value of a control.
     pub fn set_control(&self, id: u32, values: &[f32]) -> Result<(), Error> {
         let r = unsafe {
             pw_sys::pw_stream_set_control(
@@ -289,13 +556,43 @@ impl StreamRef {
                 values.as_ptr() as *mut f32,
             )
         };
-        SpaResult::from_c(r).into_sync_result()?;
+        spa::spa_result!(sync r)?;
         Ok(())
     }
 
+    /// Set the values of several controls of the stream.
+    ///
+    /// This is equivalent to calling [`set_control`](Self::set_control) for each
+    /// `(id, values)` pair, stopping at the first error.
+    pub fn set_controls(&self, controls: &[(u32, &[f32])]) -> Result<(), Error> {
+        controls
+            .iter()
+            .try_for_each(|(id, values)| self.set_control(*id, values))
+    }
+
+    /// Get the current value of the control `id` of the stream, or `None` if the stream has no such control.
+    ///
+    /// While [`set_control`](Self::set_control) changes the value of a control from the application,
+    /// the values returned here also reflect the changes made by the graph, such as the
+    /// volume set by a mixer or automation from a session manager.
+    /// It can be called from the `process` callback, next to dequeuing buffers, to apply
+    /// the current control values to the processed data: the returned control borrows the
+    /// values stored by the stream, so this does not allocate.
+    pub fn control(&self, id: u32) -> Option<&StreamControlRef> {
+        unsafe {
+            let control = pw_sys::pw_stream_get_control(self.as_raw_ptr(), id);
+            control.as_ref().map(|control| {
+                &*(control as *const pw_sys::pw_stream_control).cast::<StreamControlRef>()
+            })
+        }
+    }
+
     // getters
 
-    /// Get the name of the stream.
+    /// Get the name of the stream, as provided when creating it.
+    ///
+    /// Unless the properties already set one, this is also the
+    /// [`MEDIA_NAME`](crate::keys::MEDIA_NAME) of the stream.
     pub fn name(&self) -> String {
         let name = unsafe {
             let name = pw_sys::pw_stream_get_name(self.as_raw_ptr());
@@ -315,6 +612,11 @@ impl StreamRef {
     }
 
     /// Get the properties of the stream.
+    ///
+    /// These are the properties provided when creating the stream, completed with the ones
+    /// PipeWire assigns, such as the [`NODE_ID`](crate::keys::NODE_ID) once the stream is connected.
+    /// The returned reference always reflects the current properties; use
+    /// [`props`](Self::props) to keep a snapshot of them.
     pub fn properties(&self) -> &PropertiesRef {
         unsafe {
             let props = pw_sys::pw_stream_get_properties(self.as_raw_ptr());
@@ -323,26 +625,260 @@ impl StreamRef {
         }
     }
 
-    /// Get the node ID of the stream.
-    pub fn node_id(&self) -> u32 {
-        unsafe { pw_sys::pw_stream_get_node_id(self.as_raw_ptr()) }
+    /// Get a snapshot of the current [`properties`](Self::properties) of the stream.
+    ///
+    /// Unlike `properties()`, the returned copy is not affected by later updates, and
+    /// can be kept after the stream is destroyed, for example to log it.
+    pub fn props(&self) -> Properties {
+        self.properties().to_owned()
     }
 
+    /// Get the id of the node global created for the stream.
+    ///
+    /// The id is assigned once the stream is connected and its node has been exported to the server,
+    /// which is the case when the stream reaches the [`Paused`](StreamState::Paused) state.
+    /// It can then be used to find the node in the registry, link it or set metadata on it.
+    ///
+    /// Returns `None` before the id is assigned.
+    pub fn node_id(&self) -> Option<u32> {
+        let id = unsafe { pw_sys::pw_stream_get_node_id(self.as_raw_ptr()) };
+        (id != crate::constants::ID_ANY).then_some(id)
+    }
+
+    /// Check if the stream is driving the graph.
+    ///
+    /// The stream needs to have been connected with [`StreamFlags::DRIVER`] and be
+    /// in a streaming state for this to be `true`.
     #[cfg(feature = "v0_3_34")]
     pub fn is_driving(&self) -> bool {
         unsafe { pw_sys::pw_stream_is_driving(self.as_raw_ptr()) }
     }
 
+    /// Trigger a push/pull on the stream.
+    ///
+    /// When the stream is driving the graph (see [`is_driving()`](Self::is_driving)), this
+    /// starts a new graph cycle, calling the `process` callback of the stream and of all
+    /// nodes it drives.
+    /// When the stream is not driving and was connected with `StreamFlags::TRIGGER`, this
+    /// requests a new cycle from the driver, so the `process` callback is called as soon
+    /// as data can be produced. This is what pull-model producers should use to generate
+    /// data on demand.
+    ///
+    /// # Errors
+    /// Returns an error if the stream is not in a state where it can be triggered,
+    /// for example when it is not connected, or neither driving nor using `StreamFlags::TRIGGER`.
     #[cfg(feature = "v0_3_34")]
     pub fn trigger_process(&self) -> Result<(), Error> {
         let r = unsafe { pw_sys::pw_stream_trigger_process(self.as_raw_ptr()) };
 
-        SpaResult::from_c(r).into_result()?;
+        spa::spa_result!(r)?;
         Ok(())
     }
 
+    /// Query the timing information of the stream.
+    ///
+    /// This is typically called from the `process` callback to get the latency and
+    /// position of the stream, which can then be used for A/V synchronisation.
+    /// See [`StreamTime`] for which fields are meaningful.
+    pub fn time(&self) -> Result<StreamTime, Error> {
+        let mut time: pw_sys::pw_time = unsafe { mem::zeroed() };
+
+        #[cfg(feature = "v0_3_53")]
+        let r = unsafe {
+            pw_sys::pw_stream_get_time_n(
+                self.as_raw_ptr(),
+                &mut time,
+                mem::size_of::<pw_sys::pw_time>(),
+            )
+        };
+        #[cfg(not(feature = "v0_3_53"))]
+        let r = unsafe { pw_sys::pw_stream_get_time(self.as_raw_ptr(), &mut time) };
+
+        spa::spa_result!(sync r)?;
+        Ok(StreamTime::from_raw(&time))
+    }
+
     // TODO: pw_stream_get_core()
-    // TODO: pw_stream_get_time()
+}
+
+/// A control of a stream, borrowed from the stream by [`StreamRef::control`].
+///
+/// Use [`to_owned`](Self::to_owned) to get a [`StreamControl`] copy of the control.
+#[repr(transparent)]
+pub struct StreamControlRef(pw_sys::pw_stream_control);
+
+impl StreamControlRef {
+    pub fn as_raw(&self) -> &pw_sys::pw_stream_control {
+        &self.0
+    }
+
+    /// The name of the control, or an empty string if it has none or it is not valid UTF-8.
+    pub fn name(&self) -> &str {
+        if self.0.name.is_null() {
+            ""
+        } else {
+            unsafe { CStr::from_ptr(self.0.name) }
+                .to_str()
+                .unwrap_or_default()
+        }
+    }
+
+    /// The flags of the control.
+    pub fn flags(&self) -> u32 {
+        self.0.flags
+    }
+
+    /// The default value of the control.
+    pub fn default(&self) -> f32 {
+        self.0.def
+    }
+
+    /// The minimum value of the control.
+    pub fn min(&self) -> f32 {
+        self.0.min
+    }
+
+    /// The maximum value of the control.
+    pub fn max(&self) -> f32 {
+        self.0.max
+    }
+
+    /// The current values of the control, with one value per channel for controls such as
+    /// `SPA_PROP_channelVolumes`.
+    pub fn values(&self) -> &[f32] {
+        if self.0.values.is_null() {
+            &[]
+        } else {
+            unsafe { std::slice::from_raw_parts(self.0.values, self.0.n_values as usize) }
+        }
+    }
+
+    /// The maximum number of values of the control.
+    pub fn max_values(&self) -> u32 {
+        self.0.max_values
+    }
+
+    /// Get a [`StreamControl`] copy of the control.
+    pub fn to_owned(&self) -> StreamControl {
+        unsafe { StreamControl::from_raw(&self.0) }
+    }
+}
+
+impl std::fmt::Debug for StreamControlRef {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("StreamControlRef")
+            .field("name", &self.name())
+            .field("flags", &self.flags())
+            .field("default", &self.default())
+            .field("min", &self.min())
+            .field("max", &self.max())
+            .field("values", &self.values())
+            .field("max_values", &self.max_values())
+            .finish()
+    }
+}
+
+/// A control of a stream, owning a copy of its name and values, see [`StreamControlRef::to_owned`].
+#[derive(Debug, Clone, PartialEq)]
+pub struct StreamControl {
+    /// The name of the control.
+    pub name: String,
+    /// The flags of the control.
+    pub flags: u32,
+    /// The default value of the control.
+    pub default: f32,
+    /// The minimum value of the control.
+    pub min: f32,
+    /// The maximum value of the control.
+    pub max: f32,
+    /// The current values of the control, with one value per channel for controls such as
+    /// `SPA_PROP_channelVolumes`.
+    pub values: Vec<f32>,
+    /// The maximum number of values of the control.
+    pub max_values: u32,
+}
+
+impl StreamControl {
+    /// Create a [`StreamControl`] from a raw `pw_stream_control`, such as the one passed
+    /// to the `control_info` callback.
+    ///
+    /// # Safety
+    /// `raw` must be a valid `pw_stream_control`, with `values` pointing to
+    /// `n_values` floats, and a valid or `NULL` `name`.
+    pub unsafe fn from_raw(raw: &pw_sys::pw_stream_control) -> Self {
+        let name = if raw.name.is_null() {
+            String::new()
+        } else {
+            CStr::from_ptr(raw.name).to_string_lossy().into_owned()
+        };
+        let values = if raw.values.is_null() {
+            Vec::new()
+        } else {
+            std::slice::from_raw_parts(raw.values, raw.n_values as usize).to_vec()
+        };
+
+        Self {
+            name,
+            flags: raw.flags,
+            default: raw.def,
+            min: raw.min,
+            max: raw.max,
+            values,
+            max_values: raw.max_values,
+        }
+    }
+}
+
+/// Timing information of a stream, as returned by [`StreamRef::time`].
+///
+/// `now`, `rate` and `ticks` describe the graph clock and are valid for both directions.
+///
+/// `delay` is the latency between the stream and the device: for [`Output`](spa::utils::Direction::Output)
+/// (playback) streams it is the time until a sample queued now is played, for
+/// [`Input`](spa::utils::Direction::Input) (capture) streams it is the time since the
+/// sample dequeued now was captured.
+///
+/// `queued` and `queued_buffers` only account for data queued by the application, so they are
+/// mostly relevant for playback streams.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct StreamTime {
+    /// The time in nanoseconds, in `CLOCK_MONOTONIC`, when this info was taken.
+    pub now: i64,
+    /// The rate of `ticks` and `delay`.
+    pub rate: spa::utils::Fraction,
+    /// The ticks at `now`. This is the current time of the graph clock, in `rate` units.
+    pub ticks: u64,
+    /// The delay to the device, in `rate` units.
+    pub delay: i64,
+    /// The data queued in the stream, as the sum of the sizes of the queued buffers.
+    pub queued: u64,
+    /// The data buffered in the stream converter, in samples.
+    #[cfg(feature = "v0_3_53")]
+    pub buffered: u64,
+    /// The number of buffers queued by the application.
+    #[cfg(feature = "v0_3_53")]
+    pub queued_buffers: u32,
+    /// The number of buffers that can be dequeued by the application.
+    #[cfg(feature = "v0_3_53")]
+    pub avail_buffers: u32,
+}
+
+impl StreamTime {
+    fn from_raw(raw: &pw_sys::pw_time) -> Self {
+        Self {
+            now: raw.now,
+            rate: raw.rate.into(),
+            ticks: raw.ticks,
+            delay: raw.delay,
+            queued: raw.queued,
+            #[cfg(feature = "v0_3_53")]
+            buffered: raw.buffered,
+            #[cfg(feature = "v0_3_53")]
+            queued_buffers: raw.queued_buffers,
+            #[cfg(feature = "v0_3_53")]
+            avail_buffers: raw.avail_buffers,
+        }
+    }
 }
 
 type ParamChangedCB<D> = dyn FnMut(&StreamRef, &mut D, u32, Option<&spa::pod::Pod>);
@@ -410,7 +946,8 @@ impl<D> ListenerLocalCallbacks<D> {
             if let Some(state) = (data as *mut ListenerLocalCallbacks<D>).as_mut() {
                 if let Some(cb) = &mut state.state_changed {
                     let stream = unwrap_stream_ptr(state.stream);
-                    let old = StreamState::from_raw(old, error);
+                    // The error message only describes the new state.
+                    let old = StreamState::from_raw(old, ptr::null());
                     let new = StreamState::from_raw(new, error);
                     cb(stream, &mut state.user_data, old, new)
                 };
@@ -580,6 +1117,12 @@ pub struct ListenerLocalBuilder<'a, D> {
 
 impl<'a, D> ListenerLocalBuilder<'a, D> {
     /// Set the callback for the `state_changed` event.
+    ///
+    /// Callback parameters: stream, user data, old state, new state.
+    ///
+    /// When the stream enters the error state, for example because the format negotiation failed,
+    /// the new state is a [`StreamState::Error`] containing the error message.
+    /// The error message of the old state is not known, so it is always empty.
     pub fn state_changed<F>(mut self, callback: F) -> Self
     where
         F: FnMut(&StreamRef, &mut D, StreamState, StreamState) + 'static,
@@ -589,6 +1132,8 @@ impl<'a, D> ListenerLocalBuilder<'a, D> {
     }
 
     /// Set the callback for the `control_info` event.
+    ///
+    /// The control can be read using [`StreamControl::from_raw`].
     pub fn control_info<F>(mut self, callback: F) -> Self
     where
         F: FnMut(&StreamRef, &mut D, u32, *const pw_sys::pw_stream_control) + 'static,
@@ -607,6 +1152,12 @@ impl<'a, D> ListenerLocalBuilder<'a, D> {
     }
 
     /// Set the callback for the `param_changed` event.
+    ///
+    /// When format negotiation fails, [`to_debug_string`](spa::pod::to_debug_string) can be used to
+    /// print the params received here and the ones passed to [`connect`](StreamRef::connect).
+    ///
+    /// The latency of the graph is received with the [`Latency`](spa::param::ParamType::Latency) id,
+    /// and can be decoded with [`Latency::parse`](spa::param::latency::Latency::parse).
     pub fn param_changed<F>(mut self, callback: F) -> Self
     where
         F: FnMut(&StreamRef, &mut D, u32, Option<&spa::pod::Pod>) + 'static,
@@ -673,6 +1224,7 @@ impl<'a, D> ListenerLocalBuilder<'a, D> {
             listener,
             _events: events,
             _data: data,
+            _tracked: TrackedListener::new("Stream", self.stream.as_raw_ptr().cast()),
         })
     }
 }
@@ -682,6 +1234,7 @@ pub struct StreamListener<D> {
     // Need to stay allocated while the listener is registered
     _events: Pin<Box<pw_sys::pw_stream_events>>,
     _data: Box<ListenerLocalCallbacks<D>>,
+    _tracked: TrackedListener,
 }
 
 impl<D> StreamListener<D> {
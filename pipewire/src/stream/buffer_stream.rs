@@ -0,0 +1,160 @@
+// Copyright The pipewire-rs Contributors.
+// SPDX-License-Identifier: MIT
+
+//! Receiving the buffers of a capture stream as a [`futures_core::Stream`].
+
+use std::{
+    collections::VecDeque,
+    pin::Pin,
+    sync::{Arc, Mutex},
+    task::{Context, Poll, Waker},
+};
+
+use super::{StreamListener, StreamRef};
+use crate::Error;
+
+/// The content of a buffer dequeued from a stream, copied so it can be sent to another thread.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct CapturedBuffer {
+    /// The valid bytes of each of the datas of the buffer, as described by their chunk.
+    pub datas: Vec<Vec<u8>>,
+}
+
+#[derive(Default)]
+struct Shared {
+    buffers: VecDeque<CapturedBuffer>,
+    waker: Option<Waker>,
+    closed: bool,
+    dropped: u64,
+}
+
+/// The user data of the listener filling a [`BufferStream`].
+///
+/// The [`BufferStream`] ends once the listener holding it is dropped.
+pub struct BufferSender {
+    shared: Arc<Mutex<Shared>>,
+    capacity: usize,
+}
+
+impl BufferSender {
+    fn send(&self, buffer: CapturedBuffer) {
+        let mut shared = self.shared.lock().unwrap();
+        if shared.buffers.len() >= self.capacity {
+            shared.buffers.pop_front();
+            shared.dropped += 1;
+        }
+        shared.buffers.push_back(buffer);
+
+        if let Some(waker) = shared.waker.take() {
+            waker.wake();
+        }
+    }
+}
+
+impl Drop for BufferSender {
+    fn drop(&mut self) {
+        let mut shared = self.shared.lock().unwrap();
+        shared.closed = true;
+
+        if let Some(waker) = shared.waker.take() {
+            waker.wake();
+        }
+    }
+}
+
+/// The buffers of a capture stream, as an asynchronous [`futures_core::Stream`].
+///
+/// Each buffer is dequeued in the `process` callback of the stream, copied,
+/// and queued back to the stream right away.
+///
+/// # Backpressure
+/// At most `capacity` buffers are kept waiting for the consumer.
+/// When the consumer is slower than the stream, the oldest buffers are dropped to make room
+/// for the new ones, so the consumer always gets the most recent data.
+/// The number of dropped buffers can be checked using [`dropped`](Self::dropped).
+///
+/// ```no_run
+/// # async fn capture(stream: &pipewire::stream::Stream) -> Result<(), pipewire::Error> {
+/// use futures::StreamExt;
+/// use pipewire::stream::buffer_stream::BufferStream;
+///
+/// let (mut buffers, _listener) = BufferStream::new(stream, 8)?;
+/// while let Some(buffer) = buffers.next().await {
+///     println!("Got {} bytes", buffer.datas[0].len());
+/// }
+/// # Ok(())
+/// # }
+/// ```
+pub struct BufferStream {
+    shared: Arc<Mutex<Shared>>,
+}
+
+impl BufferStream {
+    /// Register a listener on `stream` sending its buffers to the returned [`BufferStream`],
+    /// keeping at most `capacity` buffers waiting for the consumer.
+    ///
+    /// The loop of the stream needs to be running, usually a [`ThreadLoop`](crate::thread_loop::ThreadLoop),
+    /// for buffers to be received.
+    /// The stream ends when the returned listener is dropped.
+    ///
+    /// # Panics
+    /// Panics if `capacity` is zero.
+    pub fn new(
+        stream: &StreamRef,
+        capacity: usize,
+    ) -> Result<(Self, StreamListener<BufferSender>), Error> {
+        assert!(capacity > 0, "capacity must not be zero");
+
+        let shared: Arc<Mutex<Shared>> = Arc::default();
+        let sender = BufferSender {
+            shared: shared.clone(),
+            capacity,
+        };
+
+        let listener = stream
+            .add_local_listener_with_user_data(sender)
+            .process(|stream, sender| {
+                if let Some(mut buffer) = stream.dequeue_buffer() {
+                    let datas = buffer
+                        .datas_mut()
+                        .iter_mut()
+                        .map(|data| {
+                            let offset = data.chunk().offset() as usize;
+                            let size = data.chunk().size() as usize;
+                            data.data()
+                                .and_then(|bytes| bytes.get(offset..offset + size))
+                                .map(<[u8]>::to_vec)
+                                .unwrap_or_default()
+                        })
+                        .collect();
+
+                    sender.send(CapturedBuffer { datas });
+                }
+            })
+            .register()?;
+
+        Ok((Self { shared }, listener))
+    }
+
+    /// The number of buffers dropped so far because the consumer was too slow.
+    pub fn dropped(&self) -> u64 {
+        self.shared.lock().unwrap().dropped
+    }
+}
+
+impl futures_core::Stream for BufferStream {
+    type Item = CapturedBuffer;
+
+    fn poll_next(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Option<Self::Item>> {
+        let mut shared = self.shared.lock().unwrap();
+
+        if let Some(buffer) = shared.buffers.pop_front() {
+            Poll::Ready(Some(buffer))
+        } else if shared.closed {
+            Poll::Ready(None)
+        } else {
+            shared.waker = Some(cx.waker().clone());
+            Poll::Pending
+        }
+    }
+}
@@ -0,0 +1,289 @@
+// Copyright The pipewire-rs Contributors.
+// SPDX-License-Identifier: MIT
+
+//! Statistics about the buffers of a stream, see [`Stream::stats`](super::Stream::stats).
+
+use std::{
+    cell::Cell,
+    mem, os,
+    pin::Pin,
+    sync::{
+        atomic::{AtomicU64, AtomicUsize, Ordering},
+        Arc, PoisonError, RwLock,
+    },
+};
+
+use super::StreamRef;
+
+/// Statistics about the buffers of a stream, as returned by [`Stream::stats`](super::Stream::stats).
+///
+/// Growing [`underruns`](Self::underruns), [`overruns`](Self::overruns) or [`dropped`](Self::dropped)
+/// counters mean that the application does not keep up with the graph.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub struct StreamStats {
+    /// The number of buffers added to the stream pool, as reported by the `add_buffer` event.
+    pub buffers_added: u64,
+    /// The number of buffers removed from the stream pool, as reported by the `remove_buffer` event.
+    pub buffers_removed: u64,
+    /// The number of `process` events, each of them signaling that buffers can be dequeued.
+    pub process_cycles: u64,
+    /// The number of buffers dequeued by the application.
+    pub dequeued: u64,
+    /// The number of buffers queued back by the application, including the buffers
+    /// returned when a [`Buffer`](crate::buffer::Buffer) is dropped.
+    pub queued: u64,
+    /// The number of process cycles in which the application did not queue any buffer.
+    ///
+    /// For an output stream, the graph had no new data to play in these cycles.
+    pub underruns: u64,
+    /// The number of process cycles in which the application did not dequeue any buffer.
+    ///
+    /// For an input stream, the data of these cycles was left waiting in the stream.
+    pub overruns: u64,
+    /// The number of times the application tried to dequeue a buffer while none was available,
+    /// so the data it had to write, or expected to read, was dropped.
+    pub dropped: u64,
+}
+
+impl StreamStats {
+    /// The number of buffers currently in the stream pool.
+    pub fn buffers(&self) -> u64 {
+        self.buffers_added.saturating_sub(self.buffers_removed)
+    }
+}
+
+#[derive(Debug, Default)]
+struct Counters {
+    buffers_added: AtomicU64,
+    buffers_removed: AtomicU64,
+    process_cycles: AtomicU64,
+    dequeued: AtomicU64,
+    queued: AtomicU64,
+    underruns: AtomicU64,
+    overruns: AtomicU64,
+    dropped: AtomicU64,
+}
+
+impl Counters {
+    fn snapshot(&self) -> StreamStats {
+        StreamStats {
+            buffers_added: self.buffers_added.load(Ordering::Relaxed),
+            buffers_removed: self.buffers_removed.load(Ordering::Relaxed),
+            process_cycles: self.process_cycles.load(Ordering::Relaxed),
+            dequeued: self.dequeued.load(Ordering::Relaxed),
+            queued: self.queued.load(Ordering::Relaxed),
+            underruns: self.underruns.load(Ordering::Relaxed),
+            overruns: self.overruns.load(Ordering::Relaxed),
+            dropped: self.dropped.load(Ordering::Relaxed),
+        }
+    }
+}
+
+/// The counters of the streams with statistics enabled, by stream pointer.
+///
+/// Buffers are dequeued and queued through a [`StreamRef`], which has no state of its own,
+/// so the counters of its stream are looked up here.
+static STREAMS: RwLock<Vec<(usize, Arc<Counters>)>> = RwLock::new(Vec::new());
+/// The number of streams in `STREAMS`, to skip the lookup when no stream has statistics enabled.
+static ENABLED: AtomicUsize = AtomicUsize::new(0);
+
+fn enable(stream: *mut pw_sys::pw_stream) -> Arc<Counters> {
+    let counters = Arc::<Counters>::default();
+    STREAMS
+        .write()
+        .unwrap_or_else(PoisonError::into_inner)
+        .push((stream as usize, counters.clone()));
+    ENABLED.fetch_add(1, Ordering::Relaxed);
+    counters
+}
+
+fn disable(stream: *mut pw_sys::pw_stream) {
+    STREAMS
+        .write()
+        .unwrap_or_else(PoisonError::into_inner)
+        .retain(|(ptr, _)| *ptr != stream as usize);
+    ENABLED.fetch_sub(1, Ordering::Relaxed);
+}
+
+fn with_counters(stream: *mut pw_sys::pw_stream, f: impl FnOnce(&Counters)) {
+    if ENABLED.load(Ordering::Relaxed) == 0 {
+        return;
+    }
+
+    // This may run on the realtime thread, which must not block: the lock is only held for
+    // writing while the statistics of a stream are enabled or disabled, so an event happening
+    // meanwhile is not counted instead of waiting for it.
+    if let Ok(streams) = STREAMS.try_read() {
+        if let Some((_, counters)) = streams.iter().find(|(ptr, _)| *ptr == stream as usize) {
+            f(counters);
+        }
+    }
+}
+
+/// Count the result of a `pw_stream_dequeue_buffer` call on `stream`.
+pub(super) fn dequeued(stream: *mut pw_sys::pw_stream, buffer: *mut pw_sys::pw_buffer) {
+    with_counters(stream, |counters| {
+        if buffer.is_null() {
+            counters.dropped.fetch_add(1, Ordering::Relaxed);
+        } else {
+            counters.dequeued.fetch_add(1, Ordering::Relaxed);
+        }
+    });
+}
+
+/// Count a `pw_stream_queue_buffer` call on `stream`.
+pub(super) fn queued(stream: *mut pw_sys::pw_stream) {
+    with_counters(stream, |counters| {
+        counters.queued.fetch_add(1, Ordering::Relaxed);
+    });
+}
+
+struct State {
+    counters: Arc<Counters>,
+    /// The `dequeued` and `queued` counters at the previous process event,
+    /// only accessed from the `process` event.
+    last: Cell<Option<(u64, u64)>>,
+}
+
+unsafe extern "C" fn on_add_buffer(data: *mut os::raw::c_void, _buffer: *mut pw_sys::pw_buffer) {
+    if let Some(state) = (data as *const State).as_ref() {
+        state.counters.buffers_added.fetch_add(1, Ordering::Relaxed);
+    }
+}
+
+unsafe extern "C" fn on_remove_buffer(data: *mut os::raw::c_void, _buffer: *mut pw_sys::pw_buffer) {
+    if let Some(state) = (data as *const State).as_ref() {
+        state
+            .counters
+            .buffers_removed
+            .fetch_add(1, Ordering::Relaxed);
+    }
+}
+
+unsafe extern "C" fn on_process(data: *mut os::raw::c_void) {
+    if let Some(state) = (data as *const State).as_ref() {
+        let counters = &state.counters;
+        counters.process_cycles.fetch_add(1, Ordering::Relaxed);
+
+        // The listeners of the stream are called in the order they were registered, so this may run
+        // before or after the `process` callback of the application: compare with the previous event,
+        // which is one cycle of the application either way.
+        let current = (
+            counters.dequeued.load(Ordering::Relaxed),
+            counters.queued.load(Ordering::Relaxed),
+        );
+        if let Some((dequeued, queued)) = state.last.replace(Some(current)) {
+            if current.0 == dequeued {
+                counters.overruns.fetch_add(1, Ordering::Relaxed);
+            }
+            if current.1 == queued {
+                counters.underruns.fetch_add(1, Ordering::Relaxed);
+            }
+        }
+    }
+}
+
+/// The listener updating the statistics of a stream, registered by [`Stream::stats`](super::Stream::stats).
+pub(super) struct StatsListener {
+    stream: *mut pw_sys::pw_stream,
+    listener: Box<spa_sys::spa_hook>,
+    // Need to stay allocated while the listener is registered
+    _events: Pin<Box<pw_sys::pw_stream_events>>,
+    state: Box<State>,
+}
+
+impl StatsListener {
+    pub(super) fn register(stream: &StreamRef) -> Self {
+        let state = Box::new(State {
+            counters: enable(stream.as_raw_ptr()),
+            last: Cell::new(None),
+        });
+
+        unsafe {
+            let mut events: Pin<Box<pw_sys::pw_stream_events>> = Box::pin(mem::zeroed());
+            events.version = pw_sys::PW_VERSION_STREAM_EVENTS;
+            events.add_buffer = Some(on_add_buffer);
+            events.remove_buffer = Some(on_remove_buffer);
+            events.process = Some(on_process);
+
+            let mut listener: Box<spa_sys::spa_hook> = Box::new(mem::zeroed());
+            pw_sys::pw_stream_add_listener(
+                stream.as_raw_ptr(),
+                &mut *listener,
+                events.as_ref().get_ref(),
+                &*state as *const State as *mut _,
+            );
+
+            Self {
+                stream: stream.as_raw_ptr(),
+                listener,
+                _events: events,
+                state,
+            }
+        }
+    }
+
+    pub(super) fn stats(&self) -> StreamStats {
+        self.state.counters.snapshot()
+    }
+}
+
+impl Drop for StatsListener {
+    fn drop(&mut self) {
+        spa::utils::hook::remove(*self.listener);
+        disable(self.stream);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn counters() {
+        // Only used as a key, the stream is never dereferenced.
+        let mut key = 0u8;
+        let stream: *mut pw_sys::pw_stream = (&mut key as *mut u8).cast();
+        let state = State {
+            counters: enable(stream),
+            last: Cell::new(None),
+        };
+        let data = &state as *const State as *mut os::raw::c_void;
+        let buffer = std::ptr::NonNull::<pw_sys::pw_buffer>::dangling().as_ptr();
+
+        unsafe {
+            on_add_buffer(data, buffer);
+            on_add_buffer(data, buffer);
+            on_process(data);
+            // A cycle in which a buffer is filled and queued.
+            dequeued(stream, buffer);
+            queued(stream);
+            on_process(data);
+            // A cycle in which no buffer is available.
+            dequeued(stream, std::ptr::null_mut());
+            on_process(data);
+            on_remove_buffer(data, buffer);
+        }
+
+        let stats = state.counters.snapshot();
+        disable(stream);
+        assert_eq!(
+            stats,
+            StreamStats {
+                buffers_added: 2,
+                buffers_removed: 1,
+                process_cycles: 3,
+                dequeued: 1,
+                queued: 1,
+                underruns: 1,
+                overruns: 1,
+                dropped: 1,
+            }
+        );
+        assert_eq!(stats.buffers(), 1);
+
+        // Streams without statistics are not counted.
+        queued(stream);
+        assert_eq!(state.counters.queued.load(Ordering::Relaxed), 1);
+    }
+}
@@ -68,6 +68,11 @@ impl ThreadLoop {
         self.inner.ptr.as_ptr()
     }
 
+    /// Get the [`LoopRef`] of this loop.
+    ///
+    /// Sources such as timers, idles, events, signals or IOs are registered on the returned loop,
+    /// which is shared by all loop types.
+    /// The returned [`LoopRef`] borrows from `self` and is valid for as long as it is alive.
     pub fn loop_(&self) -> &LoopRef {
         unsafe {
             let thread_loop = pw_sys::pw_thread_loop_get_loop(self.as_raw_ptr());
@@ -1,8 +1,8 @@
-use std::fmt;
+use std::{ffi::CStr, fmt};
 
 // Macro generating the ObjectType enum
 macro_rules! object_type {
-    ($( ($x:ident, $version:ident) ),*) => {
+    ($( ($x:ident, $type_:ident, $version:ident) ),*) => {
         #[derive(Debug, Eq, PartialEq, Clone)]
         pub enum ObjectType {
             $($x,)*
@@ -10,6 +10,18 @@ macro_rules! object_type {
         }
 
         impl ObjectType {
+            /// Iterate over all the object types known by these bindings.
+            ///
+            /// This contains every variant except [`ObjectType::Other`].
+            pub fn all_known() -> impl Iterator<Item = ObjectType> {
+                [$(ObjectType::$x,)*].into_iter()
+            }
+
+            /// Returns `false` if this is an [`ObjectType::Other`], `true` otherwise.
+            pub fn is_known(&self) -> bool {
+                !matches!(self, ObjectType::Other(_))
+            }
+
             pub(crate) fn from_str(s: &str) -> ObjectType {
                 match s {
                     $(
@@ -28,6 +40,22 @@ macro_rules! object_type {
                 }
             }
 
+            /// Get the `PW_TYPE_INTERFACE_*` constant of the type, as passed to the C API.
+            ///
+            /// This is the same string as [`to_str`](Self::to_str), taken from the C headers.
+            /// Returns `None` for an [`ObjectType::Other`].
+            pub fn as_type_info(&self) -> Option<&'static CStr> {
+                match self {
+                    $(
+                        ObjectType::$x => Some(
+                            CStr::from_bytes_with_nul(pw_sys::$type_)
+                                .expect("type constant is not null terminated"),
+                        ),
+                    )*
+                    ObjectType::Other(_) => None,
+                }
+            }
+
             pub(crate) fn client_version(&self) -> u32 {
                 match self {
                     $(
@@ -47,23 +75,59 @@ macro_rules! object_type {
 }
 
 object_type![
-    // Id, API version
-    (Client, PW_VERSION_CLIENT),
-    (ClientEndpoint, PW_VERSION_CLIENT_ENDPOINT),
-    (ClientNode, PW_VERSION_CLIENT_NODE),
-    (ClientSession, PW_VERSION_CLIENT_SESSION),
-    (Core, PW_VERSION_CORE),
-    (Device, PW_VERSION_DEVICE),
-    (Endpoint, PW_VERSION_ENDPOINT),
-    (EndpointLink, PW_VERSION_ENDPOINT_LINK),
-    (EndpointStream, PW_VERSION_ENDPOINT_STREAM),
-    (Factory, PW_VERSION_FACTORY),
-    (Link, PW_VERSION_LINK),
-    (Metadata, PW_VERSION_METADATA),
-    (Module, PW_VERSION_MODULE),
-    (Node, PW_VERSION_NODE),
-    (Port, PW_VERSION_PORT),
-    (Profiler, PW_VERSION_PROFILER),
-    (Registry, PW_VERSION_REGISTRY),
-    (Session, PW_VERSION_SESSION)
+    // Id, type constant, API version
+    (Client, PW_TYPE_INTERFACE_Client, PW_VERSION_CLIENT),
+    (
+        ClientEndpoint,
+        PW_TYPE_INTERFACE_ClientEndpoint,
+        PW_VERSION_CLIENT_ENDPOINT
+    ),
+    (
+        ClientNode,
+        PW_TYPE_INTERFACE_ClientNode,
+        PW_VERSION_CLIENT_NODE
+    ),
+    (
+        ClientSession,
+        PW_TYPE_INTERFACE_ClientSession,
+        PW_VERSION_CLIENT_SESSION
+    ),
+    (Core, PW_TYPE_INTERFACE_Core, PW_VERSION_CORE),
+    (Device, PW_TYPE_INTERFACE_Device, PW_VERSION_DEVICE),
+    (Endpoint, PW_TYPE_INTERFACE_Endpoint, PW_VERSION_ENDPOINT),
+    (
+        EndpointLink,
+        PW_TYPE_INTERFACE_EndpointLink,
+        PW_VERSION_ENDPOINT_LINK
+    ),
+    (
+        EndpointStream,
+        PW_TYPE_INTERFACE_EndpointStream,
+        PW_VERSION_ENDPOINT_STREAM
+    ),
+    (Factory, PW_TYPE_INTERFACE_Factory, PW_VERSION_FACTORY),
+    (Link, PW_TYPE_INTERFACE_Link, PW_VERSION_LINK),
+    (Metadata, PW_TYPE_INTERFACE_Metadata, PW_VERSION_METADATA),
+    (Module, PW_TYPE_INTERFACE_Module, PW_VERSION_MODULE),
+    (Node, PW_TYPE_INTERFACE_Node, PW_VERSION_NODE),
+    (Port, PW_TYPE_INTERFACE_Port, PW_VERSION_PORT),
+    (Profiler, PW_TYPE_INTERFACE_Profiler, PW_VERSION_PROFILER),
+    (Registry, PW_TYPE_INTERFACE_Registry, PW_VERSION_REGISTRY),
+    (Session, PW_TYPE_INTERFACE_Session, PW_VERSION_SESSION)
 ];
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn type_info_matches_str() {
+        for type_ in ObjectType::all_known() {
+            let info = type_.as_type_info().unwrap();
+            assert_eq!(info.to_str().unwrap(), type_.to_str());
+            assert_eq!(ObjectType::from_str(type_.to_str()), type_);
+        }
+
+        assert_eq!(ObjectType::Other("Foo".to_string()).as_type_info(), None);
+    }
+}
@@ -1,8 +1,37 @@
 // Copyright The pipewire-rs Contributors.
 // SPDX-License-Identifier: MIT
 
-use std::thread;
+use std::{ffi::CString, thread};
+
+use crate::Error;
 
 pub fn assert_main_thread() {
     assert_eq!(thread::current().name(), Some("main"));
 }
+
+/// Convert `s` into a [`CString`] to pass it as a C string argument.
+///
+/// Returns [`Error::NulByte`] if `s` contains an interior nul byte.
+pub(crate) fn null_terminated(s: &str) -> Result<CString, Error> {
+    Ok(CString::new(s)?)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn null_terminated_valid() {
+        let s = null_terminated("pipewire").unwrap();
+        assert_eq!(s.as_bytes_with_nul(), b"pipewire\0");
+    }
+
+    #[test]
+    fn null_terminated_interior_nul() {
+        assert!(matches!(
+            null_terminated("pipe\0wire"),
+            Err(Error::NulByte(_))
+        ));
+        assert!(matches!(null_terminated("\0"), Err(Error::NulByte(_))));
+    }
+}
@@ -0,0 +1,126 @@
+// Copyright The pipewire-rs Contributors.
+// SPDX-License-Identifier: MIT
+
+//! Helpers shared by the integration tests and the benchmarks.
+
+#![allow(dead_code)]
+
+use std::{
+    cell::Cell,
+    ffi::{c_int, c_void, CStr},
+    mem, ptr,
+};
+
+use pipewire as pw;
+use pw::{
+    permissions::PermissionFlags, properties::Properties, registry::Registry, spa::sys as spa_sys,
+    sys as pw_sys,
+};
+
+/// A registry proxy which is not connected to any server.
+///
+/// It records the listeners added to it, so that registry events can be emitted to them
+/// directly, running the same event trampolines as a real registry without a daemon.
+/// Only the `add_listener` method is implemented: listeners using
+/// [`initial_sync_done`](pw::registry::ListenerLocalBuilder::initial_sync_done) can not be registered.
+pub struct FakeRegistry {
+    inner: Box<Inner>,
+    registry: mem::ManuallyDrop<Registry>,
+}
+
+// `iface` must be the first field, so that a pointer to `Inner` is a valid `spa_interface` pointer.
+#[repr(C)]
+struct Inner {
+    iface: spa_sys::spa_interface,
+    methods: pw_sys::pw_registry_methods,
+    listener: Cell<Option<(*const pw_sys::pw_registry_events, *mut c_void)>>,
+}
+
+type AddListener = Option<
+    unsafe extern "C" fn(
+        *mut c_void,
+        *mut spa_sys::spa_hook,
+        *const pw_sys::pw_registry_events,
+        *mut c_void,
+    ) -> c_int,
+>;
+
+unsafe extern "C" fn add_listener(
+    object: *mut c_void,
+    listener: *mut spa_sys::spa_hook,
+    events: *const pw_sys::pw_registry_events,
+    data: *mut c_void,
+) -> c_int {
+    // Make the hook a list of its own, so that removing it when the listener is dropped is valid.
+    let link = ptr::addr_of_mut!((*listener).link);
+    (*link).next = link;
+    (*link).prev = link;
+
+    let inner = &*(object as *const Inner);
+    inner.listener.set(Some((events, data)));
+
+    0
+}
+
+impl FakeRegistry {
+    pub fn new() -> Self {
+        Self::with_add_listener(Some(add_listener))
+    }
+
+    /// Create a registry which does not implement any method, not even `add_listener`.
+    pub fn without_methods() -> Self {
+        Self::with_add_listener(None)
+    }
+
+    fn with_add_listener(add_listener: AddListener) -> Self {
+        let mut inner = Box::new(Inner {
+            iface: unsafe { mem::zeroed() },
+            methods: unsafe { mem::zeroed() },
+            listener: Cell::new(None),
+        });
+        inner.methods.version = pw_sys::PW_VERSION_REGISTRY_METHODS;
+        inner.methods.add_listener = add_listener;
+
+        let inner_ptr: *mut Inner = &mut *inner;
+        inner.iface.cb.funcs = ptr::addr_of!(inner.methods).cast();
+        inner.iface.cb.data = inner_ptr.cast();
+
+        // The registry must not be dropped, as destroying it would call into libpipewire.
+        let registry = unsafe {
+            Registry::from_raw(ptr::NonNull::new(inner_ptr.cast()).expect("null registry"))
+        };
+
+        Self {
+            inner,
+            registry: mem::ManuallyDrop::new(registry),
+        }
+    }
+
+    pub fn registry(&self) -> &Registry {
+        &self.registry
+    }
+
+    /// The events and data of the listener added last, which must still be registered.
+    fn listener(&self) -> (&pw_sys::pw_registry_events, *mut c_void) {
+        let (events, data) = self.inner.listener.get().expect("no listener added");
+        (unsafe { &*events }, data)
+    }
+
+    /// Emit the `global` event to the listener added last.
+    pub fn global(&self, id: u32, type_: &CStr, props: Option<&Properties>) {
+        let (events, data) = self.listener();
+        let props = props.map_or(ptr::null(), |props| &props.as_raw().dict as *const _);
+        let permissions = PermissionFlags::R | PermissionFlags::W | PermissionFlags::X;
+        if let Some(global) = events.global {
+            unsafe { global(data, id, permissions.bits(), type_.as_ptr(), 3, props) };
+        }
+    }
+
+    /// Emit the `global_remove` event to the listener added last.
+    pub fn global_remove(&self, id: u32) {
+        let (events, data) = self.listener();
+        if let Some(global_remove) = events.global_remove {
+            unsafe { global_remove(data, id) };
+        }
+    }
+}
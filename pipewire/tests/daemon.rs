@@ -0,0 +1,162 @@
+// Copyright The pipewire-rs Contributors.
+// SPDX-License-Identifier: MIT
+
+//! Integration tests requiring a running PipeWire daemon.
+//!
+//! These tests connect to an actual PipeWire instance, and are thus ignored by default.
+//! To run them, start a daemon (either the session one, or a headless `pipewire` test server
+//! with `PIPEWIRE_REMOTE` pointing to it) and run:
+//!
+//! ```sh
+//! cargo test -p pipewire --test daemon -- --ignored
+//! ```
+
+use std::{cell::RefCell, rc::Rc};
+
+use pipewire as pw;
+use pw::types::ObjectType;
+
+/// Process all pending events by waiting for a sync on the core.
+fn roundtrip(mainloop: &pw::main_loop::MainLoop, core: &pw::core::Core) {
+    let pending = core.sync(0).expect("sync failed");
+
+    let loop_clone = mainloop.clone();
+    let _listener = core
+        .add_listener_local()
+        .done(move |id, seq| {
+            if id == pw::core::PW_ID_CORE && seq == pending {
+                loop_clone.quit();
+            }
+        })
+        .register();
+
+    mainloop.run();
+}
+
+#[test]
+#[ignore = "requires a running PipeWire daemon"]
+fn registry_lists_core() {
+    let mainloop = pw::main_loop::MainLoop::new(None).expect("Failed to create main loop");
+    let context = pw::context::Context::new(&mainloop).expect("Failed to create context");
+    let core = context.connect(None).expect("Failed to connect to core");
+    let registry = core.get_registry().expect("Failed to get registry");
+
+    let globals = Rc::new(RefCell::new(Vec::new()));
+    let globals_clone = globals.clone();
+    let _listener = registry
+        .add_listener_local()
+        .global(move |global| {
+            globals_clone
+                .borrow_mut()
+                .push((global.id, global.type_.clone()));
+        })
+        .register();
+
+    roundtrip(&mainloop, &core);
+
+    let globals = globals.borrow();
+    assert!(globals
+        .iter()
+        .any(|(id, type_)| *id == pw::core::PW_ID_CORE && *type_ == ObjectType::Core));
+}
+
+#[test]
+#[ignore = "requires a running PipeWire daemon"]
+fn registry_outlives_core_and_context() {
+    let mainloop = pw::main_loop::MainLoop::new(None).expect("Failed to create main loop");
+    let context = pw::context::Context::new(&mainloop).expect("Failed to create context");
+    let core = context.connect(None).expect("Failed to connect to core");
+    let registry = core.get_registry().expect("Failed to get registry");
+    // The registry keeps the context, and so the connection, alive.
+    drop(core);
+    drop(context);
+
+    let found = Rc::new(RefCell::new(false));
+    let found_clone = found.clone();
+    let _listener = registry
+        .add_listener_local()
+        .global(move |global| {
+            if global.id == pw::core::PW_ID_CORE {
+                *found_clone.borrow_mut() = true;
+            }
+        })
+        .register();
+
+    let deadline = std::time::Instant::now() + std::time::Duration::from_secs(5);
+    while !*found.borrow() && std::time::Instant::now() < deadline {
+        mainloop
+            .loop_()
+            .iterate(std::time::Duration::from_millis(100));
+    }
+
+    assert!(*found.borrow());
+}
+
+#[test]
+#[ignore = "requires a running PipeWire daemon"]
+fn bind_each_known_type() {
+    use pw::proxy::ProxyT;
+
+    let mainloop = pw::main_loop::MainLoop::new(None).expect("Failed to create main loop");
+    let context = pw::context::Context::new(&mainloop).expect("Failed to create context");
+    let core = context.connect(None).expect("Failed to connect to core");
+    let registry = core.get_registry().expect("Failed to get registry");
+
+    let globals = Rc::new(RefCell::new(Vec::new()));
+    let globals_clone = globals.clone();
+    let _listener = registry
+        .add_listener_local()
+        .global(move |global| globals_clone.borrow_mut().push(global.to_owned()))
+        .register();
+
+    let errors = Rc::new(RefCell::new(Vec::new()));
+    let errors_clone = errors.clone();
+    let _core_listener = core
+        .add_listener_local()
+        .error(move |id, _seq, res, message| {
+            errors_clone
+                .borrow_mut()
+                .push(format!("{id}: {res} {message}"))
+        })
+        .register();
+
+    roundtrip(&mainloop, &core);
+
+    // The types with a proxy wrapper, and the one bound from the first announced global of each.
+    let mut proxies: Vec<(ObjectType, pw::proxy::Proxy)> = Vec::new();
+    for type_ in ObjectType::all_known() {
+        let globals = globals.borrow();
+        let Some(global) = globals.iter().find(|global| global.type_ == type_) else {
+            continue;
+        };
+
+        let proxy = match type_ {
+            ObjectType::Client => registry.bind_client(global).map(ProxyT::upcast),
+            ObjectType::Device => registry.bind_device(global).map(ProxyT::upcast),
+            ObjectType::Factory => registry.bind_factory(global).map(ProxyT::upcast),
+            ObjectType::Link => registry.bind_link(global).map(ProxyT::upcast),
+            ObjectType::Metadata => registry.bind_metadata(global).map(ProxyT::upcast),
+            ObjectType::Module => registry.bind_module(global).map(ProxyT::upcast),
+            ObjectType::Node => registry.bind_node(global).map(ProxyT::upcast),
+            ObjectType::Port => registry.bind_port(global).map(ProxyT::upcast),
+            _ => continue,
+        };
+        let proxy = proxy.unwrap_or_else(|e| panic!("Failed to bind {type_}: {e}"));
+        proxies.push((type_, proxy));
+    }
+
+    // Binding with a wrong type or version would make the server report an error.
+    roundtrip(&mainloop, &core);
+    assert!(errors.borrow().is_empty(), "{:?}", errors.borrow());
+
+    // A server always has at least these, whatever the session manager.
+    for type_ in [ObjectType::Client, ObjectType::Factory, ObjectType::Module] {
+        assert!(
+            proxies.iter().any(|(bound, _)| *bound == type_),
+            "no {type_} bound"
+        );
+    }
+    for (type_, proxy) in &proxies {
+        assert_eq!(proxy.get_type().0, *type_);
+    }
+}
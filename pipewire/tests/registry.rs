@@ -0,0 +1,64 @@
+// Copyright The pipewire-rs Contributors.
+// SPDX-License-Identifier: MIT
+
+//! Registering registry listeners, without a daemon, using a [`FakeRegistry`].
+
+use std::{cell::Cell, rc::Rc};
+
+use nix::errno::Errno;
+use pipewire as pw;
+use pw::registry::{GlobalObject, RegistryListener};
+use pw::spa::utils::dict::DictRef;
+
+mod common;
+use common::FakeRegistry;
+
+struct Nothing;
+
+impl RegistryListener for Nothing {
+    fn global(&self, _global: &GlobalObject<&DictRef>) {}
+}
+
+fn assert_not_supported<T>(res: Result<T, pw::Error>) {
+    match res {
+        Err(pw::Error::SpaError(err)) => assert_eq!(err.errno(), Errno::ENOTSUP),
+        Err(err) => panic!("unexpected error {err:?}"),
+        Ok(_) => panic!("registering a listener without add_listener succeeded"),
+    }
+}
+
+#[test]
+fn try_register() {
+    let fake = FakeRegistry::new();
+    let count: Rc<Cell<u32>> = Rc::default();
+    let count_clone = count.clone();
+    let _listener = fake
+        .registry()
+        .add_listener_local()
+        .global(move |_global| count_clone.set(count_clone.get() + 1))
+        .try_register()
+        .unwrap();
+
+    fake.global(1, c"PipeWire:Interface:Node", None);
+    assert_eq!(count.get(), 1);
+}
+
+#[test]
+fn try_register_without_add_listener() {
+    let fake = FakeRegistry::without_methods();
+    let registry = fake.registry();
+
+    assert_not_supported(
+        registry
+            .add_listener_local()
+            .global(|_global| {})
+            .try_register(),
+    );
+    assert_not_supported(
+        registry
+            .add_listener_local_with_user_data(0u32)
+            .global_remove(|_state, _registry, _id| {})
+            .try_register(),
+    );
+    assert_not_supported(registry.try_add_listener_local_with(Nothing));
+}
@@ -0,0 +1,78 @@
+// Copyright The pipewire-rs Contributors.
+// SPDX-License-Identifier: MIT
+
+//! Checks that the registry event paths do not allocate.
+//!
+//! This is a test binary of its own, so that its counting global allocator does not replace
+//! the allocator of the other tests. The events are emitted through the C trampolines
+//! using a [`FakeRegistry`], so no daemon is needed.
+
+use std::{
+    alloc::{GlobalAlloc, Layout, System},
+    cell::Cell,
+    rc::Rc,
+};
+
+mod common;
+use common::FakeRegistry;
+
+/// Counts the allocations of each thread, as tests run in parallel.
+struct CountingAllocator;
+
+thread_local! {
+    static ALLOCATIONS: Cell<usize> = const { Cell::new(0) };
+}
+
+unsafe impl GlobalAlloc for CountingAllocator {
+    unsafe fn alloc(&self, layout: Layout) -> *mut u8 {
+        let _ = ALLOCATIONS.try_with(|count| count.set(count.get() + 1));
+        System.alloc(layout)
+    }
+
+    unsafe fn dealloc(&self, ptr: *mut u8, layout: Layout) {
+        System.dealloc(ptr, layout)
+    }
+}
+
+#[global_allocator]
+static ALLOCATOR: CountingAllocator = CountingAllocator;
+
+fn allocations() -> usize {
+    ALLOCATIONS.with(Cell::get)
+}
+
+#[test]
+fn global_remove_does_not_allocate() {
+    let fake = FakeRegistry::new();
+    let removed: Rc<Cell<u32>> = Rc::default();
+    let removed_clone = removed.clone();
+    let _listener = fake
+        .registry()
+        .add_listener_local()
+        .global_remove(move |_id| removed_clone.set(removed_clone.get() + 1))
+        .register();
+
+    let before = allocations();
+    for id in 0..10_000 {
+        fake.global_remove(id);
+    }
+    assert_eq!(allocations(), before);
+    assert_eq!(removed.get(), 10_000);
+}
+
+#[test]
+fn stateful_global_remove_does_not_allocate() {
+    let fake = FakeRegistry::new();
+    let listener = fake
+        .registry()
+        .add_listener_local_with_user_data(0u32)
+        .global_remove(|removed, _registry, _id| *removed += 1)
+        .register();
+
+    let before = allocations();
+    for id in 0..10_000 {
+        fake.global_remove(id);
+    }
+    assert_eq!(allocations(), before);
+    assert_eq!(*listener.state(), 10_000);
+}